@@ -0,0 +1,449 @@
+use clap::Subcommand;
+use clap::ValueEnum;
+use finanzbuch_lib::accounting::accounting_year::AccountingYear;
+use finanzbuch_lib::accounting::category::CategoryKind;
+use finanzbuch_lib::accounting::csv_import;
+use finanzbuch_lib::accounting::csv_import::ColumnMapping;
+use finanzbuch_lib::accounting::ledger::LedgerTransaction;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::AccountingMonth;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Money;
+use std::path::PathBuf;
+
+use crate::parse_date;
+use crate::parse_locale;
+use crate::parse_money;
+
+#[derive(Debug, Subcommand)]
+pub enum AccountingCommand
+{
+    /// Print all months of one year
+    Show
+    {
+        year: u16
+    },
+
+    /// Overwrite one month. Values that are not given are set to 0.
+    /// Months with transactions are calculated from them and cannot be set
+    Set
+    {
+        year: u16,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        month: u8,
+        #[arg(long, default_value = "0")]
+        income: String,
+        #[arg(long, default_value = "0")]
+        expenses: String,
+        #[arg(long, default_value = "")]
+        note: String,
+    },
+
+    /// Fill the empty and planned months of a year with the values of the recurring items.
+    /// Months that were changed are kept
+    Prefill
+    {
+        year: u16
+    },
+
+    /// Decide if new years start with the values of the recurring items
+    AutoPrefill
+    {
+        #[arg(action = clap::ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Print the transactions of one month
+    Transactions
+    {
+        year: u16,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        month: u8,
+    },
+
+    /// Add a transaction to the month of its date. Income and expenses of the month are calculated from its transactions
+    AddTransaction
+    {
+        /// Like 2024-01-31
+        #[arg(value_parser = parse_date)]
+        date: FastDate,
+        /// Positive is income, negative is an expense
+        #[arg(allow_hyphen_values = true)]
+        amount: String,
+        #[arg(long, default_value = "")]
+        counterparty: String,
+        #[arg(long, default_value = "")]
+        description: String,
+        #[arg(long, default_value = "")]
+        category: String,
+    },
+
+    /// Remove a transaction, the number is shown by `transactions`
+    RemoveTransaction
+    {
+        year: u16,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        month: u8,
+        number: usize,
+    },
+
+    /// Sum up the transactions of a bank statement (semicolon separated CSV) into income and expenses per month.
+    /// Notes of existing months are kept
+    Import
+    {
+        csv: PathBuf,
+        #[arg(long, default_value = "Buchungstag")]
+        date_column: String,
+        #[arg(long, default_value = "Betrag")]
+        amount_column: String,
+        #[arg(long, default_value = "Verwendungszweck")]
+        purpose_column: String,
+        /// See https://docs.rs/chrono/latest/chrono/format/strftime
+        #[arg(long, default_value = "%d.%m.%Y")]
+        date_format: String,
+        /// How numbers are written in the file, like de-DE. The locale of the data file if not given
+        #[arg(long, value_parser = parse_locale)]
+        locale: Option<Locale>,
+        /// Only show what would be changed
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CategoryCommand
+{
+    /// Print all categories with their budgets
+    List,
+
+    /// Add a category. Levels are separated by `>`, like "Wohnen > Miete". The parent has to exist
+    Add
+    {
+        path: String,
+        #[arg(long, value_enum, default_value = "expense")]
+        kind: Kind,
+    },
+
+    /// Remove a category and its children. Transactions keep their category
+    Remove
+    {
+        path: String
+    },
+
+    /// Set the monthly budget of a category, without an amount the budget is removed
+    Budget
+    {
+        path: String, amount: Option<String>
+    },
+
+    /// Print spent and budget of every category for each month of a year
+    Report
+    {
+        year: u16
+    },
+}
+
+/// Same as `CategoryKind`, but with lowercase names for the command line
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Kind
+{
+    Income,
+    Expense,
+}
+impl From<Kind> for CategoryKind
+{
+    fn from(kind: Kind) -> Self
+    {
+        match kind {
+            Kind::Income => CategoryKind::Income,
+            Kind::Expense => CategoryKind::Expense,
+        }
+    }
+}
+
+/// Returns `true` if the data was changed and has to be written
+pub fn run(command: AccountingCommand, datafile: &mut DataFile) -> Result<bool, String>
+{
+    match command {
+        AccountingCommand::Show { year } => {
+            let Some(accounting_year) = datafile.accounting.history.get(&year) else {
+                return Err(format!("There is no data for {year}"));
+            };
+            _print_year(accounting_year);
+            return Ok(false);
+        }
+        AccountingCommand::Set {
+            year,
+            month,
+            income,
+            expenses,
+            note,
+        } => {
+            let has_transactions = datafile
+                .accounting
+                .history
+                .get(&year)
+                .is_some_and(|y| !y.months[month as usize - 1].transactions().is_empty());
+            if has_transactions {
+                return Err(format!("{month:0>2}.{year} has transactions, change or remove them instead"));
+            }
+
+            let income = parse_money(datafile.locale, &income)?;
+            let expenses = parse_money(datafile.locale, &expenses)?;
+            let new_month = AccountingMonth::new(month, income, expenses, note).map_err(|e| e.to_string())?;
+            datafile.accounting.add_or_get_year(year).insert_or_overwrite_month(new_month);
+            return Ok(true);
+        }
+        AccountingCommand::Prefill { year } => {
+            let filled = datafile.accounting.fill_planned_months(year);
+            println!("{filled} months of {year} are planned");
+            return Ok(true);
+        }
+        AccountingCommand::AutoPrefill { enabled } => {
+            datafile.accounting.prefill_from_recurring = enabled;
+            return Ok(true);
+        }
+        AccountingCommand::Transactions { year, month } => {
+            let Some(accounting_year) = datafile.accounting.history.get(&year) else {
+                return Err(format!("There is no data for {year}"));
+            };
+            let accounting_month = &accounting_year.months[month as usize - 1];
+
+            println!(
+                "{:>3}  {:<10} {:>12}  {:<24} {:<20} Description",
+                "Nr", "Date", "Amount", "Counterparty", "Category"
+            );
+            for (index, t) in accounting_month.transactions().iter().enumerate() {
+                println!(
+                    "{:>3}  {} {:>12.2}  {:<24} {:<20} {}",
+                    index + 1,
+                    t.date,
+                    t.amount(),
+                    t.counterparty,
+                    t.category,
+                    t.description
+                );
+            }
+            return Ok(false);
+        }
+        AccountingCommand::AddTransaction {
+            date,
+            amount,
+            counterparty,
+            description,
+            category,
+        } => {
+            let amount = parse_money(datafile.locale, &amount)?;
+            let transaction = LedgerTransaction::new(date.clone(), amount, counterparty, description, category);
+            let accounting_year = datafile.accounting.add_or_get_year(date.year());
+            accounting_year.add_transaction(transaction).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        AccountingCommand::RemoveTransaction { year, month, number } => {
+            let removed = datafile
+                .accounting
+                .history
+                .get_mut(&year)
+                .and_then(|y| y.months[month as usize - 1].remove_transaction(number.wrapping_sub(1)));
+            if removed.is_none() {
+                return Err(format!("{month:0>2}.{year} has no transaction {number}"));
+            }
+            return Ok(true);
+        }
+        AccountingCommand::Import {
+            csv,
+            date_column,
+            amount_column,
+            purpose_column,
+            date_format,
+            locale,
+            dry_run,
+        } => {
+            let mapping = ColumnMapping {
+                date: date_column,
+                amount: amount_column,
+                purpose: purpose_column,
+                date_format,
+                locale: locale.unwrap_or(datafile.locale),
+            };
+            let transactions = csv_import::read_transactions(&csv, &mapping).map_err(|e| e.to_string())?;
+            let months = csv_import::aggregate(&transactions);
+
+            let previews = match dry_run {
+                true => csv_import::preview(&datafile.accounting, &months),
+                false => csv_import::apply(&mut datafile.accounting, &months),
+            };
+            _print_import(&previews, transactions.len(), dry_run);
+            return Ok(!dry_run && !previews.is_empty());
+        }
+    }
+}
+
+/// Returns `true` if the data was changed and has to be written
+pub fn run_category(command: CategoryCommand, datafile: &mut DataFile) -> Result<bool, String>
+{
+    let locale = datafile.locale;
+    let accounting = &mut datafile.accounting;
+    match command {
+        CategoryCommand::List => {
+            for category in accounting.categories.iter() {
+                let budget = category.budget().map(|b| format!("{b:.2}")).unwrap_or_default();
+                println!("{:<40} {:<8} {:>12}", category.path(), category.kind.to_string(), budget);
+            }
+            return Ok(false);
+        }
+        CategoryCommand::Add { path, kind } => {
+            accounting.add_category(&path, kind.into()).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        CategoryCommand::Remove { path } => {
+            accounting.remove_category(&path).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        CategoryCommand::Budget { path, amount } => {
+            let amount = amount.map(|a| parse_money(locale, &a)).transpose()?;
+            accounting.set_category_budget(&path, amount).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        CategoryCommand::Report { year } => {
+            let Some(accounting_year) = accounting.history.get(&year) else {
+                return Err(format!("There is no data for {year}"));
+            };
+
+            // months that missed the budget are marked with a !
+            print!("{:<30} {:>10}", year, "Budget");
+            (1..=12).for_each(|m| print!(" {m:>10}"));
+            println!(" {:>11}", "Year");
+            for report in accounting_year.get_category_reports(&accounting.categories) {
+                let budget = report.budget.map(|b| format!("{b:.2}")).unwrap_or_default();
+                print!("{:<30} {:>10}", report.path, budget);
+                for (index, amount) in report.months.iter().enumerate() {
+                    let mark = if report.misses_budget(index as u8 + 1) { "!" } else { " " };
+                    print!(" {amount:>9.2}{mark}");
+                }
+                let mark = if report.misses_budget_year() { "!" } else { " " };
+                println!(" {:>10.2}{mark}", report.sum());
+            }
+
+            for breach in accounting.get_goal_breaches(year) {
+                let percentage = breach
+                    .percentage
+                    .map(|p| format!("{:.0}%", p * 100.0))
+                    .unwrap_or(String::from("no income"));
+                println!(
+                    "{:0>2}.{year} missed the goal with {percentage}, over budget: {}",
+                    breach.month,
+                    breach.over_budget.join(", ")
+                );
+            }
+            return Ok(false);
+        }
+    }
+}
+
+/// Sums of the year and medians of the months, months without data are not part of the medians
+pub fn print_summary(datafile: &DataFile, year: u16) -> Result<bool, String>
+{
+    let Some(accounting_year) = datafile.accounting.history.get(&year) else {
+        return Err(format!("There is no data for {year}"));
+    };
+
+    let median = |value: Option<Money>| match value {
+        Some(v) => format!("{v:>12}"),
+        None => format!("{:>12}", "-"),
+    };
+
+    println!("{:<12} {:>12} {:>12}", year, "Sum", "Median");
+    println!(
+        "{:<12} {:>12.2} {}",
+        "Income",
+        accounting_year.get_sum_income(),
+        median(accounting_year.get_median_income())
+    );
+    println!(
+        "{:<12} {:>12.2} {}",
+        "Expenses",
+        accounting_year.get_sum_expenses(),
+        median(accounting_year.get_median_expenses())
+    );
+    println!(
+        "{:<12} {:>12.2} {}",
+        "Difference",
+        accounting_year.get_difference(),
+        median(accounting_year.get_median_difference())
+    );
+    println!(
+        "{:<12} {:>11}% {}",
+        "Percentage",
+        accounting_year.get_percentage100(),
+        match accounting_year.get_median_percentage_100() {
+            Some(v) => format!("{v:>11}%"),
+            None => format!("{:>12}", "-"),
+        }
+    );
+    println!("{:<12} {:>11}%", "Goal", (datafile.accounting.goal * 100.0) as u16);
+
+    if let Some(summary) = datafile.accounting.get_year_summary(year) {
+        if let Some(goal) = summary.goal {
+            let margin_percentage = goal.margin_percentage.map(|p| format!(" ({:.1}%)", p * 100.0)).unwrap_or_default();
+            println!(
+                "\nGoal {} with a margin of {:.2}{margin_percentage}",
+                if goal.reached { "reached" } else { "missed" },
+                goal.margin
+            );
+        }
+        println!(
+            "Months reached: {}, missed: {}, longest streak: {}",
+            summary.months_reached, summary.months_missed, summary.longest_streak
+        );
+    }
+    let streaks = datafile.accounting.get_goal_streaks();
+    println!("Current streak over all years: {} months", streaks.current);
+    return Ok(false);
+}
+
+// ================================================== Private ================================================== //
+
+fn _print_year(year: &AccountingYear)
+{
+    println!("{:<8} {:>12} {:>12} {:>12}  Note", year.year_nr, "Income", "Expenses", "Difference");
+    for month in year.months.iter() {
+        println!(
+            "{:0>2} {:<5} {:>12.2} {:>12.2} {:>12.2}  {}",
+            month.month_nr(),
+            if month.is_planned() { "plan" } else { "" },
+            month.income(),
+            month.expenses(),
+            month.difference(),
+            month.note()
+        );
+    }
+}
+
+fn _print_import(previews: &[csv_import::ImportPreview], transaction_count: usize, dry_run: bool)
+{
+    println!("{:<8} {:>12} {:>12}  Overwrites", "Month", "Income", "Expenses");
+    for preview in previews {
+        let overwritten = match &preview.overwritten {
+            Some(old) => format!("{:.2} / {:.2}", old.income(), old.expenses()),
+            None => String::new(),
+        };
+        println!(
+            "{:0>2}.{:<5} {:>12.2} {:>12.2}  {}",
+            preview.new.month_nr(),
+            preview.year,
+            preview.new.income(),
+            preview.new.expenses(),
+            overwritten
+        );
+    }
+
+    match dry_run {
+        true => println!(
+            "{transaction_count} transactions in {} months, nothing was changed (--dry-run)",
+            previews.len()
+        ),
+        false => println!("{transaction_count} transactions imported into {} months", previews.len()),
+    }
+}
@@ -0,0 +1,152 @@
+use clap::Subcommand;
+use finanzbuch_lib::currency::Currency;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::DepotEntry;
+use std::path::PathBuf;
+
+use crate::parse_amount;
+use crate::parse_currency;
+use crate::parse_locale;
+use crate::parse_year_month;
+
+#[derive(Debug, Subcommand)]
+pub enum CurrencyCommand
+{
+    /// Print the currencies of accounting, the depot overview and all depot entries
+    Show,
+
+    /// Set the currency all exchange rates are quoted in. Only possible while there are no rates
+    SetBase
+    {
+        #[arg(value_parser = parse_currency)]
+        currency: Currency,
+    },
+
+    /// Set the currency of the accounting history, the values themselves are not converted
+    SetAccounting
+    {
+        #[arg(value_parser = parse_currency)]
+        currency: Currency,
+    },
+
+    /// Set the currency the depot overview is shown in
+    SetOverview
+    {
+        #[arg(value_parser = parse_currency)]
+        currency: Currency,
+    },
+
+    /// Print all exchange rates, or the ones of one currency
+    Rates
+    {
+        #[arg(value_parser = parse_currency)]
+        currency: Option<Currency>,
+    },
+
+    /// Set the value of one unit of a currency in the base currency, valid from this month on
+    SetRate
+    {
+        #[arg(value_parser = parse_currency)]
+        currency: Currency,
+        /// Like 2024-01
+        #[arg(value_parser = parse_year_month)]
+        month: (u16, u8),
+        rate: String,
+    },
+
+    /// Remove the rate of a currency in one month
+    RemoveRate
+    {
+        #[arg(value_parser = parse_currency)]
+        currency: Currency,
+        /// Like 2024-01
+        #[arg(value_parser = parse_year_month)]
+        month: (u16, u8),
+    },
+
+    /// Set all rates of a semicolon separated file with the columns Währung, Jahr, Monat and Kurs
+    Import
+    {
+        csv: PathBuf,
+        /// How numbers are written in the file, like de-DE. The locale of the data file if not given
+        #[arg(long, value_parser = parse_locale)]
+        locale: Option<Locale>,
+    },
+}
+
+/// Returns `true` if the data was changed and has to be written
+pub fn run(command: CurrencyCommand, datafile: &mut DataFile) -> Result<bool, String>
+{
+    match command {
+        CurrencyCommand::Show => {
+            println!("{:<30} {}", "Base of exchange rates", datafile.exchange_rates.base);
+            println!("{:<30} {}", "Accounting", datafile.accounting.currency);
+            println!("{:<30} {}", "Depot overview", datafile.overview_currency());
+
+            let mut entries: Vec<&DepotEntry> = datafile.investing.depot.entries.values().collect();
+            entries.sort_by(|a, b| a.name().cmp(b.name()));
+            if !entries.is_empty() {
+                println!();
+            }
+            for entry in entries {
+                println!("{:<30} {}", entry.name(), entry.currency);
+            }
+            return Ok(false);
+        }
+        CurrencyCommand::SetBase { currency } => {
+            if !datafile.exchange_rates.rates.is_empty() && currency != datafile.exchange_rates.base {
+                return Err(format!(
+                    "All exchange rates are quoted in {}, remove them before changing the base currency",
+                    datafile.exchange_rates.base
+                ));
+            }
+            datafile.exchange_rates.base = currency;
+            return Ok(true);
+        }
+        CurrencyCommand::SetAccounting { currency } => {
+            datafile.accounting.currency = currency;
+            return Ok(true);
+        }
+        CurrencyCommand::SetOverview { currency } => {
+            datafile.investing.overview_currency = Some(currency);
+            return Ok(true);
+        }
+        CurrencyCommand::Rates { currency } => {
+            let base = &datafile.exchange_rates.base;
+            for rate in datafile
+                .exchange_rates
+                .rates
+                .iter()
+                .filter(|r| currency.as_ref().map_or(true, |c| *c == r.currency))
+            {
+                println!("{} {}-{:0>2} {:>14} {}", rate.currency, rate.year, rate.month, rate.rate, base);
+            }
+            return Ok(false);
+        }
+        CurrencyCommand::SetRate {
+            currency,
+            month: (year, month),
+            rate,
+        } => {
+            let rate = parse_amount(datafile.locale, &rate)?;
+            datafile.exchange_rates.set_rate(currency, year, month, rate).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        CurrencyCommand::RemoveRate {
+            currency,
+            month: (year, month),
+        } => {
+            if datafile.exchange_rates.remove_rate(&currency, year, month).is_none() {
+                return Err(format!("There is no rate for {currency} in {year}-{month:0>2}"));
+            }
+            return Ok(true);
+        }
+        CurrencyCommand::Import { csv, locale } => {
+            let locale = locale.unwrap_or(datafile.locale);
+            let count = datafile.exchange_rates.import_csv(&csv, locale).map_err(|e| e.to_string())?;
+            println!("{count} exchange rates imported");
+            return Ok(count > 0);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use clap::Args;
+use clap::ValueEnum;
+use finanzbuch_lib::export::Export;
+use finanzbuch_lib::export::ExportFilter;
+use finanzbuch_lib::DataFile;
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct ExportArgs
+{
+    table: Table,
+    #[arg(long, value_enum, default_value = "csv")]
+    format: Format,
+    /// First year that is exported
+    #[arg(long)]
+    from: Option<u16>,
+    /// Last year that is exported
+    #[arg(long)]
+    to: Option<u16>,
+    /// Only export this depot entry
+    #[arg(long)]
+    entry: Option<String>,
+    /// Write into this file instead of printing
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum Table
+{
+    Accounting,
+    Depot,
+    /// Only possible as JSON
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum Format
+{
+    Csv,
+    Json,
+}
+
+/// Never changes the data
+pub fn run(args: ExportArgs, datafile: &DataFile) -> Result<bool, String>
+{
+    let filter = ExportFilter {
+        first_year: args.from,
+        last_year: args.to,
+        entry: args.entry,
+    };
+    let mut export = Export::new(datafile, &filter);
+
+    let content = match (args.format, args.table) {
+        (Format::Csv, Table::Accounting) => export.accounting_csv(),
+        (Format::Csv, Table::Depot) => export.investing_csv(),
+        (Format::Csv, Table::All) => return Err(String::from("A CSV file can only contain one table, use accounting or depot")),
+        (Format::Json, table) => {
+            // the other table is left empty, so that the structure is always the same
+            match table {
+                Table::Accounting => export.investing.clear(),
+                Table::Depot => export.accounting.clear(),
+                Table::All => (),
+            }
+            export.to_json()
+        }
+    }
+    .map_err(|e| e.to_string())?;
+
+    match args.output {
+        Some(path) => std::fs::write(&path, content).map_err(|e| format!("Could not write {:?}: {e}", path))?,
+        None => print!("{content}"),
+    }
+    return Ok(false);
+}
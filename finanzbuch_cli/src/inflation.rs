@@ -0,0 +1,108 @@
+use clap::Subcommand;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::CurrentDate;
+use finanzbuch_lib::DataFile;
+use std::path::PathBuf;
+
+use crate::parse_amount;
+use crate::parse_locale;
+use crate::parse_year_month;
+
+#[derive(Debug, Subcommand)]
+pub enum InflationCommand
+{
+    /// Print all values of the consumer price index
+    Show,
+
+    /// Set the consumer price index of one month, valid until the next value
+    Set
+    {
+        /// Like 2024-01
+        #[arg(value_parser = parse_year_month)]
+        month: (u16, u8),
+        index: String,
+    },
+
+    /// Remove the value of one month
+    Remove
+    {
+        /// Like 2024-01
+        #[arg(value_parser = parse_year_month)]
+        month: (u16, u8),
+    },
+
+    /// Set all values of a semicolon separated file with the columns Jahr, Monat and Index
+    Import
+    {
+        csv: PathBuf,
+        /// How numbers are written in the file, like de-DE. The locale of the data file if not given
+        #[arg(long, value_parser = parse_locale)]
+        locale: Option<Locale>,
+    },
+
+    /// Print income, expenses and the value of the depot of each month in prices of the base month
+    Real
+    {
+        /// Like 2024-01, January of the current year if not given
+        #[arg(long, value_parser = parse_year_month)]
+        from: Option<(u16, u8)>,
+        /// Like 2024-12, the current month if not given
+        #[arg(long, value_parser = parse_year_month)]
+        to: Option<(u16, u8)>,
+        /// Month whose prices are used, like 2024-12. The last month if not given
+        #[arg(long, value_parser = parse_year_month)]
+        base: Option<(u16, u8)>,
+    },
+}
+
+/// Returns `true` if the data was changed and has to be written
+pub fn run(command: InflationCommand, datafile: &mut DataFile) -> Result<bool, String>
+{
+    match command {
+        InflationCommand::Show => {
+            for value in datafile.price_index.values.iter() {
+                println!("{}-{:0>2} {:>10}", value.year, value.month, value.index);
+            }
+            return Ok(false);
+        }
+        InflationCommand::Set { month: (year, month), index } => {
+            let index = parse_amount(datafile.locale, &index)?;
+            datafile.price_index.set_value(year, month, index).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        InflationCommand::Remove { month: (year, month) } => {
+            if datafile.price_index.remove_value(year, month).is_none() {
+                return Err(format!("There is no price index for {year}-{month:0>2}"));
+            }
+            return Ok(true);
+        }
+        InflationCommand::Import { csv, locale } => {
+            let locale = locale.unwrap_or(datafile.locale);
+            let count = datafile.price_index.import_csv(&csv, locale).map_err(|e| e.to_string())?;
+            println!("{count} values of the price index imported");
+            return Ok(count > 0);
+        }
+        InflationCommand::Real { from, to, base } => {
+            let from = from.unwrap_or((CurrentDate::current_year(), 1));
+            let to = to.unwrap_or((CurrentDate::current_year(), CurrentDate::current_month()));
+            let base = base.unwrap_or(to);
+            let accounting = datafile.real_accounting_months(from, to, base).map_err(|e| e.to_string())?;
+            let depot = datafile.real_depot_values(from, to, base).map_err(|e| e.to_string())?;
+
+            println!("In prices of {}-{:0>2}", base.0, base.1);
+            println!("{:<7} {:>14} {:>14} {:>14} {:>14}", "Month", "Income", "Expenses", "Difference", "Depot");
+            for (a, d) in accounting.iter().zip(depot.iter()) {
+                println!(
+                    "{}-{:0>2} {:>14.2} {:>14.2} {:>14.2} {:>14.2}",
+                    a.year,
+                    a.month,
+                    a.income,
+                    a.expenses,
+                    a.difference(),
+                    d.value
+                );
+            }
+            return Ok(false);
+        }
+    }
+}
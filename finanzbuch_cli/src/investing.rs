@@ -0,0 +1,767 @@
+use clap::Subcommand;
+use clap::ValueEnum;
+use finanzbuch_lib::currency::Currency;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::cost_basis::CostBasis;
+use finanzbuch_lib::investing::cost_basis::CostBasisMethod;
+use finanzbuch_lib::investing::csv_import;
+use finanzbuch_lib::investing::csv_import::BrokerProfile;
+use finanzbuch_lib::investing::depot_transaction::DepotTransaction;
+use finanzbuch_lib::investing::depot_transaction::DepotTransactionKind;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::inv_year::InvestmentYear;
+use finanzbuch_lib::investing::performance::Performance;
+use finanzbuch_lib::investing::running_costs::ExpenseRatio;
+use finanzbuch_lib::investing::running_costs::RunningCosts;
+use finanzbuch_lib::investing::savings_plan_section::SavingsPlanSection;
+use finanzbuch_lib::investing::SavingsPlanInterval;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::CurrentDate;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::DepotEntry;
+use finanzbuch_lib::Money;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::parse_amount;
+use crate::parse_currency;
+use crate::parse_date;
+use crate::parse_locale;
+use crate::parse_money;
+use crate::parse_quantity;
+use crate::parse_year_month;
+
+#[derive(Debug, Subcommand)]
+pub enum DepotCommand
+{
+    /// Print all entries of the depot
+    List,
+
+    /// Add a new entry with the current year
+    Add
+    {
+        name: String,
+        /// Stock, Fund, Etf, Bond, Option, Commoditiy or Crypto
+        #[arg(value_parser = InvestmentVariant::from_str)]
+        variant: InvestmentVariant,
+        /// Used to find the entry when importing broker exports
+        #[arg(long)]
+        isin: Option<String>,
+        /// Currency of prices and transactions, like USD
+        #[arg(long, value_parser = parse_currency, default_value = "EUR")]
+        currency: Currency,
+    },
+
+    /// Set the ISIN of an entry, an empty ISIN removes it
+    SetIsin
+    {
+        name: String, isin: String
+    },
+
+    /// Set the currency of prices and transactions of an entry, the values themselves are not converted
+    SetCurrency
+    {
+        name: String,
+        #[arg(value_parser = parse_currency)]
+        currency: Currency,
+    },
+
+    /// Print all months of one year of an entry
+    Show
+    {
+        name: String, year: u16
+    },
+
+    /// Change the values of one month. Values that are not given stay as they are.
+    /// If the year does not exist in this entry yet, it is added.
+    /// From the first transaction on, amount and additional transactions are calculated and cannot be set
+    SetMonth
+    {
+        name: String,
+        year: u16,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        month: u8,
+        /// Number of shares
+        #[arg(long)]
+        amount: Option<String>,
+        #[arg(long)]
+        price_per_unit: Option<String>,
+        /// Eg. dividends, can be negative
+        #[arg(long, allow_hyphen_values = true)]
+        additional_transactions: Option<String>,
+    },
+
+    /// Print the transactions of an entry
+    Transactions
+    {
+        name: String
+    },
+
+    /// Add a transaction, the months from its month on are calculated from the transactions of the entry.
+    /// The entry holds no units before its first transaction
+    AddTransaction
+    {
+        name: String,
+        /// Like 2024-01-31
+        #[arg(value_parser = parse_date)]
+        date: FastDate,
+        /// Buy, Sell, Dividend, Fee, Tax or Split
+        #[arg(value_parser = DepotTransactionKind::from_str)]
+        kind: DepotTransactionKind,
+        /// Traded units, units the dividend was paid for or units added by a split (negative for a reverse split)
+        #[arg(long, allow_hyphen_values = true, default_value = "0")]
+        units: String,
+        /// Price of one unit, or the dividend per unit
+        #[arg(long, default_value = "0")]
+        price: String,
+        /// Fees and taxes, the whole amount for Fee and Tax
+        #[arg(long, default_value = "0")]
+        fees: String,
+    },
+
+    /// Remove a transaction, the number is shown by `transactions`
+    RemoveTransaction
+    {
+        name: String, number: usize
+    },
+
+    /// Print what the held units of each entry cost, what they are worth and the realized gains.
+    /// The sum is shown in the overview currency
+    Gains
+    {
+        #[arg(long, value_enum, default_value = "fifo")]
+        method: Method,
+        /// Up to the end of this month, like 2024-12. The current month if not given
+        #[arg(long, value_parser = parse_year_month)]
+        month: Option<(u16, u8)>,
+    },
+
+    /// Print the changes of the total expense ratio (TER) of an entry
+    ExpenseRatios
+    {
+        name: String
+    },
+
+    /// Set the total expense ratio (TER) of an entry in percent per year, valid from this month on until the next change
+    SetExpenseRatio
+    {
+        name: String,
+        /// Like 2024-01
+        #[arg(value_parser = parse_year_month)]
+        month: (u16, u8),
+        /// Like 0.2 for 0.2%
+        percent: String,
+    },
+
+    /// Remove the change of the total expense ratio in one month
+    RemoveExpenseRatio
+    {
+        name: String,
+        /// Like 2024-01
+        #[arg(value_parser = parse_year_month)]
+        month: (u16, u8),
+    },
+
+    /// Print the estimated running costs (TER) of each entry and the whole depot, and what they would be worth without them.
+    /// The depot is shown in the overview currency
+    Costs
+    {
+        /// First month, like 2024-01. January of the oldest year if not given
+        #[arg(long, value_parser = parse_year_month)]
+        from: Option<(u16, u8)>,
+        /// Last month, like 2024-12. The current month if not given
+        #[arg(long, value_parser = parse_year_month)]
+        to: Option<(u16, u8)>,
+    },
+
+    /// Print the time-weighted and money-weighted (XIRR) returns of each entry, each variant and the whole depot.
+    /// Variants and the depot are shown in the overview currency
+    Performance
+    {
+        /// First month, like 2024-01. January of the oldest year if not given
+        #[arg(long, value_parser = parse_year_month)]
+        from: Option<(u16, u8)>,
+        /// Last month, like 2024-12. The current month if not given
+        #[arg(long, value_parser = parse_year_month)]
+        to: Option<(u16, u8)>,
+    },
+
+    /// Fill the months of the depot entries from the export of a broker (semicolon separated CSV).
+    /// Rows are matched to entries by ISIN first and by name second
+    Import
+    {
+        csv: PathBuf,
+        #[arg(long, value_enum, default_value = "generic")]
+        broker: Broker,
+        /// How numbers are written in the file, like de-DE. The locale of the data file if not given
+        #[arg(long, value_parser = parse_locale)]
+        locale: Option<Locale>,
+        /// Only show what would be changed
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SavingsPlanCommand
+{
+    /// Print all sections of an entry
+    List
+    {
+        name: String
+    },
+
+    /// Add a section, it must not overlap with existing ones
+    Add
+    {
+        name: String,
+        /// First day, like 2024-01-31
+        #[arg(long, value_parser = parse_date)]
+        start: FastDate,
+        /// Last day, like 2024-12-31
+        #[arg(long, value_parser = parse_date)]
+        end: FastDate,
+        /// Can be negative
+        #[arg(long, allow_hyphen_values = true)]
+        amount: String,
+        #[arg(long, value_enum, default_value = "monthly")]
+        interval: Interval,
+    },
+
+    /// Remove a section
+    Remove
+    {
+        name: String,
+        /// As printed by `list`, starting at 1
+        number: usize,
+    },
+
+    /// Change a section, values that are not given stay the same. It must not overlap with the other ones
+    Update
+    {
+        name: String,
+        /// As printed by `list`, starting at 1
+        number: usize,
+        /// First day, like 2024-01-31
+        #[arg(long, value_parser = parse_date)]
+        start: Option<FastDate>,
+        /// Last day, like 2024-12-31
+        #[arg(long, value_parser = parse_date)]
+        end: Option<FastDate>,
+        /// Can be negative
+        #[arg(long, allow_hyphen_values = true)]
+        amount: Option<String>,
+        #[arg(long, value_enum)]
+        interval: Option<Interval>,
+    },
+}
+
+/// Same as `SavingsPlanInterval`, but with lowercase names for the command line
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Interval
+{
+    Monthly,
+    Annually,
+}
+impl From<Interval> for SavingsPlanInterval
+{
+    fn from(interval: Interval) -> Self
+    {
+        match interval {
+            Interval::Monthly => SavingsPlanInterval::Monthly,
+            Interval::Annually => SavingsPlanInterval::Annually,
+        }
+    }
+}
+
+/// Same as `CostBasisMethod`, but with lowercase names for the command line
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Method
+{
+    /// The units that were bought first are sold first
+    Fifo,
+    /// All units cost the average price of all purchases
+    Average,
+}
+impl From<Method> for CostBasisMethod
+{
+    fn from(method: Method) -> Self
+    {
+        match method {
+            Method::Fifo => CostBasisMethod::Fifo,
+            Method::Average => CostBasisMethod::Average,
+        }
+    }
+}
+
+/// Column profiles of `csv_import::BrokerProfile`
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Broker
+{
+    /// Columns Datum, ISIN, Name, Stück, Kurs and Betrag
+    Generic,
+    ScalableCapital,
+    Comdirect,
+}
+impl From<Broker> for BrokerProfile
+{
+    fn from(broker: Broker) -> Self
+    {
+        match broker {
+            Broker::Generic => BrokerProfile::default(),
+            Broker::ScalableCapital => BrokerProfile::scalable_capital(),
+            Broker::Comdirect => BrokerProfile::comdirect(),
+        }
+    }
+}
+
+/// Returns `true` if the data was changed and has to be written
+pub fn run_depot(command: DepotCommand, datafile: &mut DataFile) -> Result<bool, String>
+{
+    match command {
+        DepotCommand::List => {
+            let mut entries: Vec<&DepotEntry> = datafile.investing.depot.entries.values().collect();
+            entries.sort_by(|a, b| a.name().cmp(b.name()));
+
+            for entry in entries {
+                let years: Vec<String> = entry.history.keys().map(|y| y.to_string()).collect();
+                println!(
+                    "{:<30} {:<12} {:<4} {}",
+                    entry.name(),
+                    entry.variant.to_string(),
+                    entry.currency,
+                    years.join(", ")
+                );
+            }
+            return Ok(false);
+        }
+        DepotCommand::Add {
+            name,
+            variant,
+            isin,
+            currency,
+        } => {
+            if name.trim().is_empty() {
+                return Err(String::from("The name must not be empty"));
+            }
+            if datafile.investing.depot.get_entry_from_str(&name).is_some() {
+                return Err(format!("There already is an entry called {name}"));
+            }
+
+            let mut entry = DepotEntry::default_with_current_year(&name, variant);
+            entry.isin = isin.filter(|i| !i.trim().is_empty()).map(|i| String::from(i.trim()));
+            entry.currency = currency;
+            datafile.investing.depot.add_entry(&name, entry);
+            return Ok(true);
+        }
+        DepotCommand::SetIsin { name, isin } => {
+            let entry = _get_entry_mut(datafile, &name)?;
+            entry.isin = Some(String::from(isin.trim())).filter(|i| !i.is_empty());
+            return Ok(true);
+        }
+        DepotCommand::SetCurrency { name, currency } => {
+            _get_entry_mut(datafile, &name)?.currency = currency;
+            return Ok(true);
+        }
+        DepotCommand::Show { name, year } => {
+            let entry = _get_entry(datafile, &name)?;
+            let Some(investment_year) = entry.history.get(&year) else {
+                return Err(format!("{name} has no data for {year}"));
+            };
+
+            println!("{:<8} {:>14} {:>14} {:>14}", year, "Amount", "Price per unit", "Additional");
+            for month in investment_year.months.iter() {
+                println!(
+                    "{:0>2}       {:>14} {:>14} {:>14.2}",
+                    month.month_nr(),
+                    month.amount(),
+                    month.price_per_unit(),
+                    month.additional_transactions()
+                );
+            }
+            return Ok(false);
+        }
+        DepotCommand::SetMonth {
+            name,
+            year,
+            month,
+            amount,
+            price_per_unit,
+            additional_transactions,
+        } => {
+            let locale = datafile.locale;
+            let amount = amount.map(|a| parse_quantity(locale, &a)).transpose()?;
+            let price_per_unit = price_per_unit.map(|p| parse_quantity(locale, &p)).transpose()?;
+            let additional_transactions = additional_transactions.map(|a| parse_money(locale, &a)).transpose()?;
+            let entry = _get_entry_mut(datafile, &name)?;
+            let calculated = entry
+                .transactions()
+                .first()
+                .is_some_and(|t| (t.date.year(), t.date.month()) <= (year, month));
+            if calculated && (amount.is_some() || additional_transactions.is_some()) {
+                return Err(format!(
+                    "The amount and additional transactions of {name} are calculated from its transactions, use add-transaction instead"
+                ));
+            }
+            let investment_year = entry.history.entry(year).or_insert_with(|| InvestmentYear::default(year));
+            let investment_month = &mut investment_year.months[month as usize - 1];
+
+            if let Some(amount) = amount {
+                investment_month.set_amount(amount);
+            }
+            if let Some(price_per_unit) = price_per_unit {
+                investment_month.set_price_per_unit(price_per_unit);
+            }
+            if let Some(additional_transactions) = additional_transactions {
+                investment_month.set_additional_transactions(additional_transactions);
+            }
+            return Ok(true);
+        }
+        DepotCommand::Transactions { name } => {
+            let entry = _get_entry(datafile, &name)?;
+            println!(
+                "{:>3}  {:<10} {:<8} {:>14} {:>14} {:>10} {:>12}",
+                "Nr", "Date", "Kind", "Units", "Price", "Fees", "Cash flow"
+            );
+            for (index, t) in entry.transactions().iter().enumerate() {
+                println!(
+                    "{:>3}  {} {:<8} {:>14} {:>14} {:>10.2} {:>12.2}",
+                    index + 1,
+                    t.date,
+                    t.kind.to_string(),
+                    t.units(),
+                    t.price(),
+                    t.fees(),
+                    t.cash_flow()
+                );
+            }
+            return Ok(false);
+        }
+        DepotCommand::AddTransaction {
+            name,
+            date,
+            kind,
+            units,
+            price,
+            fees,
+        } => {
+            let locale = datafile.locale;
+            let (units, price, fees) = (
+                parse_quantity(locale, &units)?,
+                parse_quantity(locale, &price)?,
+                parse_money(locale, &fees)?,
+            );
+            let transaction = DepotTransaction::new(date, kind, units, price, fees).map_err(|e| e.to_string())?;
+            _get_entry_mut(datafile, &name)?.add_transaction(transaction).map_err(|e| e.to_string())?;
+            datafile.investing.depot.ensure_uniform_histories();
+            return Ok(true);
+        }
+        DepotCommand::RemoveTransaction { name, number } => {
+            let entry = _get_entry_mut(datafile, &name)?;
+            if number == 0 || number > entry.transactions().len() {
+                return Err(format!("{name} has no transaction number {number}"));
+            }
+            entry.remove_transaction(number - 1).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        DepotCommand::Gains { method, month } => {
+            let (year, month) = month.unwrap_or((CurrentDate::current_year(), CurrentDate::current_month()));
+            let method: CostBasisMethod = method.into();
+            let mut entries: Vec<&DepotEntry> = datafile.investing.depot.entries.values().collect();
+            entries.sort_by(|a, b| a.name().cmp(b.name()));
+
+            println!(
+                "{:<30} {:<4} {:>14} {:>14} {:>14} {:>14} {:>9}",
+                format!("{method} {year}-{month:0>2}"),
+                "",
+                "Invested",
+                "Value",
+                "Unrealized",
+                "Realized",
+                "Gain %"
+            );
+            for entry in entries {
+                let cost_basis = entry.cost_basis(method, year, month);
+                _print_cost_basis(entry.name(), &entry.currency, &cost_basis);
+            }
+            let sum = datafile.depot_cost_basis(method, year, month).map_err(|e| e.to_string())?;
+            _print_cost_basis("Sum", datafile.overview_currency(), &sum);
+            return Ok(false);
+        }
+        DepotCommand::ExpenseRatios { name } => {
+            for r in _get_entry(datafile, &name)?.expense_ratios() {
+                println!("{}-{:0>2} {:>8} %", r.year, r.month, r.percent);
+            }
+            return Ok(false);
+        }
+        DepotCommand::SetExpenseRatio {
+            name,
+            month: (year, month),
+            percent,
+        } => {
+            let percent = parse_amount(datafile.locale, &percent)?;
+            let expense_ratio = ExpenseRatio::new(year, month, percent).map_err(|e| e.to_string())?;
+            _get_entry_mut(datafile, &name)?.set_expense_ratio(expense_ratio);
+            return Ok(true);
+        }
+        DepotCommand::RemoveExpenseRatio { name, month: (year, month) } => {
+            if _get_entry_mut(datafile, &name)?.remove_expense_ratio(year, month).is_none() {
+                return Err(format!("{name} has no change of the expense ratio in {year}-{month:0>2}"));
+            }
+            return Ok(true);
+        }
+        DepotCommand::Costs { from, to } => {
+            let depot = &datafile.investing.depot;
+            let from = from.unwrap_or((depot.get_oldest_year().unwrap_or(CurrentDate::current_year()), 1));
+            let to = to.unwrap_or((CurrentDate::current_year(), CurrentDate::current_month()));
+            let mut entries: Vec<&DepotEntry> = depot.entries.values().collect();
+            entries.sort_by(|a, b| a.name().cmp(b.name()));
+
+            println!(
+                "{:<30} {:<4} {:>8} {:>12} {:>14} {:>14} {:>12}",
+                format!("{}-{:0>2} - {}-{:0>2}", from.0, from.1, to.0, to.1),
+                "",
+                "TER %",
+                "Costs",
+                "Value",
+                "Without costs",
+                "Difference"
+            );
+            for entry in entries {
+                let costs = entry.running_costs(from, to).map_err(|e| e.to_string())?;
+                let ter = entry.expense_ratio(to.0, to.1).map(|r| r.percent).unwrap_or_default();
+                _print_running_costs(entry.name(), &entry.currency, Some(ter), &costs);
+            }
+            let costs = datafile.depot_running_costs(from, to).map_err(|e| e.to_string())?;
+            _print_running_costs("Depot", datafile.overview_currency(), None, &costs);
+            return Ok(false);
+        }
+        DepotCommand::Performance { from, to } => {
+            let depot = &datafile.investing.depot;
+            let from = from.unwrap_or((depot.get_oldest_year().unwrap_or(CurrentDate::current_year()), 1));
+            let to = to.unwrap_or((CurrentDate::current_year(), CurrentDate::current_month()));
+            let mut entries: Vec<&DepotEntry> = depot.entries.values().collect();
+            entries.sort_by(|a, b| a.name().cmp(b.name()));
+
+            println!(
+                "{:<30} {:<4} {:>14} {:>10} {:>10} {:>10}",
+                format!("{}-{:0>2} - {}-{:0>2}", from.0, from.1, to.0, to.1),
+                "",
+                "Gain",
+                "TWR %",
+                "TWR p.a.",
+                "XIRR %"
+            );
+            for entry in entries {
+                let performance = entry.performance(from, to).map_err(|e| e.to_string())?;
+                _print_performance(entry.name(), &entry.currency, &performance);
+            }
+
+            println!();
+            for variant in InvestmentVariant::into_iter() {
+                if !depot.entries.values().any(|e| e.variant == variant) {
+                    continue;
+                }
+                let performance = datafile.depot_performance(Some(&variant), from, to).map_err(|e| e.to_string())?;
+                _print_performance(&variant.to_string(), datafile.overview_currency(), &performance);
+            }
+            let performance = datafile.depot_performance(None, from, to).map_err(|e| e.to_string())?;
+            _print_performance("Depot", datafile.overview_currency(), &performance);
+            return Ok(false);
+        }
+        DepotCommand::Import {
+            csv,
+            broker,
+            locale,
+            dry_run,
+        } => {
+            let mut profile: BrokerProfile = broker.into();
+            profile.locale = locale.unwrap_or(datafile.locale);
+
+            // a dry run imports into a copy
+            let mut depot = datafile.investing.depot.clone();
+            let report = csv_import::import(&mut depot, &csv, &profile).map_err(|e| e.to_string())?;
+            _print_import(&report, dry_run);
+
+            if dry_run || report.changed.is_empty() {
+                return Ok(false);
+            }
+            datafile.investing.depot = depot;
+            return Ok(true);
+        }
+    }
+}
+
+/// Returns `true` if the data was changed and has to be written
+pub fn run_savings_plan(command: SavingsPlanCommand, datafile: &mut DataFile) -> Result<bool, String>
+{
+    match command {
+        SavingsPlanCommand::List { name } => {
+            let entry = _get_entry(datafile, &name)?;
+            for (i, section) in entry.savings_plan().iter().enumerate() {
+                println!(
+                    "{:>3}. {} - {} {:>12.2} {}",
+                    i + 1,
+                    section.start,
+                    section.end,
+                    section.amount,
+                    section.interval
+                );
+            }
+            return Ok(false);
+        }
+        SavingsPlanCommand::Add {
+            name,
+            start,
+            end,
+            amount,
+            interval,
+        } => {
+            let amount = parse_money(datafile.locale, &amount)?;
+            let entry = _get_entry_mut(datafile, &name)?;
+            let section = SavingsPlanSection {
+                start,
+                end,
+                amount,
+                interval: interval.into(),
+            };
+            entry.add_savings_plan_section(section).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        SavingsPlanCommand::Remove { name, number } => {
+            let entry = _get_entry_mut(datafile, &name)?;
+            entry.remove_savings_plan_section(_section_index(number)?).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        SavingsPlanCommand::Update {
+            name,
+            number,
+            start,
+            end,
+            amount,
+            interval,
+        } => {
+            let amount = amount.map(|a| parse_money(datafile.locale, &a)).transpose()?;
+            let entry = _get_entry_mut(datafile, &name)?;
+            let index = _section_index(number)?;
+            let Some(old) = entry.savings_plan().get(index) else {
+                return Err(format!("There is no savings plan section number {number}"));
+            };
+            let mut section = old.clone();
+            section.start = start.unwrap_or(section.start);
+            section.end = end.unwrap_or(section.end);
+            section.amount = amount.unwrap_or(section.amount);
+            section.interval = interval.map(SavingsPlanInterval::from).unwrap_or(section.interval);
+            entry.update_savings_plan_section(index, section).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+    }
+}
+
+// ================================================== Private ================================================== //
+
+/// The numbers printed by `savings-plan list` start at 1
+fn _section_index(number: usize) -> Result<usize, String>
+{
+    return number
+        .checked_sub(1)
+        .ok_or_else(|| String::from("Savings plan sections are numbered starting at 1"));
+}
+
+fn _get_entry<'a>(datafile: &'a DataFile, name: &str) -> Result<&'a DepotEntry, String>
+{
+    return match datafile.investing.depot.get_entry_from_str(name) {
+        Some(entry) => Ok(entry),
+        None => Err(format!("There is no depot entry called {name}")),
+    };
+}
+
+fn _get_entry_mut<'a>(datafile: &'a mut DataFile, name: &str) -> Result<&'a mut DepotEntry, String>
+{
+    return match datafile.investing.depot.get_entry_mut_from_str(name) {
+        Some(entry) => Ok(entry),
+        None => Err(format!("There is no depot entry called {name}")),
+    };
+}
+
+fn _print_cost_basis(name: &str, currency: &Currency, cost_basis: &CostBasis)
+{
+    let percent = match cost_basis.gain_percent() {
+        Some(percent) => format!("{percent:.2}"),
+        None => String::from("-"),
+    };
+    println!(
+        "{:<30} {:<4} {:>14.2} {:>14.2} {:>14.2} {:>14.2} {:>9}",
+        name,
+        currency,
+        cost_basis.invested,
+        cost_basis.current_value,
+        cost_basis.unrealized_gain(),
+        cost_basis.realized_gain,
+        percent
+    );
+}
+
+fn _print_performance(name: &str, currency: &Currency, performance: &Performance)
+{
+    let xirr = match performance.xirr {
+        Some(xirr) => format!("{:.2}", xirr * 100.0),
+        None => String::from("-"),
+    };
+    println!(
+        "{:<30} {:<4} {:>14.2} {:>10.2} {:>10.2} {:>10}",
+        name,
+        currency,
+        performance.gain(),
+        performance.time_weighted * 100.0,
+        performance.annualized_time_weighted() * 100.0,
+        xirr
+    );
+}
+
+/// `ter` of the last month, if there is only one
+fn _print_running_costs(name: &str, currency: &Currency, ter: Option<f64>, costs: &RunningCosts)
+{
+    let ter = match ter {
+        Some(ter) => ter.to_string(),
+        None => String::from("-"),
+    };
+    let (value, without_costs) = match costs.months.last() {
+        Some(last) => (last.value, last.value_without_costs),
+        None => (Money::ZERO, Money::ZERO),
+    };
+    println!(
+        "{:<30} {:<4} {:>8} {:>12.2} {:>14.2} {:>14.2} {:>12.2}",
+        name,
+        currency,
+        ter,
+        costs.total_cost(),
+        value,
+        without_costs,
+        costs.drag()
+    );
+}
+
+fn _print_import(report: &csv_import::ImportReport, dry_run: bool)
+{
+    let mut entry_names: Vec<&str> = report.changed.iter().map(|c| c.entry_name.as_str()).collect();
+    entry_names.dedup();
+    for name in entry_names {
+        let months: Vec<&csv_import::ChangedMonth> = report.changed.iter().filter(|c| c.entry_name == name).collect();
+        if let (Some(first), Some(last)) = (months.first(), months.last()) {
+            println!("{:<30} {:0>2}.{} - {:0>2}.{}", name, first.month, first.year, last.month, last.year);
+        }
+    }
+
+    if !report.unmatched.is_empty() {
+        println!("\nRows that were not imported:");
+        for row in report.unmatched.iter() {
+            println!("line {:<6} {:<14} {:<30} {}", row.line, row.isin, row.name, row.reason);
+        }
+    }
+
+    match dry_run {
+        true => println!("{} months would be changed, nothing was changed (--dry-run)", report.changed.len()),
+        false => println!("{} months changed", report.changed.len()),
+    }
+}
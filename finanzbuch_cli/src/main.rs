@@ -0,0 +1,222 @@
+mod accounting;
+mod currency;
+mod export;
+mod inflation;
+mod investing;
+mod recurring;
+
+use clap::Parser;
+use clap::Subcommand;
+use finanzbuch_lib::currency::Currency;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::profiles::AppConfig;
+use finanzbuch_lib::profiles::Selection;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Used instead of `--passphrase`, so that it does not show up in the list of processes
+const ENV_PASSPHRASE: &str = "FINANZBUCH_PASSPHRASE";
+
+/// Command line interface for finanzbuch, for scripting and quick entries.
+///
+/// Without `--file` or `--profile`, the same data file as in the app is used.
+#[derive(Debug, Parser)]
+#[command(name = "finanzbuch", version)]
+struct Cli
+{
+    /// Path of the data file
+    #[arg(long, global = true)]
+    file: Option<PathBuf>,
+
+    /// Name of a profile from the app config
+    #[arg(long, global = true, conflicts_with = "file")]
+    profile: Option<String>,
+
+    /// Passphrase of an encrypted data file, it stays encrypted when it is written.
+    /// Prefer `FINANZBUCH_PASSPHRASE`, arguments can be seen by other users of the system
+    #[arg(long, global = true)]
+    passphrase: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command
+{
+    /// Income and expenses per month
+    #[command(subcommand)]
+    Accounting(accounting::AccountingCommand),
+
+    /// Categories of transactions and their monthly budgets
+    #[command(subcommand)]
+    Category(accounting::CategoryCommand),
+
+    /// Recurring income and expenses and the projection made from them
+    #[command(subcommand)]
+    Recurring(recurring::RecurringCommand),
+
+    /// Entries of the depot and their monthly values
+    #[command(subcommand)]
+    Depot(investing::DepotCommand),
+
+    /// Savings plan sections of one depot entry
+    #[command(subcommand)]
+    SavingsPlan(investing::SavingsPlanCommand),
+
+    /// Currencies of accounting and depot entries, and the exchange rates between them
+    #[command(subcommand)]
+    Currency(currency::CurrencyCommand),
+
+    /// Consumer price index, to show income, expenses and the depot in real terms
+    #[command(subcommand)]
+    Inflation(inflation::InflationCommand),
+
+    /// Sums and medians of one accounting year
+    Summary
+    {
+        year: u16
+    },
+
+    /// Write all months as CSV or JSON, for spreadsheets
+    Export(export::ExportArgs),
+
+    /// Print how numbers are written in the app and in imported files, or change it to de-DE, en-US or de-CH
+    Locale
+    {
+        #[arg(value_parser = parse_locale)]
+        locale: Option<Locale>,
+    },
+}
+
+fn main() -> ExitCode
+{
+    let cli = Cli::parse();
+
+    let mut datafile = match _open(&cli) {
+        Ok(datafile) => datafile,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    // write_on_drop is stored in the file, so keep the users setting for when it is written
+    let write_on_drop = datafile.write_on_drop;
+    datafile.write_on_drop = false;
+
+    let result = match cli.command {
+        Command::Accounting(command) => accounting::run(command, &mut datafile),
+        Command::Category(command) => accounting::run_category(command, &mut datafile),
+        Command::Recurring(command) => recurring::run(command, &mut datafile),
+        Command::Depot(command) => investing::run_depot(command, &mut datafile),
+        Command::SavingsPlan(command) => investing::run_savings_plan(command, &mut datafile),
+        Command::Currency(command) => currency::run(command, &mut datafile),
+        Command::Inflation(command) => inflation::run(command, &mut datafile),
+        Command::Summary { year } => accounting::print_summary(&datafile, year),
+        Command::Export(args) => export::run(args, &datafile),
+        Command::Locale { locale } => _locale(&mut datafile, locale),
+    };
+
+    let changed = match result {
+        Ok(changed) => changed,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if changed {
+        datafile.write_on_drop = write_on_drop;
+        let write_result = datafile.write();
+        datafile.write_on_drop = false;
+
+        if let Err(e) = write_result {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    }
+    return ExitCode::SUCCESS;
+}
+
+// Numbers are written in the locale of the data file, which is only known after it was read.
+// So they are taken as `String` by clap and parsed with these functions afterwards
+
+/// Factors like exchange rates, so that `1.234,5` can be used in de-DE
+fn parse_amount(locale: Locale, value: &str) -> Result<f64, String> { return locale.parse_f64(value).map_err(|e| e.to_string()); }
+
+/// Amounts of money, rounded to cents
+fn parse_money(locale: Locale, value: &str) -> Result<Money, String> { return locale.parse_money(value).map_err(|e| e.to_string()); }
+
+/// Units and prices, with up to eight decimal places
+fn parse_quantity(locale: Locale, value: &str) -> Result<Quantity, String> { return locale.parse_quantity(value).map_err(|e| e.to_string()); }
+
+/// Used as `value_parser` for dates in the format `YYYY-MM-DD`
+fn parse_date(value: &str) -> Result<FastDate, String> { return value.parse::<FastDate>().map_err(|e| e.to_string()); }
+
+/// Used as `value_parser` for months in the format `YYYY-MM`
+fn parse_year_month(value: &str) -> Result<(u16, u8), String>
+{
+    let Some((year, month)) = value.split_once('-') else {
+        return Err(format!("{value} is not a month like 2024-01"));
+    };
+    return match (year.parse::<u16>(), month.parse::<u8>()) {
+        (Ok(year), Ok(month)) if (1..=12).contains(&month) => Ok((year, month)),
+        _ => Err(format!("{value} is not a month like 2024-01")),
+    };
+}
+
+/// Used as `value_parser` for locales like `de-DE`
+fn parse_locale(value: &str) -> Result<Locale, String> { return value.parse::<Locale>().map_err(|e| e.to_string()); }
+
+/// Used as `value_parser` for currency codes like `EUR`
+fn parse_currency(value: &str) -> Result<Currency, String> { return Currency::new(value).map_err(|e| e.to_string()); }
+
+// ================================================== Private ================================================== //
+
+/// Same order as in the app: `--file` / `--profile`, then the environment variables, then the last used profile.
+///
+/// Encrypted files are read with `--passphrase` or `FINANZBUCH_PASSPHRASE`, plain files ignore it
+fn _open(cli: &Cli) -> Result<DataFile, finanzbuch_lib::Error>
+{
+    let selection = match (&cli.file, &cli.profile) {
+        (Some(file), _) => Some(Selection::File(file.clone())),
+        (None, Some(profile)) => Some(Selection::Profile(profile.clone())),
+        (None, None) => Selection::from_env(),
+    };
+
+    let path = match selection {
+        // dont require a readable app config if the file is given directly
+        Some(Selection::File(path)) => path,
+        selection => AppConfig::read()?.resolve(selection)?.path,
+    };
+    let passphrase = cli
+        .passphrase
+        .clone()
+        .or_else(|| std::env::var(ENV_PASSPHRASE).ok())
+        .filter(|p| !p.is_empty());
+    let (datafile, report) = match passphrase {
+        Some(passphrase) => DataFile::read_encrypted_with_report(path, &passphrase)?,
+        None => DataFile::read_from_custom_path_with_report(path)?,
+    };
+    // stdout may be redirected into a file, eg. by `export`
+    if !report.is_empty() {
+        eprint!("{report}");
+    }
+    return Ok(datafile);
+}
+
+/// Returns `true` if the locale was changed
+fn _locale(datafile: &mut DataFile, locale: Option<Locale>) -> Result<bool, String>
+{
+    let Some(locale) = locale else {
+        let example = datafile.locale.format_money(Money::from_cents(-123456789));
+        println!("{} (like {example})", datafile.locale);
+        return Ok(false);
+    };
+    datafile.locale = locale;
+    return Ok(true);
+}
@@ -0,0 +1,243 @@
+use clap::Subcommand;
+use clap::ValueEnum;
+use finanzbuch_lib::accounting::recurrence::Recurrence;
+use finanzbuch_lib::accounting::recurrence::RecurringInOut;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Money;
+
+use crate::parse_amount;
+use crate::parse_date;
+use crate::parse_year_month;
+
+#[derive(Debug, Subcommand)]
+pub enum RecurringCommand
+{
+    /// Print all recurring income and expenses with their average per month and year
+    List,
+
+    /// Add a recurring income or expense, like "50 every 2 weeks": --quantity 50 --recurrence week --interval 2
+    Add
+    {
+        side: Side,
+        name: String,
+        #[arg(long)]
+        quantity: String,
+        #[arg(long, value_enum, default_value = "month")]
+        recurrence: Unit,
+        /// After how many days/weeks/months/years it happens again
+        #[arg(long, default_value = "1")]
+        interval: u16,
+        /// How often it happens per interval
+        #[arg(long, default_value = "1")]
+        frequency: u16,
+        /// First day it is valid, like 2024-03-01
+        #[arg(long, value_parser = parse_date)]
+        start: Option<FastDate>,
+        /// Last day it is valid, like 2024-12-31
+        #[arg(long, value_parser = parse_date)]
+        end: Option<FastDate>,
+        /// Day of the month it is booked on, for monthly and yearly recurrences
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=31))]
+        anchor_day: Option<u8>,
+    },
+
+    /// Change the quantity from a day on, eg. when the rent was raised
+    Change
+    {
+        side: Side,
+        name: String,
+        /// First day of the new quantity, like 2025-01-01
+        #[arg(long, value_parser = parse_date)]
+        from: FastDate,
+        #[arg(long)]
+        quantity: String,
+    },
+
+    /// Set the last day of an item, eg. when a subscription was cancelled
+    End
+    {
+        side: Side,
+        name: String,
+        /// Like 2024-12-31
+        #[arg(value_parser = parse_date)]
+        date: FastDate,
+    },
+
+    /// Remove a recurring income or expense by its name
+    Remove
+    {
+        side: Side, name: String
+    },
+
+    /// Compare the expected values of each month with the actual ones
+    Project
+    {
+        /// First month, like 2024-01
+        #[arg(long, value_parser = parse_year_month)]
+        from: (u16, u8),
+        /// Last month, like 2024-12
+        #[arg(long, value_parser = parse_year_month)]
+        to: (u16, u8),
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Side
+{
+    Income,
+    Expense,
+}
+
+/// Same as `Recurrence`, but with lowercase names for the command line
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Unit
+{
+    Day,
+    Week,
+    Month,
+    Year,
+}
+impl From<Unit> for Recurrence
+{
+    fn from(unit: Unit) -> Self
+    {
+        match unit {
+            Unit::Day => Recurrence::Day,
+            Unit::Week => Recurrence::Week,
+            Unit::Month => Recurrence::Month,
+            Unit::Year => Recurrence::Year,
+        }
+    }
+}
+
+/// Returns `true` if the data was changed and has to be written
+pub fn run(command: RecurringCommand, datafile: &mut DataFile) -> Result<bool, String>
+{
+    let accounting = &mut datafile.accounting;
+    match command {
+        RecurringCommand::List => {
+            println!("{:<30} {:>12} {:>12}", "Income", "Per month", "Per year");
+            accounting.recurring_income.iter().for_each(_print_item);
+            println!("\n{:<30} {:>12} {:>12}", "Expenses", "Per month", "Per year");
+            accounting.recurring_expenses.iter().for_each(_print_item);
+
+            let difference = accounting.get_recurring_income_per_month() - accounting.get_recurring_expenses_per_month();
+            println!("\n{:<30} {:>12.2} {:>12.2}", "Difference", difference, difference * 12.0);
+            return Ok(false);
+        }
+        RecurringCommand::Add {
+            side,
+            name,
+            quantity,
+            recurrence,
+            interval,
+            frequency,
+            start,
+            end,
+            anchor_day,
+        } => {
+            if interval == 0 {
+                return Err(String::from("The interval has to be at least 1"));
+            }
+            let list = match side {
+                Side::Income => &mut accounting.recurring_income,
+                Side::Expense => &mut accounting.recurring_expenses,
+            };
+            if list.iter().any(|r| r.name == name) {
+                return Err(format!("There already is an item called {name}"));
+            }
+            if let (Some(start), Some(end)) = (&start, &end) {
+                if start > end {
+                    return Err(String::from("The item has to start before it ends"));
+                }
+            }
+
+            let quantity = parse_amount(datafile.locale, &quantity)?;
+            let mut item = RecurringInOut::new(&name, quantity, recurrence.into(), interval, frequency);
+            item.start = start;
+            item.end = end;
+            item.anchor_day = anchor_day;
+            list.push(item);
+            return Ok(true);
+        }
+        RecurringCommand::Change { side, name, from, quantity } => {
+            let quantity = parse_amount(datafile.locale, &quantity)?;
+            _get_item_mut(datafile, side, &name)?.add_change(from, quantity);
+            return Ok(true);
+        }
+        RecurringCommand::End { side, name, date } => {
+            let item = _get_item_mut(datafile, side, &name)?;
+            if item.start.as_ref().is_some_and(|start| *start > date) {
+                return Err(String::from("The item has to start before it ends"));
+            }
+            item.end = Some(date);
+            return Ok(true);
+        }
+        RecurringCommand::Remove { side, name } => {
+            let list = match side {
+                Side::Income => &mut accounting.recurring_income,
+                Side::Expense => &mut accounting.recurring_expenses,
+            };
+            let len_before = list.len();
+            list.retain(|r| r.name != name);
+            if list.len() == len_before {
+                return Err(format!("There is no item called {name}"));
+            }
+            return Ok(true);
+        }
+        RecurringCommand::Project { from, to } => {
+            let comparisons = accounting.compare_with_projection(from, to).map_err(|e| e.to_string())?;
+            let optional = |value: Option<Money>| value.map(|v| v.to_string()).unwrap_or(String::from("-"));
+
+            println!(
+                "{:<8} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+                "Month", "Income", "Actual", "Deviation", "Expenses", "Actual", "Deviation"
+            );
+            for c in comparisons {
+                println!(
+                    "{:0>2}.{:<5} {:>12.2} {:>12} {:>12} {:>12.2} {:>12} {:>12}",
+                    c.projected.month,
+                    c.projected.year,
+                    c.projected.income,
+                    optional(c.actual_income),
+                    optional(c.income_deviation()),
+                    c.projected.expenses,
+                    optional(c.actual_expenses),
+                    optional(c.expenses_deviation())
+                );
+            }
+            return Ok(false);
+        }
+    }
+}
+
+// ================================================== Private ================================================== //
+
+fn _get_item_mut<'a>(datafile: &'a mut DataFile, side: Side, name: &str) -> Result<&'a mut RecurringInOut, String>
+{
+    let list = match side {
+        Side::Income => &mut datafile.accounting.recurring_income,
+        Side::Expense => &mut datafile.accounting.recurring_expenses,
+    };
+    return match list.iter_mut().find(|r| r.name == name) {
+        Some(item) => Ok(item),
+        None => Err(format!("There is no item called {name}")),
+    };
+}
+
+fn _print_item(item: &RecurringInOut)
+{
+    let date = |date: &Option<FastDate>| match date {
+        Some(d) => d.to_string(),
+        None => String::from("..."),
+    };
+    println!(
+        "{:<30} {:>12.2} {:>12.2}  {} - {}",
+        item.name,
+        item.per_month(),
+        item.per_year(),
+        date(&item.start),
+        date(&item.end)
+    );
+}
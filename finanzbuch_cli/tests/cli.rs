@@ -0,0 +1,569 @@
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Output;
+
+/// Every test gets its own file, so that they can run in parallel
+fn prepare_file(name: &str) -> PathBuf
+{
+    let dir = PathBuf::from(format!("/tmp/finanzbuch_cli_{name}"));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    return dir.join("finanzbuch.yaml");
+}
+
+fn run(path: &Path, args: &[&str]) -> Output
+{
+    return Command::new(env!("CARGO_BIN_EXE_finanzbuch"))
+        .arg("--file")
+        .arg(path)
+        .args(args)
+        .output()
+        .unwrap();
+}
+
+fn money(value: &str) -> Money { value.parse().unwrap() }
+fn quantity(value: &str) -> Quantity { value.parse().unwrap() }
+
+fn read(path: &Path) -> DataFile
+{
+    let mut datafile = DataFile::read_from_custom_path(path.to_path_buf()).unwrap();
+    datafile.write_on_drop = false;
+    return datafile;
+}
+
+#[test]
+fn accounting_set_and_show()
+{
+    let path = prepare_file("accounting");
+    assert!(run(
+        &path,
+        &[
+            "accounting",
+            "set",
+            "2024",
+            "3",
+            "--income",
+            "2500,50",
+            "--expenses",
+            "1200",
+            "--note",
+            "rent"
+        ]
+    )
+    .status
+    .success());
+
+    let month = read(&path).accounting.history.get(&2024).unwrap().months[2].clone();
+    assert_eq!(month.income(), money("2500.5"));
+    assert_eq!(month.expenses(), money("1200"));
+    assert_eq!(month.note(), "rent");
+
+    let output = run(&path, &["accounting", "show", "2024"]);
+    assert!(String::from_utf8(output.stdout).unwrap().contains("2500.50"));
+}
+
+#[test]
+fn depot_add_and_set_month()
+{
+    let path = prepare_file("depot");
+    assert!(run(&path, &["depot", "add", "World", "etf"]).status.success());
+    assert!(run(
+        &path,
+        &[
+            "depot",
+            "set-month",
+            "World",
+            "2020",
+            "5",
+            "--amount",
+            "2",
+            "--additional-transactions",
+            "-3,5"
+        ]
+    )
+    .status
+    .success());
+
+    let datafile = read(&path);
+    let entry = datafile.investing.depot.get_entry_from_str("World").unwrap();
+    assert_eq!(entry.variant, InvestmentVariant::Etf);
+    let month = &entry.history.get(&2020).unwrap().months[4];
+    assert_eq!(month.amount(), quantity("2"));
+    assert_eq!(month.additional_transactions(), money("-3.5"));
+}
+
+#[test]
+fn overlapping_savings_plan_fails()
+{
+    let path = prepare_file("savings_plan");
+    run(&path, &["depot", "add", "World", "etf"]);
+    let add_section = |start: &str, end: &str| run(&path, &["savings-plan", "add", "World", "--start", start, "--end", end, "--amount", "50"]);
+
+    assert!(add_section("2024-01-01", "2024-06-30").status.success());
+    assert!(!add_section("2024-03-01", "2024-12-31").status.success());
+
+    assert_eq!(read(&path).investing.depot.get_entry_from_str("World").unwrap().savings_plan().len(), 1);
+}
+
+#[test]
+fn failed_command_does_not_write()
+{
+    let path = prepare_file("no_write");
+    assert!(!run(&path, &["depot", "set-month", "Missing", "2024", "1", "--amount", "1"])
+        .status
+        .success());
+    assert!(!path.exists());
+}
+
+#[test]
+fn import_dry_run_does_not_write()
+{
+    let path = prepare_file("import");
+    let csv = path.with_file_name("statement.csv");
+    std::fs::write(
+        &csv,
+        "Buchungstag;Verwendungszweck;Betrag\n01.03.2024;Gehalt;2.500,00\n03.03.2024;Miete;-950,00\n",
+    )
+    .unwrap();
+
+    assert!(run(&path, &["accounting", "import", csv.to_str().unwrap(), "--dry-run"]).status.success());
+    assert!(!path.exists());
+
+    assert!(run(&path, &["accounting", "import", csv.to_str().unwrap()]).status.success());
+    let march = read(&path).accounting.history.get(&2024).unwrap().months[2].clone();
+    assert_eq!((march.income(), march.expenses()), (money("2500"), money("950")));
+}
+
+#[test]
+fn import_uses_the_locale_of_the_data_file()
+{
+    let path = prepare_file("locale");
+    let csv = path.with_file_name("statement.csv");
+    std::fs::write(&csv, "Buchungstag;Verwendungszweck;Betrag\n01.03.2024;Gehalt;\"2,500.00\"\n").unwrap();
+
+    let output = run(&path, &["accounting", "import", csv.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("is not a valid number in de-DE"));
+
+    assert!(run(&path, &["accounting", "import", csv.to_str().unwrap(), "--locale", "en-US"])
+        .status
+        .success());
+    assert!(!run(&path, &["locale", "fr-FR"]).status.success());
+    assert!(run(&path, &["locale", "en-us"]).status.success());
+    assert_eq!(read(&path).locale, Locale::EnUs);
+
+    let output = run(&path, &["locale"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "en-US (like -1,234,567.89)\n");
+    assert!(run(&path, &["accounting", "import", csv.to_str().unwrap()]).status.success());
+}
+
+#[test]
+fn depot_import_by_isin()
+{
+    let path = prepare_file("depot_import");
+    let csv = path.with_file_name("export.csv");
+    std::fs::write(&csv, "Datum;ISIN;Name;Stück;Kurs;Betrag\n15.01.2024;IE00B4L5Y983;;2;80,00;160,00\n").unwrap();
+    let world = |datafile: &DataFile| datafile.investing.depot.get_entry_from_str("World").unwrap().clone();
+
+    assert!(run(&path, &["depot", "add", "World", "etf", "--isin", "IE00B4L5Y983"]).status.success());
+    assert!(run(&path, &["depot", "import", csv.to_str().unwrap(), "--dry-run"]).status.success());
+    assert!(!world(&read(&path)).history.contains_key(&2024));
+
+    assert!(run(&path, &["depot", "import", csv.to_str().unwrap()]).status.success());
+    let january = world(&read(&path)).history.get(&2024).unwrap().months[0].clone();
+    assert_eq!((january.amount(), january.price_per_unit()), (quantity("2"), quantity("80")));
+}
+
+#[test]
+fn export_csv()
+{
+    let path = prepare_file("export");
+    run(&path, &["accounting", "set", "2024", "1", "--income", "10"]);
+    run(&path, &["accounting", "set", "2025", "1", "--income", "20"]);
+
+    let output = run(&path, &["export", "accounting", "--from", "2025"]);
+    let csv = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(csv.lines().count(), 13);
+    assert!(csv.contains("2025;1;20.0;0.0;20.0;"));
+
+    assert!(!run(&path, &["export", "all"]).status.success());
+    assert!(run(&path, &["export", "all", "--format", "json"]).status.success());
+}
+
+#[test]
+fn migration_report_is_not_part_of_the_export()
+{
+    let path = prepare_file("export_migrated");
+    let version_3 = "version: 3\naccounting:\n  goal: 0.75\n  history: {}\n  recurring_income: []\n  recurring_expenses: []\ninvesting:\n  comparisons: []\n  depot: {}\nwrite_on_drop: false\n";
+    std::fs::write(&path, version_3).unwrap();
+
+    let output = run(&path, &["export", "all", "--format", "json"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.trim_start().starts_with('{') && stdout.trim_end().ends_with('}'));
+    assert!(!stdout.contains("migrated"));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("migrated from version 3"));
+}
+
+#[test]
+fn transactions_make_up_the_month()
+{
+    let path = prepare_file("transactions");
+    assert!(run(
+        &path,
+        &["accounting", "add-transaction", "2024-03-01", "2500", "--counterparty", "Employer"]
+    )
+    .status
+    .success());
+    assert!(
+        run(&path, &["accounting", "add-transaction", "2024-03-03", "-950,50", "--category", "Rent"])
+            .status
+            .success()
+    );
+    assert!(!run(&path, &["accounting", "set", "2024", "3", "--income", "1"]).status.success());
+
+    let march = read(&path).accounting.history.get(&2024).unwrap().months[2].clone();
+    assert_eq!((march.income(), march.expenses()), (money("2500"), money("950.5")));
+
+    assert!(!run(&path, &["accounting", "remove-transaction", "2024", "3", "3"]).status.success());
+    assert!(run(&path, &["accounting", "remove-transaction", "2024", "3", "1"]).status.success());
+    assert_eq!(read(&path).accounting.history.get(&2024).unwrap().months[2].transactions().len(), 1);
+}
+
+#[test]
+fn numbers_are_parsed_in_the_locale_of_the_data_file()
+{
+    let path = prepare_file("locale_numbers");
+    let set_income = |income: &str| run(&path, &["accounting", "set", "2024", "1", "--income", income]).status.success();
+    let income = || read(&path).accounting.history.get(&2024).unwrap().months[0].income();
+
+    // de-DE: a single dot separates thousands, it is not the decimal point
+    assert!(set_income("1.234,56"));
+    assert_eq!(income(), money("1234.56"));
+    assert!(set_income("1.234"));
+    assert_eq!(income(), money("1234"));
+    assert!(!set_income("1,234.56"));
+
+    assert!(run(&path, &["locale", "en-US"]).status.success());
+    assert!(set_income("1,234.56"));
+    assert_eq!(income(), money("1234.56"));
+    assert!(!set_income("1.234,56"));
+    assert!(run(&path, &["currency", "set-rate", "USD", "2024-01", "1,050.5"]).status.success());
+    assert_eq!(read(&path).exchange_rates.rates[0].rate, 1050.5);
+}
+
+#[test]
+fn depot_transactions_make_up_the_months()
+{
+    let path = prepare_file("depot_transactions");
+    let add = |args: &[&str]| -> bool {
+        let mut all = vec!["depot", "add-transaction", "World"];
+        all.extend_from_slice(args);
+        return run(&path, &all).status.success();
+    };
+    assert!(run(&path, &["depot", "add", "World", "etf"]).status.success());
+    assert!(add(&["2024-01-10", "buy", "--units", "4", "--price", "80,5", "--fees", "1"]));
+    assert!(add(&["2024-02-10", "dividend", "--units", "4", "--price", "0,25"]));
+    assert!(!add(&["2024-03-01", "sell", "--units", "5", "--price", "90"]));
+    assert!(!add(&["2024-03-01", "kauf", "--units", "1", "--price", "90"]));
+    assert!(!run(&path, &["depot", "set-month", "World", "2024", "2", "--amount", "1"])
+        .status
+        .success());
+
+    let datafile = read(&path);
+    let entry = datafile.investing.depot.get_entry_from_str("World").unwrap();
+    assert_eq!(entry.transactions().len(), 2);
+    let months = &entry.history.get(&2024).unwrap().months;
+    assert_eq!(months[0].amount(), quantity("4"));
+    assert_eq!(months[0].additional_transactions(), money("323"));
+    assert_eq!(months[1].amount(), quantity("4"));
+    assert_eq!(months[1].additional_transactions(), money("-1"));
+
+    assert!(!run(&path, &["depot", "remove-transaction", "World", "3"]).status.success());
+    assert!(run(&path, &["depot", "remove-transaction", "World", "2"]).status.success());
+    assert_eq!(read(&path).investing.depot.get_entry_from_str("World").unwrap().transactions().len(), 1);
+}
+
+#[test]
+fn depot_gains()
+{
+    let path = prepare_file("depot_gains");
+    assert!(run(&path, &["depot", "add", "World", "etf"]).status.success());
+    for args in [
+        ["2024-01-10", "buy", "10", "10"],
+        ["2024-02-10", "buy", "10", "20"],
+        ["2024-03-10", "sell", "15", "30"],
+    ] {
+        let [date, kind, units, price] = args;
+        let output = run(
+            &path,
+            &["depot", "add-transaction", "World", date, kind, "--units", units, "--price", price],
+        );
+        assert!(output.status.success());
+    }
+
+    let output = run(&path, &["depot", "gains", "--month", "2024-03"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("FIFO 2024-03"));
+    let columns = |stdout: &str, name: &str| -> Vec<String> {
+        let line = stdout.lines().find(|l| l.starts_with(name)).unwrap();
+        return line.split_whitespace().map(String::from).collect();
+    };
+    assert_eq!(
+        columns(&stdout, "World"),
+        ["World", "EUR", "100.00", "150.00", "50.00", "250.00", "50.00"]
+    );
+
+    let output = run(&path, &["depot", "gains", "--month", "2024-03", "--method", "average"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(columns(&stdout, "Sum"), ["Sum", "EUR", "75.00", "150.00", "75.00", "225.00", "100.00"]);
+}
+
+#[test]
+fn depot_performance()
+{
+    let path = prepare_file("depot_performance");
+    assert!(run(&path, &["depot", "add", "World", "etf"]).status.success());
+    for (month, price) in [("12", "100"), ("1", "110"), ("2", "121")] {
+        let year = if month == "12" { "2023" } else { "2024" };
+        let args = ["depot", "set-month", "World", year, month, "--amount", "10", "--price-per-unit", price];
+        assert!(run(&path, &args).status.success());
+    }
+
+    let output = run(&path, &["depot", "performance", "--from", "2024-01", "--to", "2024-02"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let columns = |name: &str| -> Vec<String> {
+        let line = stdout.lines().find(|l| l.starts_with(name)).unwrap();
+        return line.split_whitespace().map(String::from).collect();
+    };
+    assert_eq!(columns("World")[..4], ["World", "EUR", "210.00", "21.00"]);
+    assert_eq!(columns("Etf")[..4], ["Etf", "EUR", "210.00", "21.00"]);
+    assert_eq!(columns("Depot")[..4], ["Depot", "EUR", "210.00", "21.00"]);
+    assert!(!run(&path, &["depot", "performance", "--from", "2024-13"]).status.success());
+}
+
+#[test]
+fn category_budget_report()
+{
+    let path = prepare_file("categories");
+    assert!(run(&path, &["category", "add", "Wohnen"]).status.success());
+    assert!(run(&path, &["category", "add", "Wohnen > Miete"]).status.success());
+    assert!(!run(&path, &["category", "add", "Auto > Benzin"]).status.success());
+    assert!(run(&path, &["category", "budget", "Wohnen", "900"]).status.success());
+    run(&path, &["accounting", "add-transaction", "2024-01-02", "1000"]);
+    run(
+        &path,
+        &["accounting", "add-transaction", "2024-01-03", "-1050", "--category", "Wohnen>Miete"],
+    );
+
+    assert_eq!(read(&path).accounting.get_category("Wohnen").unwrap().budget(), Some(money("900")));
+    let report = String::from_utf8(run(&path, &["category", "report", "2024"]).stdout).unwrap();
+    assert!(report.contains("1050.00!"));
+    assert!(report.contains("01.2024 missed the goal with 105%, over budget: Wohnen"));
+}
+
+#[test]
+fn recurring_projection()
+{
+    let path = prepare_file("recurring");
+    assert!(run(&path, &["recurring", "add", "income", "Salary", "--quantity", "2500"])
+        .status
+        .success());
+    assert!(run(
+        &path,
+        &["recurring", "add", "expense", "Insurance", "--quantity", "1200", "--recurrence", "year"]
+    )
+    .status
+    .success());
+    assert!(!run(&path, &["recurring", "add", "expense", "Insurance", "--quantity", "1"])
+        .status
+        .success());
+    run(&path, &["accounting", "set", "2024", "1", "--income", "2500", "--expenses", "150"]);
+
+    let output = String::from_utf8(run(&path, &["recurring", "project", "--from", "2024-01", "--to", "2024-02"]).stdout).unwrap();
+    let january = output.lines().nth(1).unwrap();
+    assert!(january.starts_with("01.2024"));
+    assert!(january.ends_with("150.00        50.00"));
+
+    assert!(run(
+        &path,
+        &[
+            "recurring",
+            "change",
+            "expense",
+            "Insurance",
+            "--from",
+            "2024-02-01",
+            "--quantity",
+            "2400"
+        ]
+    )
+    .status
+    .success());
+    assert!(run(&path, &["recurring", "end", "income", "Salary", "2024-01-31"]).status.success());
+    let february = String::from_utf8(run(&path, &["recurring", "project", "--from", "2024-02", "--to", "2024-02"]).stdout).unwrap();
+    assert!(february.lines().nth(1).unwrap().starts_with("02.2024          0.00"));
+    assert!(february.contains("200.00"));
+
+    assert!(run(&path, &["recurring", "remove", "income", "Salary"]).status.success());
+    assert!(read(&path).accounting.recurring_income.is_empty());
+}
+
+#[test]
+fn prefill_new_years()
+{
+    let path = prepare_file("prefill");
+    run(&path, &["recurring", "add", "income", "Salary", "--quantity", "2500"]);
+    assert!(run(&path, &["accounting", "auto-prefill", "true"]).status.success());
+    run(&path, &["accounting", "set", "2024", "2", "--income", "2600"]);
+
+    let year = read(&path).accounting.history.get(&2024).unwrap().clone();
+    assert!(year.months[0].is_planned());
+    assert_eq!(year.months[0].income(), money("2500"));
+    assert!(!year.months[1].is_planned());
+
+    let output = String::from_utf8(run(&path, &["accounting", "show", "2024"]).stdout).unwrap();
+    assert!(output.lines().nth(1).unwrap().starts_with("01 plan"));
+}
+
+#[test]
+fn currencies_and_exchange_rates()
+{
+    let path = prepare_file("currency");
+    assert!(run(&path, &["depot", "add", "S&P 500", "Etf", "--currency", "usd"]).status.success());
+    assert!(run(&path, &["currency", "set-rate", "USD", "2024-01", "0,92"]).status.success());
+    assert!(run(&path, &["currency", "set-overview", "CHF"]).status.success());
+    assert!(!run(&path, &["currency", "set-rate", "EUR", "2024-01", "1"]).status.success());
+    assert!(!run(&path, &["currency", "set-base", "USD"]).status.success());
+
+    let datafile = read(&path);
+    let entry = datafile.investing.depot.get_entry_from_str("S&P 500").unwrap();
+    assert_eq!(entry.currency.code(), "USD");
+    assert_eq!(datafile.overview_currency().code(), "CHF");
+    assert_eq!(datafile.exchange_rates.rate(&entry.currency, 2024, 6).unwrap(), 0.92);
+
+    let output = String::from_utf8(run(&path, &["currency", "rates"]).stdout).unwrap();
+    assert_eq!(output.lines().count(), 1);
+    assert!(output.starts_with("USD 2024-01"));
+}
+
+#[test]
+fn inflation_adjusted_values()
+{
+    let path = prepare_file("inflation");
+    assert!(run(&path, &["accounting", "set", "2024", "1", "--income", "2200", "--expenses", "1100"])
+        .status
+        .success());
+    assert!(run(&path, &["inflation", "set", "2023-01", "100"]).status.success());
+    assert!(run(&path, &["inflation", "set", "2024-01", "110"]).status.success());
+    assert!(!run(&path, &["inflation", "set", "2024-02", "-1"]).status.success());
+    assert!(!run(&path, &["inflation", "remove", "2024-02"]).status.success());
+
+    let datafile = read(&path);
+    assert_eq!(datafile.price_index.index(2024, 6).unwrap(), 110.0);
+
+    let output = String::from_utf8(run(&path, &["inflation", "show"]).stdout).unwrap();
+    assert_eq!(output.lines().count(), 2);
+
+    let output = String::from_utf8(run(&path, &["inflation", "real", "--from", "2024-01", "--to", "2024-01", "--base", "2023-01"]).stdout).unwrap();
+    let row: Vec<&str> = output.lines().nth(2).unwrap().split_whitespace().collect();
+    assert_eq!(row, vec!["2024-01", "2000.00", "1000.00", "1000.00", "0.00"]);
+}
+
+#[test]
+fn depot_running_costs()
+{
+    let path = prepare_file("depot_costs");
+    assert!(run(&path, &["depot", "add", "World", "etf"]).status.success());
+    for (year, month) in [("2023", "12"), ("2024", "1")] {
+        let args = ["depot", "set-month", "World", year, month, "--amount", "10", "--price-per-unit", "100"];
+        assert!(run(&path, &args).status.success());
+    }
+    assert!(run(&path, &["depot", "set-expense-ratio", "World", "2023-01", "1,2"]).status.success());
+    assert!(!run(&path, &["depot", "set-expense-ratio", "World", "2023-01", "-1"]).status.success());
+    assert!(!run(&path, &["depot", "remove-expense-ratio", "World", "2023-02"]).status.success());
+
+    let datafile = read(&path);
+    let entry = datafile.investing.depot.get_entry_from_str("World").unwrap();
+    assert_eq!(entry.expense_ratio(2024, 1).unwrap().percent, 1.2);
+
+    let output = run(&path, &["depot", "costs", "--from", "2024-01", "--to", "2024-01"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let columns = |name: &str| -> Vec<String> {
+        let line = stdout.lines().find(|l| l.starts_with(name)).unwrap();
+        return line.split_whitespace().map(String::from).collect();
+    };
+    assert_eq!(columns("World"), ["World", "EUR", "1.2", "1.00", "1000.00", "1001.00", "1.00"]);
+    assert_eq!(columns("Depot"), ["Depot", "EUR", "-", "1.00", "1000.00", "1001.00", "1.00"]);
+}
+
+#[test]
+fn savings_plan_update_and_remove()
+{
+    let path = prepare_file("savings_plan_edit");
+    assert!(run(&path, &["depot", "add", "World", "etf"]).status.success());
+    for (start, end) in [("2024-01-01", "2024-12-31"), ("2023-01-01", "2023-12-31")] {
+        let args = ["savings-plan", "add", "World", "--start", start, "--end", end, "--amount", "50"];
+        assert!(run(&path, &args).status.success());
+    }
+
+    let output = run(&path, &["savings-plan", "list", "World"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.lines().next().unwrap().trim_start().starts_with("1. 2023-01-01"));
+
+    assert!(run(
+        &path,
+        &["savings-plan", "update", "World", "2", "--amount", "75", "--interval", "annually"]
+    )
+    .status
+    .success());
+    assert!(!run(&path, &["savings-plan", "update", "World", "1", "--end", "2024-02-01"])
+        .status
+        .success());
+    assert!(!run(&path, &["savings-plan", "update", "World", "0", "--amount", "1"]).status.success());
+    assert!(!run(&path, &["savings-plan", "remove", "World", "3"]).status.success());
+
+    let datafile = read(&path);
+    let plan = datafile.investing.depot.get_entry_from_str("World").unwrap().savings_plan();
+    assert_eq!(plan[0].end, FastDate::new_risky(2023, 12, 31));
+    assert_eq!((plan[1].amount, plan[1].interval.to_string()), (money("75"), String::from("Annually")));
+
+    assert!(run(&path, &["savings-plan", "remove", "World", "1"]).status.success());
+    let datafile = read(&path);
+    let plan = datafile.investing.depot.get_entry_from_str("World").unwrap().savings_plan();
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].amount, money("75"));
+}
+
+#[test]
+fn encrypted_file_stays_encrypted()
+{
+    let path = prepare_file("encrypted");
+    DataFile::default_no_write_on_drop().write_encrypted(path.clone(), "secret").unwrap();
+
+    assert!(!run(&path, &["accounting", "set", "2024", "1", "--income", "10"]).status.success());
+    let with_passphrase = run(&path, &["--passphrase", "secret", "accounting", "set", "2024", "1", "--income", "10"]);
+    assert!(with_passphrase.status.success());
+    let output = Command::new(env!("CARGO_BIN_EXE_finanzbuch"))
+        .env("FINANZBUCH_PASSPHRASE", "secret")
+        .args(["--file", path.to_str().unwrap(), "accounting", "show", "2024"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!run(&path, &["--passphrase", "wrong", "accounting", "show", "2024"]).status.success());
+
+    assert!(finanzbuch_lib::encryption::is_encrypted(&std::fs::read(&path).unwrap()));
+    let mut datafile = DataFile::read_encrypted(path, "secret").unwrap();
+    datafile.write_on_drop = false;
+    assert_eq!(datafile.accounting.history.get(&2024).unwrap().months[0].income(), money("10"));
+}
@@ -1,4 +1,7 @@
-use crate::SanitizeInput;
+use crate::accounting::goal::GoalResult;
+use crate::accounting::ledger::LedgerTransaction;
+use crate::Error;
+use crate::Money;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -6,66 +9,195 @@ use serde::Serialize;
 pub struct AccountingMonth
 {
     month_nr: u8,
-    income: f64,
-    expenses: f64,
+    /// Calculated from `transactions` if there are any. They are still stored, so that older versions can read the totals
+    income: Money,
+    expenses: Money,
     note: String,
+    /// Optional, files from before the ledger only contain the totals
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    transactions: Vec<LedgerTransaction>,
+    /// `true` if income and expenses were generated from the recurring items and not changed since
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    planned: bool,
 }
 impl AccountingMonth
 {
-    /// Will panic if month_nr not 1-12
-    pub fn default(month_nr: u8) -> Self
+    /// Returns `Err(InvalidMonth)` if month_nr not 1-12
+    pub fn default(month_nr: u8) -> Result<Self, Error>
     {
         if month_nr > 12 || month_nr == 0 {
-            panic!("month_nr out of bounds");
+            return Err(Error::InvalidMonth(month_nr));
         }
-        return Self {
-            month_nr,
-            income: 0.0,
-            expenses: 0.0,
-            note: String::new(),
-        };
+        return Ok(Self::_default_unchecked(month_nr));
     }
 
-    /// Will panic if month_nr not 1-12
-    pub fn new(month_nr: u8, income: f64, expenses: f64, note: String) -> Self
+    /// Returns `Err(InvalidMonth)` if month_nr not 1-12
+    /// Income and expenses are stored as absolute values
+    pub fn new(month_nr: u8, income: Money, expenses: Money, note: String) -> Result<Self, Error>
     {
         if month_nr > 12 || month_nr == 0 {
-            panic!("month_nr out of bounds");
+            return Err(Error::InvalidMonth(month_nr));
         }
-        Self {
+        Ok(Self {
             month_nr,
-            income: SanitizeInput::f64_to_monetary_f64_abs(income),
-            expenses: SanitizeInput::f64_to_monetary_f64_abs(expenses),
+            income: income.abs(),
+            expenses: expenses.abs(),
             note,
-        }
+            transactions: vec![],
+            planned: false,
+        })
     }
 
-    pub fn default_months() -> [Self; 12] { return std::array::from_fn(|i| Self::default(i as u8 + 1)); }
+    /// Same as `new()`, but marked as planned until income, expenses or transactions are changed
+    pub fn new_planned(month_nr: u8, income: Money, expenses: Money) -> Result<Self, Error>
+    {
+        let mut month = Self::new(month_nr, income, expenses, String::new())?;
+        month.planned = true;
+        return Ok(month);
+    }
+
+    pub fn default_months() -> [Self; 12] { return std::array::from_fn(|i| Self::_default_unchecked(i as u8 + 1)); }
+
+    /// `true` if this month only contains default values
+    pub fn is_default(&self) -> bool { *self == Self::_default_unchecked(self.month_nr) }
 
     // Getter
     pub fn month_nr(&self) -> u8 { self.month_nr }
-    pub fn income(&self) -> f64 { self.income }
-    pub fn expenses(&self) -> f64 { self.expenses }
+    /// Sum of the incoming transactions, if there are any
+    pub fn income(&self) -> Money
+    {
+        return match self.transactions.is_empty() {
+            true => self.income,
+            false => _sum(self.transactions.iter().filter(|t| t.is_income())),
+        };
+    }
+    /// Sum of the outgoing transactions as positive value, if there are any
+    pub fn expenses(&self) -> Money
+    {
+        return match self.transactions.is_empty() {
+            true => self.expenses,
+            false => _sum(self.transactions.iter().filter(|t| !t.is_income())).abs(),
+        };
+    }
     pub fn note(&self) -> &str { self.note.as_ref() }
+    pub fn is_planned(&self) -> bool { self.planned }
     // note doesnt need any content checking, because yaml can store any String
     pub fn note_mut(&mut self) -> &mut String { &mut self.note }
 
     // Setters
     // month_nr cannot be changed after the month was created
 
-    /// Absolute value, rounded to two decimal places will be stored
-    pub fn set_income(&mut self, income: f64) { self.income = SanitizeInput::f64_to_monetary_f64_abs(income); }
+    /// Absolute value will be stored.
+    /// Has no effect on `income()` while the month has transactions
+    pub fn set_income(&mut self, income: Money)
+    {
+        self.income = income.abs();
+        self.planned = false;
+    }
 
-    /// Absolute value, rounded to two decimal places will be stored
-    pub fn set_expenses(&mut self, expenses: f64) { self.expenses = SanitizeInput::f64_to_monetary_f64_abs(expenses); }
+    /// Absolute value will be stored.
+    /// Has no effect on `expenses()` while the month has transactions
+    pub fn set_expenses(&mut self, expenses: Money)
+    {
+        self.expenses = expenses.abs();
+        self.planned = false;
+    }
     pub fn set_note(&mut self, note: String) { self.note = note; }
 
+    // Ledger
+
+    /// Sorted by date
+    pub fn transactions(&self) -> &[LedgerTransaction] { self.transactions.as_ref() }
+
+    /// Returns `Err(InvalidTransaction)` if the transaction is not in this month. The year has to be checked by the caller
+    pub fn add_transaction(&mut self, transaction: LedgerTransaction) -> Result<(), Error>
+    {
+        if transaction.date.month() != self.month_nr {
+            return Err(Error::InvalidTransaction(format!(
+                "a transaction from month {} cannot be added to month {}",
+                transaction.date.month(),
+                self.month_nr
+            )));
+        }
+
+        // after all transactions of the same day
+        let index = self.transactions.partition_point(|t| t.date <= transaction.date);
+        self.transactions.insert(index, transaction);
+        self._update_totals();
+        return Ok(());
+    }
+
+    /// Replaces all transactions. If one of them is not in this month, nothing is changed
+    pub fn set_transactions(&mut self, mut transactions: Vec<LedgerTransaction>) -> Result<(), Error>
+    {
+        if let Some(wrong) = transactions.iter().find(|t| t.date.month() != self.month_nr) {
+            return Err(Error::InvalidTransaction(format!(
+                "a transaction from month {} cannot be added to month {}",
+                wrong.date.month(),
+                self.month_nr
+            )));
+        }
+
+        transactions.sort_by(|a, b| a.date.cmp(&b.date));
+        self.transactions = transactions;
+        self._update_totals();
+        return Ok(());
+    }
+
+    /// Index as in `transactions()`. Removing the last transaction keeps its totals in `income()` and `expenses()`
+    pub fn remove_transaction(&mut self, index: usize) -> Option<LedgerTransaction>
+    {
+        if index >= self.transactions.len() {
+            return None;
+        }
+        let removed = self.transactions.remove(index);
+        self._update_totals();
+        return Some(removed);
+    }
+
     // Others
-    pub fn difference(&self) -> f64 { self.income - self.expenses }
+    pub fn difference(&self) -> Money { self.income() - self.expenses() }
 
     /// 1.0 = 100%
-    pub fn percentage_1(&self) -> f64 { self.expenses / self.income }
+    pub fn percentage_1(&self) -> f64 { self.expenses().to_f64() / self.income().to_f64() }
 
     // 100 = 100%
     pub fn percentage_100(&self) -> u16 { (self.percentage_1() * 100.0) as u16 }
+
+    /// `goal` is the maximum share of the income that may be spent (1.0 = 100%).
+    /// `None` if the month has no data or is only planned
+    pub fn evaluate_goal(&self, goal: f64) -> Option<GoalResult>
+    {
+        if self.planned {
+            return None;
+        }
+        return GoalResult::new(goal, self.income(), self.expenses());
+    }
+
+    /// The month_nr has to be checked by the caller
+    fn _default_unchecked(month_nr: u8) -> Self
+    {
+        return Self {
+            month_nr,
+            income: Money::ZERO,
+            expenses: Money::ZERO,
+            note: String::new(),
+            transactions: vec![],
+            planned: false,
+        };
+    }
+
+    /// Keeps the stored totals equal to the transactions. Every change of the ledger is a manual change
+    fn _update_totals(&mut self)
+    {
+        self.planned = false;
+        if !self.transactions.is_empty() {
+            self.income = self.income();
+            self.expenses = self.expenses();
+        }
+    }
 }
+
+// ================================================== Private ================================================== //
+
+fn _sum<'a>(transactions: impl Iterator<Item = &'a LedgerTransaction>) -> Money { transactions.map(|t| t.amount()).sum() }
@@ -1,4 +1,14 @@
 use crate::accounting::accounting_month::AccountingMonth;
+use crate::accounting::category::Category;
+use crate::accounting::category::CategoryKind;
+use crate::accounting::category::CategoryReport;
+use crate::accounting::goal::GoalResult;
+use crate::accounting::goal::GoalStreaks;
+use crate::accounting::goal::MonthSummary;
+use crate::accounting::goal::YearSummary;
+use crate::accounting::ledger::LedgerTransaction;
+use crate::Error;
+use crate::Money;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -25,7 +35,7 @@ impl AccountingYear
         let month_nr = new_month.month_nr();
         let month: &mut AccountingMonth = &mut self.months[month_nr as usize - 1];
 
-        if *month != AccountingMonth::default(month.month_nr()) {
+        if !month.is_default() {
             // ("{:0>2?}")
             //       2 - width
             //      > -- where to align actual value, > means {fill}{value}, < means {value}{fill}
@@ -37,67 +47,162 @@ impl AccountingYear
         *month = new_month;
     }
 
-    pub fn get_difference(&self) -> f64 { self.get_sum_income() - self.get_sum_expenses() }
+    /// Adds the transaction to the ledger of its month.
+    /// Returns `Err(InvalidTransaction)` if it is from another year
+    pub fn add_transaction(&mut self, transaction: LedgerTransaction) -> Result<(), Error>
+    {
+        if transaction.date.year() != self.year_nr {
+            return Err(Error::InvalidTransaction(format!(
+                "a transaction from {} cannot be added to {}",
+                transaction.date.year(),
+                self.year_nr
+            )));
+        }
+        return self.months[transaction.date.month() as usize - 1].add_transaction(transaction);
+    }
+
+    /// One report per category, in the order of `categories`. Transactions of child categories count for their parents too.
+    /// Months without transactions have no categories and count as 0
+    pub fn get_category_reports(&self, categories: &[Category]) -> Vec<CategoryReport>
+    {
+        let mut reports: Vec<CategoryReport> = categories
+            .iter()
+            .map(|c| CategoryReport {
+                path: String::from(c.path()),
+                kind: c.kind,
+                budget: c.budget(),
+                months: [Money::ZERO; 12],
+            })
+            .collect();
+
+        for (index, month) in self.months.iter().enumerate() {
+            for transaction in month.transactions() {
+                let Ok(path) = Category::normalize_path(&transaction.category) else {
+                    continue; // uncategorized
+                };
+                for (category, report) in categories.iter().zip(reports.iter_mut()) {
+                    if category.contains(&path) {
+                        report.months[index] += match category.kind {
+                            CategoryKind::Income => transaction.amount(),
+                            CategoryKind::Expense => -transaction.amount(),
+                        };
+                    }
+                }
+            }
+        }
+        return reports;
+    }
 
-    pub fn get_percentage1(&self) -> f64 { self.get_sum_expenses() / self.get_sum_income() }
+    pub fn get_difference(&self) -> Money { self.get_sum_income() - self.get_sum_expenses() }
+
+    pub fn get_percentage1(&self) -> f64 { self.get_sum_expenses().to_f64() / self.get_sum_income().to_f64() }
 
     pub fn get_percentage100(&self) -> u16 { (self.get_percentage1() * 100.0) as u16 }
 
-    pub fn get_sum_income(&self) -> f64
+    /// Goal of the whole year, measured with the sums. `None` if the year has no data that is not only planned
+    pub fn evaluate_goal(&self, goal: f64) -> Option<GoalResult> { GoalResult::new(goal, self.get_sum_income(), self.get_sum_expenses()) }
+
+    /// Streaks inside this year only, see `Accounting::get_goal_streaks()` for streaks over all years
+    pub fn get_goal_streaks(&self, goal: f64) -> GoalStreaks
     {
-        let mut sum: f64 = 0.0;
-        self.months.iter().for_each(|i| sum += i.income());
-        return sum;
+        return GoalStreaks::new(self.months.iter().map(|m| ((self.year_nr, m.month_nr()), m.evaluate_goal(goal))));
     }
 
-    pub fn get_sum_expenses(&self) -> f64
+    /// Sums, medians and the goal of the year leave out planned months, which only contain projected values
+    pub fn get_summary(&self, goal: f64) -> YearSummary
     {
-        let mut sum: f64 = 0.0;
-        self.months.iter().for_each(|i| sum += i.expenses());
-        return sum;
+        let months: [MonthSummary; 12] = std::array::from_fn(|i| {
+            let month = &self.months[i];
+            return MonthSummary {
+                month: month.month_nr(),
+                income: month.income(),
+                expenses: month.expenses(),
+                difference: month.difference(),
+                percentage: month.income().is_positive().then(|| month.percentage_1()),
+                goal: month.evaluate_goal(goal),
+            };
+        });
+        let count = |reached: bool| months.iter().filter(|m| m.goal.is_some_and(|g| g.reached == reached)).count() as u8;
+
+        return YearSummary {
+            year: self.year_nr,
+            goal_percentage: goal,
+            sum_income: self.get_sum_income(),
+            sum_expenses: self.get_sum_expenses(),
+            difference: self.get_difference(),
+            percentage: self.get_sum_income().is_positive().then(|| self.get_percentage1()),
+            median_income: self.get_median_income(),
+            median_expenses: self.get_median_expenses(),
+            median_difference: self.get_median_difference(),
+            goal: self.evaluate_goal(goal),
+            months_reached: count(true),
+            months_missed: count(false),
+            longest_streak: self.get_goal_streaks(goal).longest as u8,
+            months,
+        };
     }
 
-    /// will return Err(()) if there is no data to calculate a median of
-    pub fn get_median_income(&self) -> Result<f64, ()>
+    /// Planned months are not included
+    pub fn get_sum_income(&self) -> Money { self._actual_months().map(|m| m.income()).sum() }
+
+    /// Planned months are not included
+    pub fn get_sum_expenses(&self) -> Money { self._actual_months().map(|m| m.expenses()).sum() }
+
+    /// will return None if there is no data to calculate a median of, planned months are not included
+    pub fn get_median_income(&self) -> Option<Money>
     {
-        let incomes: Vec<f64> = self.months.iter().map(|m| m.income()).collect();
-        Self::_get_median_f64(&incomes)
+        let incomes: Vec<Money> = self._actual_months().map(|m| m.income()).collect();
+        Self::_get_median_money(&incomes)
     }
 
-    /// will return Err(()) if there is no data to calculate a median of
-    pub fn get_median_expenses(&self) -> Result<f64, ()>
+    /// will return None if there is no data to calculate a median of, planned months are not included
+    pub fn get_median_expenses(&self) -> Option<Money>
     {
-        let expenses: Vec<f64> = self.months.iter().map(|m| m.expenses()).collect();
-        Self::_get_median_f64(&expenses)
+        let expenses: Vec<Money> = self._actual_months().map(|m| m.expenses()).collect();
+        Self::_get_median_money(&expenses)
     }
 
-    /// will return Err(()) if there is no data to calculate a median of
-    pub fn get_median_difference(&self) -> Result<f64, ()>
+    /// will return None if there is no data to calculate a median of, planned months are not included
+    pub fn get_median_difference(&self) -> Option<Money>
     {
-        let diffs: Vec<f64> = self.months.iter().map(|m| m.difference()).collect();
-        Self::_get_median_f64(&diffs)
+        let diffs: Vec<Money> = self._actual_months().map(|m| m.difference()).collect();
+        Self::_get_median_money(&diffs)
     }
 
-    /// will return Err(()) if there is no data to calculate a median of
-    pub fn get_median_percentage_100(&self) -> Result<u16, ()>
+    /// will return None if there is no data to calculate a median of, planned months are not included
+    pub fn get_median_percentage_100(&self) -> Option<u16>
     {
         // this is not really that nice, but this way the _get_median_f64 doesnt have to be written twice
-        let percentages: Vec<f64> = self.months.iter().map(|m| m.percentage_100() as f64).collect();
-        match Self::_get_median_f64(&percentages) {
-            Ok(v) => return Ok(v as u16),
-            Err(_) => return Err(()),
+        let percentages: Vec<f64> = self._actual_months().map(|m| m.percentage_100() as f64).collect();
+        return Self::_get_median_f64(&percentages).map(|v| v as u16);
+    }
+
+    /// All months that are not only planned
+    fn _actual_months(&self) -> impl Iterator<Item = &AccountingMonth> { self.months.iter().filter(|m| !m.is_planned()) }
+
+    /// Same as `_get_median_f64()`, the average of the two middle values is rounded to whole cents
+    fn _get_median_money(values: &[Money]) -> Option<Money>
+    {
+        let mut vec: Vec<Money> = values.iter().filter(|m| m.is_positive()).copied().collect(); // remove all 0's
+        vec.sort();
+
+        let len = vec.len();
+        return match len {
+            0 => None,
+            _ if len % 2 == 0 => (vec[len / 2 - 1] + vec[len / 2]).div_rounded(2),
+            _ => Some(vec[len / 2]),
         };
     }
 
-    /// will return Err(()) if there is no data to calculate a median of
-    fn _get_median_f64(vec_f64: &Vec<f64>) -> Result<f64, ()>
+    /// will return None if there is no data to calculate a median of
+    fn _get_median_f64(vec_f64: &Vec<f64>) -> Option<f64>
     {
         let mut vec: Vec<f64> = vec_f64.iter().filter(|&m| m > &0.0).map(|v| v.to_owned()).collect(); // remove all 0's
 
         let len = vec.len();
         match len {
-            0 => return Err(()),
-            1 => return Ok(vec.get(0).unwrap().to_owned()),
+            0 => return None,
+            1 => return Some(vec.first().unwrap().to_owned()),
             _ => (),
         }
 
@@ -109,9 +214,9 @@ impl AccountingYear
             0 => {
                 let before_mid = vec.get(len / 2 - 1).unwrap();
                 let after_mid = vec.get(len / 2).unwrap();
-                return Ok((before_mid + after_mid) / 2.0);
+                return Some((before_mid + after_mid) / 2.0);
             }
-            _ => Ok(vec.get(len / 2).unwrap().to_owned()), // if length is odd, element in middle is median
+            _ => Some(vec.get(len / 2).unwrap().to_owned()), // if length is odd, element in middle is median
         }
     }
 }
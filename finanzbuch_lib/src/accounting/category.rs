@@ -0,0 +1,120 @@
+use crate::Error;
+use crate::Money;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Separates the levels of a category path: `Wohnen > Miete`
+pub const CATEGORY_SEPARATOR: &str = " > ";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+pub enum CategoryKind
+{
+    Income,
+    Expense,
+}
+impl std::fmt::Display for CategoryKind
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self {
+            CategoryKind::Income => write!(f, "Income"),
+            CategoryKind::Expense => write!(f, "Expense"),
+        }
+    }
+}
+
+/// A user defined category for ledger transactions.
+///
+/// Categories are hierarchical, the path contains all parents: `Wohnen > Miete` is a child of `Wohnen`
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Category
+{
+    path: String,
+    pub kind: CategoryKind,
+    /// Per month, always positive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    budget: Option<Money>,
+}
+impl Category
+{
+    /// The path is normalized, see `normalize_path()`
+    pub fn new(path: &str, kind: CategoryKind) -> Result<Self, Error>
+    {
+        return Ok(Self {
+            path: Self::normalize_path(path)?,
+            kind,
+            budget: None,
+        });
+    }
+
+    /// - Levels can be separated by `>` with or without spaces: `Wohnen>Miete` becomes `Wohnen > Miete`
+    /// - Returns `Err(InvalidCategory)` if the path or one of its levels is empty
+    pub fn normalize_path(path: &str) -> Result<String, Error>
+    {
+        let levels: Vec<&str> = path.split('>').map(|l| l.trim()).collect();
+        if levels.iter().any(|l| l.is_empty()) {
+            return Err(Error::InvalidCategory(format!("{path:?} contains an empty level")));
+        }
+        return Ok(levels.join(CATEGORY_SEPARATOR));
+    }
+
+    pub fn path(&self) -> &str { self.path.as_ref() }
+
+    /// Last level of the path
+    pub fn name(&self) -> &str { self.path.rsplit(CATEGORY_SEPARATOR).next().unwrap_or_default() }
+
+    /// `None` for top level categories
+    pub fn parent(&self) -> Option<&str> { self.path.rsplit_once(CATEGORY_SEPARATOR).map(|(parent, _)| parent) }
+
+    /// `true` if `path` is this category or one of its children (normalized path expected)
+    pub fn contains(&self, path: &str) -> bool
+    {
+        return path == self.path || (path.starts_with(&self.path) && path[self.path.len()..].starts_with(CATEGORY_SEPARATOR));
+    }
+
+    pub fn budget(&self) -> Option<Money> { self.budget }
+
+    /// Absolute value will be stored
+    pub fn set_budget(&mut self, budget: Option<Money>) { self.budget = budget.map(|b| b.abs()); }
+}
+
+/// Actual amounts of one category (including its children) in one year, compared with its budget
+#[derive(Debug, PartialEq, Clone)]
+pub struct CategoryReport
+{
+    pub path: String,
+    pub kind: CategoryKind,
+    /// Per month
+    pub budget: Option<Money>,
+    /// Spent for expense categories, received for income categories. Refunds reduce the spent amount
+    pub months: [Money; 12],
+}
+impl CategoryReport
+{
+    pub fn sum(&self) -> Money { self.months.iter().sum() }
+
+    pub fn budget_year(&self) -> Option<Money> { self.budget.map(|b| Money::from_cents(b.cents() * 12)) }
+
+    /// Budget minus actual amount, negative if more was spent than planned (or less was received)
+    pub fn remaining(&self, month_nr: u8) -> Option<Money>
+    {
+        let actual = *self.months.get((month_nr as usize).checked_sub(1)?)?;
+        return self.budget.map(|budget| match self.kind {
+            CategoryKind::Expense => budget - actual,
+            CategoryKind::Income => actual - budget,
+        });
+    }
+
+    /// `true` if more was spent than budgeted, or less was received. Always `false` without a budget
+    pub fn misses_budget(&self, month_nr: u8) -> bool { self.remaining(month_nr).is_some_and(|r| r.is_negative()) }
+
+    /// `true` if the sum of the year misses the budget of the year
+    pub fn misses_budget_year(&self) -> bool
+    {
+        return match (self.budget_year(), self.kind) {
+            (Some(budget), CategoryKind::Expense) => self.sum() > budget,
+            (Some(budget), CategoryKind::Income) => self.sum() < budget,
+            (None, _) => false,
+        };
+    }
+}
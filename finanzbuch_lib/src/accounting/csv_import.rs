@@ -0,0 +1,217 @@
+use crate::accounting::ledger::LedgerTransaction;
+use crate::accounting::Accounting;
+use crate::fast_date::FastDate;
+use crate::get_csv_contents_with_header;
+use crate::locale::Locale;
+use crate::AccountingMonth;
+use crate::Error;
+use crate::Money;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Which columns of the bank statement contain what. Columns are found by their name in the header line.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ColumnMapping
+{
+    pub date: String,
+    pub amount: String,
+    pub purpose: String,
+    /// Format of the date column, see `chrono::format::strftime`
+    pub date_format: String,
+    /// How the amounts are written
+    pub locale: Locale,
+}
+impl Default for ColumnMapping
+{
+    /// Column names as they are used by most german banks
+    fn default() -> Self
+    {
+        return Self {
+            date: String::from("Buchungstag"),
+            amount: String::from("Betrag"),
+            purpose: String::from("Verwendungszweck"),
+            date_format: String::from("%d.%m.%Y"),
+            locale: Locale::DeDe,
+        };
+    }
+}
+
+/// One line of the bank statement
+#[derive(Debug, PartialEq, Clone)]
+pub struct Transaction
+{
+    pub date: FastDate,
+    /// Positive is income, negative is an expense
+    pub amount: Money,
+    pub purpose: String,
+}
+
+/// Sum of all transactions of one month
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportedMonth
+{
+    pub year: u16,
+    pub month: u8,
+    pub income: Money,
+    pub expenses: Money,
+    pub transactions: Vec<Transaction>,
+}
+
+/// What `apply()` would do to one month, so that it can be shown to the user before anything is changed
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportPreview
+{
+    pub year: u16,
+    pub new: AccountingMonth,
+    /// The current content of this month, if it contains anything that would be overwritten
+    pub overwritten: Option<AccountingMonth>,
+}
+
+/// - Reads all transactions of a semicolon separated bank statement
+/// - Amounts are read in the format of `mapping.locale`, eg. `-1.234,56` for de-DE
+/// - Empty lines are skipped, any other line that cannot be read returns an error with its line number
+pub fn read_transactions(path: &PathBuf, mapping: &ColumnMapping) -> Result<Vec<Transaction>, Error>
+{
+    let mut lines = get_csv_contents_with_header(path)?.into_iter();
+    let header = lines.next().unwrap_or_default();
+
+    let date_col = _column_index(path, &header, &mapping.date)?;
+    let amount_col = _column_index(path, &header, &mapping.amount)?;
+    let purpose_col = _column_index(path, &header, &mapping.purpose)?;
+
+    let mut transactions: Vec<Transaction> = Vec::new();
+    for (index, cells) in lines.enumerate() {
+        let line_nr = index + 2; // header is line 1
+        if cells.iter().all(|c| c.trim().is_empty()) {
+            continue;
+        }
+
+        let cell = |col: usize| cells.get(col).map(|c| c.trim()).unwrap_or_default();
+        let parse_error = |message: String| Error::Parse {
+            path: path.clone(),
+            line: Some(line_nr),
+            column: None,
+            message,
+        };
+
+        let date = match NaiveDate::parse_from_str(cell(date_col), &mapping.date_format) {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(parse_error(format!(
+                    "{:?} is not a date like {}: {e}",
+                    cell(date_col),
+                    mapping.date_format
+                )))
+            }
+        };
+        let date = FastDate::new(date.year() as u16, date.month() as u8, date.day() as u8)?;
+
+        if cell(amount_col).is_empty() {
+            return Err(parse_error(String::from("the amount is missing")));
+        }
+        let amount = mapping.locale.parse_money(cell(amount_col)).map_err(|e| parse_error(e.to_string()))?;
+
+        transactions.push(Transaction {
+            date,
+            amount,
+            purpose: String::from(cell(purpose_col)),
+        });
+    }
+
+    return Ok(transactions);
+}
+
+/// Sums up the transactions of each month, sorted by date
+pub fn aggregate(transactions: &[Transaction]) -> Vec<ImportedMonth>
+{
+    let mut months: BTreeMap<(u16, u8), ImportedMonth> = BTreeMap::new();
+
+    for transaction in transactions {
+        let (year, month) = (transaction.date.year(), transaction.date.month());
+        let imported = months.entry((year, month)).or_insert_with(|| ImportedMonth {
+            year,
+            month,
+            income: Money::ZERO,
+            expenses: Money::ZERO,
+            transactions: vec![],
+        });
+
+        match transaction.amount.is_negative() {
+            false => imported.income += transaction.amount,
+            true => imported.expenses += transaction.amount.abs(),
+        }
+        imported.transactions.push(transaction.clone());
+    }
+
+    return months.into_values().collect();
+}
+
+/// Dry run of `apply()`, nothing is changed
+pub fn preview(accounting: &Accounting, imported: &[ImportedMonth]) -> Vec<ImportPreview>
+{
+    let mut previews: Vec<ImportPreview> = Vec::new();
+
+    for month in imported {
+        let existing = accounting.history.get(&month.year).map(|y| &y.months[month.month as usize - 1]);
+
+        // the note was written by the user, it is kept
+        let note = existing.map(|m| String::from(m.note())).unwrap_or_default();
+        let Ok(mut new) = AccountingMonth::new(month.month, month.income, month.expenses, note) else {
+            continue; // month comes from a FastDate, so this cant happen
+        };
+        let ledger = month.transactions.iter().map(_to_ledger).collect();
+        if new.set_transactions(ledger).is_err() {
+            continue; // aggregate() sorted them into this month
+        }
+
+        previews.push(ImportPreview {
+            year: month.year,
+            new,
+            overwritten: existing.filter(|m| !m.is_default()).cloned(),
+        });
+    }
+
+    return previews;
+}
+
+/// Overwrites income, expenses and transactions of every month that is contained in `imported`.
+///
+/// Years that do not exist yet are created, notes of existing months are kept.
+pub fn apply(accounting: &mut Accounting, imported: &[ImportedMonth]) -> Vec<ImportPreview>
+{
+    let previews = preview(accounting, imported);
+    for p in previews.iter() {
+        accounting.add_or_get_year(p.year).insert_or_overwrite_month(p.new.clone());
+    }
+    return previews;
+}
+
+// ================================================== Private ================================================== //
+
+fn _to_ledger(transaction: &Transaction) -> LedgerTransaction
+{
+    return LedgerTransaction::new(
+        transaction.date.clone(),
+        transaction.amount,
+        String::new(),
+        transaction.purpose.clone(),
+        String::new(),
+    );
+}
+
+fn _column_index(path: &Path, header: &[String], name: &str) -> Result<usize, Error>
+{
+    // some banks start the file with a byte order mark
+    return match header.iter().position(|h| h.trim_start_matches('\u{feff}').trim() == name) {
+        Some(index) => Ok(index),
+        None => Err(Error::Parse {
+            path: path.to_path_buf(),
+            line: Some(1),
+            column: None,
+            message: format!("there is no column called {name:?}, the columns are: {}", header.join(", ")),
+        }),
+    };
+}
@@ -0,0 +1,106 @@
+use crate::Money;
+
+/// How a month or year did compared to `Accounting.goal`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct GoalResult
+{
+    pub reached: bool,
+    /// Money that could still have been spent, negative if the goal was missed
+    pub margin: Money,
+    /// Same as `margin`, in percent of the income (1.0 = 100%). `None` without income
+    pub margin_percentage: Option<f64>,
+}
+impl GoalResult
+{
+    /// `None` if there is neither income nor expenses, because there is nothing to evaluate
+    pub fn new(goal: f64, income: Money, expenses: Money) -> Option<Self>
+    {
+        if income.is_zero() && expenses.is_zero() {
+            return None;
+        }
+
+        let margin = income.mul_f64(goal) - expenses;
+        return Some(Self {
+            reached: !margin.is_negative(),
+            margin,
+            margin_percentage: income.is_positive().then(|| goal - expenses.to_f64() / income.to_f64()),
+        });
+    }
+}
+
+/// Consecutive months that reached the goal. Months without data break a streak,
+/// except for the months after the last month with data
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct GoalStreaks
+{
+    /// Streak that includes the last month with data
+    pub current: u16,
+    pub longest: u16,
+    /// Year and month where the longest streak ended
+    pub longest_end: Option<(u16, u8)>,
+}
+impl GoalStreaks
+{
+    /// `months` have to be sorted by year and month
+    pub fn new(months: impl IntoIterator<Item = ((u16, u8), Option<GoalResult>)>) -> Self
+    {
+        let mut months: Vec<((u16, u8), Option<GoalResult>)> = months.into_iter().collect();
+        let last_with_data = months.iter().rposition(|(_, result)| result.is_some());
+        months.truncate(last_with_data.map_or(0, |index| index + 1));
+
+        let mut streaks = Self::default();
+        let mut running: u16 = 0;
+        for (year_month, result) in months {
+            match result.is_some_and(|r| r.reached) {
+                true => running += 1,
+                false => running = 0,
+            }
+            if running > streaks.longest {
+                streaks.longest = running;
+                streaks.longest_end = Some(year_month);
+            }
+        }
+        streaks.current = running;
+        return streaks;
+    }
+}
+
+/// One row of `YearSummary`
+#[derive(Debug, PartialEq, Clone)]
+pub struct MonthSummary
+{
+    pub month: u8,
+    pub income: Money,
+    pub expenses: Money,
+    pub difference: Money,
+    /// Expenses in percent of the income (1.0 = 100%), `None` without income
+    pub percentage: Option<f64>,
+    /// `None` for months without data
+    pub goal: Option<GoalResult>,
+}
+
+/// Everything the overview of one accounting year shows
+#[derive(Debug, PartialEq, Clone)]
+pub struct YearSummary
+{
+    pub year: u16,
+    /// The goal the year was evaluated with, 1.0 = 100%
+    pub goal_percentage: f64,
+    pub months: [MonthSummary; 12],
+
+    pub sum_income: Money,
+    pub sum_expenses: Money,
+    pub difference: Money,
+    pub percentage: Option<f64>,
+    /// Medians only contain months with data, `None` if there are none
+    pub median_income: Option<Money>,
+    pub median_expenses: Option<Money>,
+    pub median_difference: Option<Money>,
+
+    /// Goal of the whole year, measured with the sums
+    pub goal: Option<GoalResult>,
+    pub months_reached: u8,
+    pub months_missed: u8,
+    /// Longest streak inside this year
+    pub longest_streak: u8,
+}
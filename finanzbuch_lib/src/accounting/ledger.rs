@@ -0,0 +1,38 @@
+use crate::fast_date::FastDate;
+use crate::Money;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// One booking of an `AccountingMonth`
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct LedgerTransaction
+{
+    pub date: FastDate,
+    /// Positive is income, negative is an expense
+    amount: Money,
+    #[serde(default)]
+    pub counterparty: String,
+    #[serde(default)]
+    pub description: String,
+    /// Empty if the transaction has no category
+    #[serde(default)]
+    pub category: String,
+}
+impl LedgerTransaction
+{
+    pub fn new(date: FastDate, amount: Money, counterparty: String, description: String, category: String) -> Self
+    {
+        return Self {
+            date,
+            amount,
+            counterparty,
+            description,
+            category,
+        };
+    }
+
+    pub fn amount(&self) -> Money { self.amount }
+    pub fn set_amount(&mut self, amount: Money) { self.amount = amount; }
+
+    pub fn is_income(&self) -> bool { !self.amount.is_negative() }
+}
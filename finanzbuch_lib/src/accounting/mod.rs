@@ -1,8 +1,23 @@
 pub mod accounting_month;
 pub mod accounting_year;
+pub mod category;
+pub mod csv_import;
+pub mod goal;
+pub mod ledger;
+pub mod projection;
 pub mod recurrence;
 
+use crate::accounting::accounting_month::AccountingMonth;
 use crate::accounting::accounting_year::AccountingYear;
+use crate::accounting::category::Category;
+use crate::accounting::category::CategoryKind;
+use crate::accounting::goal::GoalStreaks;
+use crate::accounting::goal::YearSummary;
+use crate::accounting::projection::ProjectedMonth;
+use crate::accounting::projection::ProjectionComparison;
+use crate::currency::Currency;
+use crate::Error;
+use crate::Money;
 
 use serde::Deserialize;
 use serde::Serialize;
@@ -20,6 +35,26 @@ pub struct Accounting
     // Check if the BTreeMap Key can be used instead of Year.year_nr
     pub recurring_income: Vec<RecurringInOut>,
     pub recurring_expenses: Vec<RecurringInOut>,
+    /// Sorted by path, parents always exist. Use `add_category()` and `remove_category()` to keep it that way
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<Category>,
+    /// If `true`, years created by `add_or_get_year()` start with the planned values of the recurring items
+    #[serde(default)]
+    pub prefill_from_recurring: bool,
+    /// Currency of all amounts in the accounting history
+    #[serde(default, skip_serializing_if = "Currency::is_default")]
+    pub currency: Currency,
+}
+
+/// A month that spent more of its income than `Accounting.goal` allows
+#[derive(Debug, PartialEq, Clone)]
+pub struct GoalBreach
+{
+    pub month: u8,
+    /// 1.0 = 100%, `None` if the month had expenses but no income
+    pub percentage: Option<f64>,
+    /// Expense categories that spent more than their budget in this month
+    pub over_budget: Vec<String>,
 }
 impl Accounting
 {
@@ -30,10 +65,14 @@ impl Accounting
             history: BTreeMap::new(),
             recurring_income: vec![],
             recurring_expenses: vec![],
+            categories: vec![],
+            prefill_from_recurring: false,
+            currency: Currency::default(),
         };
     }
 
     /// - if the year does not already exist, adds it to `DataFile.years` with default values
+    ///   (or planned values, see `prefill_from_recurring`)
     /// - changes nothing if the year exists
     /// - returns the year as a mutable reference (`&mut Year`)`
     ///   - this allows function chaining: `DataFile.add_or_get_year().function_on_year()`
@@ -41,6 +80,9 @@ impl Accounting
     {
         if self.history.contains_key(&year_nr) == false {
             self.history.insert(year_nr, AccountingYear::default(year_nr));
+            if self.prefill_from_recurring {
+                self.fill_planned_months(year_nr);
+            }
         }
 
         match self.history.get_mut(&year_nr) {
@@ -48,4 +90,173 @@ impl Accounting
             None => panic!("The year {year_nr} was just created but could not be retrieved from HashMap"),
         };
     }
+
+    pub fn get_category(&self, path: &str) -> Option<&Category>
+    {
+        let path = Category::normalize_path(path).ok()?;
+        return self.categories.iter().find(|c| c.path() == path);
+    }
+
+    /// - The parent has to exist and be of the same kind
+    /// - Returns `Err(InvalidCategory)` if the path is invalid, already exists or the parent does not fit
+    pub fn add_category(&mut self, path: &str, kind: CategoryKind) -> Result<(), Error>
+    {
+        let category = Category::new(path, kind)?;
+        if self.get_category(category.path()).is_some() {
+            return Err(Error::InvalidCategory(format!("{} already exists", category.path())));
+        }
+        if let Some(parent_path) = category.parent() {
+            match self.get_category(parent_path) {
+                None => return Err(Error::InvalidCategory(format!("the parent {parent_path} does not exist"))),
+                Some(parent) if parent.kind != kind => {
+                    return Err(Error::InvalidCategory(format!("{parent_path} is a category for {}", parent.kind)))
+                }
+                Some(_) => (),
+            }
+        }
+
+        let index = self.categories.partition_point(|c| c.path() < category.path());
+        self.categories.insert(index, category);
+        return Ok(());
+    }
+
+    /// Removes the category and all of its children. Transactions keep their category and count as uncategorized
+    pub fn remove_category(&mut self, path: &str) -> Result<(), Error>
+    {
+        let Some(category) = self.get_category(path).cloned() else {
+            return Err(Error::UnknownCategory(String::from(path)));
+        };
+        self.categories.retain(|c| !category.contains(c.path()));
+        return Ok(());
+    }
+
+    /// Monthly budget, `None` removes it
+    pub fn set_category_budget(&mut self, path: &str, budget: Option<Money>) -> Result<(), Error>
+    {
+        let path = Category::normalize_path(path)?;
+        let Some(category) = self.categories.iter_mut().find(|c| c.path() == path) else {
+            return Err(Error::UnknownCategory(path));
+        };
+        category.set_budget(budget);
+        return Ok(());
+    }
+
+    /// Streaks over all years, a missing year breaks the streak
+    pub fn get_goal_streaks(&self) -> GoalStreaks
+    {
+        let (Some(first), Some(last)) = (self.history.keys().next(), self.history.keys().last()) else {
+            return GoalStreaks::default();
+        };
+
+        let mut months = Vec::new();
+        for year_nr in *first..=*last {
+            for month_nr in 1..=12 {
+                let result = self
+                    .history
+                    .get(&year_nr)
+                    .and_then(|y| y.months[month_nr as usize - 1].evaluate_goal(self.goal));
+                months.push(((year_nr, month_nr), result));
+            }
+        }
+        return GoalStreaks::new(months);
+    }
+
+    /// Sums, medians and goal status of one year, `None` if the year does not exist
+    pub fn get_year_summary(&self, year_nr: u16) -> Option<YearSummary> { self.history.get(&year_nr).map(|y| y.get_summary(self.goal)) }
+
+    /// Months of the year that missed the goal, with the expense categories that were over budget in that month
+    pub fn get_goal_breaches(&self, year_nr: u16) -> Vec<GoalBreach>
+    {
+        let Some(year) = self.history.get(&year_nr) else {
+            return vec![];
+        };
+        let reports = year.get_category_reports(&self.categories);
+
+        let mut breaches: Vec<GoalBreach> = Vec::new();
+        for month in year.months.iter() {
+            if month.evaluate_goal(self.goal).map_or(true, |g| g.reached) {
+                continue;
+            }
+            breaches.push(GoalBreach {
+                month: month.month_nr(),
+                percentage: month.income().is_positive().then(|| month.percentage_1()),
+                over_budget: reports
+                    .iter()
+                    .filter(|r| r.kind == CategoryKind::Expense && r.misses_budget(month.month_nr()))
+                    .map(|r| r.path.clone())
+                    .collect(),
+            });
+        }
+        return breaches;
+    }
+
+    /// Sum of all recurring income, as average per month with the current quantities. Ignores start and end
+    pub fn get_recurring_income_per_month(&self) -> f64 { self.recurring_income.iter().map(|r| r.per_month()).sum() }
+
+    /// Sum of all recurring expenses, as average per month with the current quantities. Ignores start and end
+    pub fn get_recurring_expenses_per_month(&self) -> f64 { self.recurring_expenses.iter().map(|r| r.per_month()).sum() }
+
+    /// Expected income and expenses of every month from `first` to `last` (both included), from the recurring items.
+    /// Items only count in months they are valid in, with the quantity of that time (see `RecurringInOut::amount_in_month()`)
+    pub fn project(&self, first: (u16, u8), last: (u16, u8)) -> Result<Vec<ProjectedMonth>, Error>
+    {
+        let sum = |items: &[RecurringInOut], year: u16, month: u8| Money::from_f64(items.iter().map(|r| r.amount_in_month(year, month)).sum());
+
+        return Ok(projection::month_range(first, last)?
+            .into_iter()
+            .map(|(year, month)| ProjectedMonth {
+                year,
+                month,
+                income: sum(&self.recurring_income, year, month),
+                expenses: sum(&self.recurring_expenses, year, month),
+            })
+            .collect());
+    }
+
+    /// Fills every month of the year that is empty or still planned with the projection of the recurring items.
+    /// Months that were changed by the user are not touched. Creates the year if it does not exist.
+    /// Returns how many months were filled
+    pub fn fill_planned_months(&mut self, year_nr: u16) -> u8
+    {
+        let Ok(projected) = self.project((year_nr, 1), (year_nr, 12)) else {
+            return 0; // cant happen, the months are valid
+        };
+
+        let year = self.history.entry(year_nr).or_insert_with(|| AccountingYear::default(year_nr));
+        let mut filled: u8 = 0;
+        for (month, projected) in year.months.iter_mut().zip(projected) {
+            if !month.is_default() && !month.is_planned() {
+                continue;
+            }
+
+            let note = String::from(month.note());
+            *month = match (projected.income, projected.expenses) {
+                (Money::ZERO, Money::ZERO) => AccountingMonth::default(projected.month).unwrap_or(month.clone()),
+                (income, expenses) => AccountingMonth::new_planned(projected.month, income, expenses).unwrap_or(month.clone()),
+            };
+            month.set_note(note);
+            filled += 1;
+        }
+        return filled;
+    }
+
+    /// `project()` next to the values of the `AccountingMonth`s
+    pub fn compare_with_projection(&self, first: (u16, u8), last: (u16, u8)) -> Result<Vec<ProjectionComparison>, Error>
+    {
+        let mut comparisons: Vec<ProjectionComparison> = Vec::new();
+        for projected in self.project(first, last)? {
+            let actual = self
+                .history
+                .get(&projected.year)
+                .map(|y| &y.months[projected.month as usize - 1])
+                .filter(|m| !m.is_planned() && (!m.income().is_zero() || !m.expenses().is_zero()));
+
+            comparisons.push(ProjectionComparison {
+                actual_income: actual.map(|m| m.income()),
+                actual_expenses: actual.map(|m| m.expenses()),
+                projected,
+            });
+        }
+        return Ok(comparisons);
+    }
 }
@@ -0,0 +1,55 @@
+use crate::Error;
+use crate::Money;
+
+/// Expected income and expenses of one month, from the recurring items
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProjectedMonth
+{
+    pub year: u16,
+    pub month: u8,
+    pub income: Money,
+    pub expenses: Money,
+}
+
+/// Projection and actual values of one month
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProjectionComparison
+{
+    pub projected: ProjectedMonth,
+    /// `None` if the month has no data yet
+    pub actual_income: Option<Money>,
+    pub actual_expenses: Option<Money>,
+}
+impl ProjectionComparison
+{
+    /// Actual minus projected income, positive if more was received than expected
+    pub fn income_deviation(&self) -> Option<Money> { self.actual_income.map(|a| a - self.projected.income) }
+
+    /// Actual minus projected expenses, positive if more was spent than expected
+    pub fn expenses_deviation(&self) -> Option<Money> { self.actual_expenses.map(|a| a - self.projected.expenses) }
+}
+
+/// All months from `first` to `last`, both included, as `(year, month)`.
+/// Returns `Err(InvalidMonth)` if one of the months is not 1-12. Empty if `last` is before `first`
+pub fn month_range(first: (u16, u8), last: (u16, u8)) -> Result<Vec<(u16, u8)>, Error>
+{
+    for (_, month) in [first, last] {
+        if month == 0 || month > 12 {
+            return Err(Error::InvalidMonth(month));
+        }
+    }
+
+    let mut months: Vec<(u16, u8)> = Vec::new();
+    let (mut year, mut month) = first;
+    while (year, month) <= last {
+        months.push((year, month));
+        (year, month) = match month {
+            12 => match year.checked_add(1) {
+                Some(next) => (next, 1),
+                None => break,
+            },
+            m => (year, m + 1),
+        };
+    }
+    return Ok(months);
+}
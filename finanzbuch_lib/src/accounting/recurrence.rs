@@ -1,6 +1,12 @@
+use crate::fast_date::FastDate;
 use serde::Deserialize;
 use serde::Serialize;
 
+/// Leap years are ignored, the difference is too small to matter for a projection
+pub const DAYS_PER_YEAR: f64 = 365.0;
+/// `(52 * 7 + 1) / 7`
+pub const WEEKS_PER_YEAR: f64 = DAYS_PER_YEAR / 7.0;
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct RecurringInOut
 {
@@ -8,9 +14,7 @@ pub struct RecurringInOut
 
     /// always positive
     pub quantity: f64,
-    // TODO When calculating how much this would be per month, just use the standard year.
-    // weeks per year normally: ((52*7 + 1) / 7) weeks (I dont care about leap years, its such a small difference)
-    // Example: something that happens every 5 weeks:
+    // Example for `per_month()`, something that happens every 5 weeks:
     //  52,1428 / 5 = 10,4286
     //  (quantity * 10,4286) / 12 = per month
     pub recurrence: Recurrence,
@@ -20,6 +24,30 @@ pub struct RecurringInOut
 
     /// how often does this happen per interval
     pub frequency: u16, // not u8, so that 365 days are possible
+
+    /// First day this is valid, `None` if it was always valid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<FastDate>,
+    /// Last day this is valid, `None` if it does not end
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<FastDate>,
+    /// Day of the month it is booked on, only used for monthly and yearly recurrences.
+    /// Yearly ones are booked in the month of `start` (January without `start`).
+    /// Months that are shorter use their last day
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor_day: Option<u8>,
+    /// Changes of `quantity` over time, sorted by date. `quantity` is valid until the first change
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<QuantityChange>,
+}
+
+/// From `from` on, `quantity` is used instead of the previous one
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct QuantityChange
+{
+    pub from: FastDate,
+    /// always positive
+    pub quantity: f64,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -30,3 +58,126 @@ pub enum Recurrence
     Month,
     Year,
 }
+impl Recurrence
+{
+    /// How often one unit of this recurrence fits into a year
+    pub fn per_year(&self) -> f64
+    {
+        return match self {
+            Recurrence::Day => DAYS_PER_YEAR,
+            Recurrence::Week => WEEKS_PER_YEAR,
+            Recurrence::Month => 12.0,
+            Recurrence::Year => 1.0,
+        };
+    }
+}
+
+impl RecurringInOut
+{
+    /// Valid forever, without anchor day and changes
+    pub fn new(name: &str, quantity: f64, recurrence: Recurrence, interval: u16, frequency: u16) -> Self
+    {
+        return Self {
+            name: String::from(name),
+            quantity: quantity.abs(),
+            recurrence,
+            interval,
+            frequency,
+            start: None,
+            end: None,
+            anchor_day: None,
+            changes: vec![],
+        };
+    }
+
+    /// `false` before `start` and after `end`
+    pub fn is_valid_on(&self, date: &FastDate) -> bool
+    {
+        return self.start.as_ref().map_or(true, |start| date >= start) && self.end.as_ref().map_or(true, |end| date <= end);
+    }
+
+    /// Quantity of the last change before or on `date`
+    pub fn quantity_on(&self, date: &FastDate) -> f64
+    {
+        return match self.changes.iter().rev().find(|c| c.from <= *date) {
+            Some(change) => change.quantity.abs(),
+            None => self.quantity.abs(),
+        };
+    }
+
+    /// Quantity of the newest change, or `quantity` if there is none
+    pub fn current_quantity(&self) -> f64 { self.changes.last().map_or(self.quantity, |c| c.quantity).abs() }
+
+    /// A change on the same day as an existing one replaces it
+    pub fn add_change(&mut self, from: FastDate, quantity: f64)
+    {
+        self.changes.retain(|c| c.from != from);
+        let index = self.changes.partition_point(|c| c.from < from);
+        self.changes.insert(
+            index,
+            QuantityChange {
+                from,
+                quantity: quantity.abs(),
+            },
+        );
+    }
+
+    /// Expected amount in one month, respecting `start`, `end`, `anchor_day` and `changes`:
+    /// - With an anchor day, monthly and yearly recurrences are booked in full on that day, if it is valid
+    /// - Everything else is spread evenly over the days of the month, so partially valid months get a part of it
+    pub fn amount_in_month(&self, year: u16, month: u8) -> f64
+    {
+        let days = FastDate::days_in_month(year, month);
+        if days == 0 || self.interval == 0 {
+            return 0.0;
+        }
+
+        if let (Some(anchor_day), Recurrence::Month | Recurrence::Year) = (self.anchor_day, &self.recurrence) {
+            let date = FastDate::new_risky(year, month, anchor_day.clamp(1, days));
+            if !self.is_valid_on(&date) || !self._is_booking_month(year, month) {
+                return 0.0;
+            }
+            return self.quantity_on(&date) * self.frequency as f64;
+        }
+
+        // sum of the quantities of all valid days, divided only once to stay exact for whole months
+        let mut quantity_days: f64 = 0.0;
+        for day in 1..=days {
+            let date = FastDate::new_risky(year, month, day);
+            if self.is_valid_on(&date) {
+                quantity_days += self.quantity_on(&date);
+            }
+        }
+        return quantity_days / days as f64 * self.occurrences_per_year() / 12.0;
+    }
+
+    /// How often this happens in an average year. 0 if `interval` is 0
+    pub fn occurrences_per_year(&self) -> f64
+    {
+        if self.interval == 0 {
+            return 0.0;
+        }
+        return self.recurrence.per_year() / self.interval as f64 * self.frequency as f64;
+    }
+
+    /// Average with the current quantity, ignores `start` and `end`. Not rounded, so that sums of several items stay exact
+    pub fn per_year(&self) -> f64 { self.current_quantity() * self.occurrences_per_year() }
+
+    /// Average with the current quantity, ignores `start` and `end`. Not rounded, so that sums of several items stay exact
+    pub fn per_month(&self) -> f64 { self.per_year() / 12.0 }
+
+    /// Only for anchored monthly and yearly recurrences: `true` if `interval` fits between the first booking and this month
+    fn _is_booking_month(&self, year: u16, month: u8) -> bool
+    {
+        let (first_year, first_month) = match &self.start {
+            Some(start) => (start.year(), start.month()),
+            None => (0, 1),
+        };
+        let months_since = (year as i64 * 12 + month as i64) - (first_year as i64 * 12 + first_month as i64);
+
+        return match self.recurrence {
+            Recurrence::Year => month == first_month && months_since.rem_euclid(12 * self.interval as i64) == 0,
+            _ => months_since.rem_euclid(self.interval as i64) == 0,
+        };
+    }
+}
@@ -0,0 +1,218 @@
+use crate::encryption;
+use crate::Error;
+use chrono::Duration;
+use chrono::Local;
+use chrono::NaiveDateTime;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Used in the name of the backup files, sorts the same way as the timestamp itself
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+const BACKUP_EXTENSION: &str = "bak";
+
+/// One backup of a data file, stored in the same directory as the data file:
+///
+/// `finanzbuch.yaml` -> `finanzbuch.yaml.20240102-225257.123.bak`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Backup
+{
+    pub path: PathBuf,
+    /// Local time at which the backup was created
+    pub created: NaiveDateTime,
+}
+
+/// All backups of the given data file, newest first
+///
+/// Returns an empty Vec if the directory of the data file does not exist
+pub fn list(datafile_path: &Path) -> Result<Vec<Backup>, Error>
+{
+    let dir = _parent_dir(datafile_path);
+    let prefix = format!("{}.", _file_name(datafile_path));
+    let suffix = format!(".{BACKUP_EXTENSION}");
+
+    let read_dir = match std::fs::read_dir(&dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(Error::io(&dir, e)),
+    };
+
+    let mut backups: Vec<Backup> = Vec::new();
+    for dir_entry in read_dir {
+        let dir_entry = match dir_entry {
+            Ok(d) => d,
+            Err(e) => return Err(Error::io(&dir, e)),
+        };
+        let file_name = dir_entry.file_name().to_string_lossy().to_string();
+
+        let Some(timestamp) = file_name.strip_prefix(&prefix).and_then(|n| n.strip_suffix(&suffix)) else {
+            continue;
+        };
+        // other files that just happen to have a similar name
+        let Ok(created) = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT) else {
+            continue;
+        };
+
+        backups.push(Backup {
+            path: dir_entry.path(),
+            created,
+        });
+    }
+
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created));
+    return Ok(backups);
+}
+
+/// Copies the current content of the data file into a new backup and deletes the oldest backups, so that only `keep` backups remain.
+///
+/// - Nothing is done if `keep` is 0
+/// - Returns `None` if there is no data file to create a backup of
+pub fn create(datafile_path: &Path, keep: u8) -> Result<Option<Backup>, Error>
+{
+    if keep == 0 {
+        return Ok(None);
+    }
+
+    let content = match std::fs::read(datafile_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::io(datafile_path, e)),
+    };
+
+    let created = Local::now().naive_local();
+    let path = _parent_dir(datafile_path).join(format!(
+        "{}.{}.{BACKUP_EXTENSION}",
+        _file_name(datafile_path),
+        created.format(TIMESTAMP_FORMAT)
+    ));
+    write_atomic(&path, &content)?;
+    // a backup should not be readable by more users than the data file itself
+    if let Ok(metadata) = std::fs::metadata(datafile_path) {
+        if let Err(e) = std::fs::set_permissions(&path, metadata.permissions()) {
+            return Err(Error::io(&path, e));
+        }
+    }
+
+    // rotate
+    for old in list(datafile_path)?.iter().skip(keep as usize) {
+        if let Err(e) = std::fs::remove_file(&old.path) {
+            return Err(Error::io(&old.path, e));
+        }
+    }
+
+    return Ok(Some(Backup { path, created }));
+}
+
+/// Same as `create()`, but only if the newest backup is older than `min_age`.
+///
+/// Data is written after every small change, so without this, all backups would only contain the last few changes.
+pub fn create_if_due(datafile_path: &Path, keep: u8, min_age: Duration) -> Result<Option<Backup>, Error>
+{
+    if let Some(newest) = list(datafile_path)?.first() {
+        if Local::now().naive_local() - newest.created < min_age {
+            return Ok(None);
+        }
+    }
+    return create(datafile_path, keep);
+}
+
+/// Replaces the data file with the content of the given backup.
+///
+/// The current content of the data file is saved as a new backup first, so restoring can be undone.
+/// Any `DataFile` that was read before has to be read again to see the restored content.
+pub fn restore(datafile_path: &Path, backup: &Backup, keep: u8) -> Result<(), Error>
+{
+    let content = match std::fs::read(&backup.path) {
+        Ok(content) => content,
+        Err(e) => return Err(Error::io(&backup.path, e)),
+    };
+
+    create(datafile_path, keep)?;
+    return write_atomic(datafile_path, &content);
+}
+
+/// Encrypts all backups of the data file that are still plain text, with a key derived from `passphrase`.
+///
+/// Backups are copies of the file on disk, so without this, the plain text of a data file would stay in its backups after switching to encryption.
+/// Returns the number of backups that were encrypted
+pub fn encrypt_plain(datafile_path: &Path, passphrase: &str) -> Result<usize, Error>
+{
+    let mut count = 0;
+    for backup in list(datafile_path)? {
+        let content = match std::fs::read(&backup.path) {
+            Ok(content) => content,
+            Err(e) => return Err(Error::io(&backup.path, e)),
+        };
+        if encryption::is_encrypted(&content) {
+            continue;
+        }
+
+        write_atomic(&backup.path, &encryption::encrypt(&content, passphrase)?)?;
+        count += 1;
+    }
+    return Ok(count);
+}
+
+/// Writes `content` into a temporary file next to `path` and then renames it to `path`.
+///
+/// Renaming is atomic, so `path` either contains its old or its new content, even if the program crashes or the disk is full.
+/// The permissions of an existing file at `path` are kept.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), Error>
+{
+    let tmp_path = _parent_dir(path).join(format!("{}.tmp", _file_name(path)));
+
+    let mut tmp_file = match File::create(&tmp_path) {
+        Ok(file) => file,
+        Err(e) => return Err(Error::io(&tmp_path, e)),
+    };
+
+    // the new file would otherwise get the default permissions, an only user readable file would become world readable
+    if let Ok(metadata) = std::fs::metadata(path) {
+        if let Err(e) = tmp_file.set_permissions(metadata.permissions()) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(Error::io(&tmp_path, e));
+        }
+    }
+
+    // sync_all() makes sure that the content is actually on the disk before the old file is replaced
+    if let Err(e) = tmp_file.write_all(content).and_then(|_| tmp_file.sync_all()) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(Error::io(&tmp_path, e));
+    }
+    drop(tmp_file);
+
+    if let Err(e) = std::fs::rename(&tmp_path, path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(Error::io(path, e));
+    }
+
+    // the rename itself is only persistent once the directory is synced
+    #[cfg(unix)]
+    {
+        let dir = _parent_dir(path);
+        if let Err(e) = File::open(&dir).and_then(|d| d.sync_all()) {
+            return Err(Error::io(&dir, e));
+        }
+    }
+
+    return Ok(());
+}
+
+// ================================================== Private ================================================== //
+
+fn _parent_dir(path: &Path) -> PathBuf
+{
+    return match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+}
+
+fn _file_name(path: &Path) -> String
+{
+    return match path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => String::new(),
+    };
+}
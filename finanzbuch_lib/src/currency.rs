@@ -0,0 +1,173 @@
+use crate::import_csv_lines;
+use crate::locale::Locale;
+use crate::Error;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Column names of an exchange rate CSV file, one rate per line: `Währung;Jahr;Monat;Kurs`
+pub const CSV_HEADER_CURRENCY: &str = "Währung";
+pub const CSV_HEADER_YEAR: &str = "Jahr";
+pub const CSV_HEADER_MONTH: &str = "Monat";
+pub const CSV_HEADER_RATE: &str = "Kurs";
+
+/// ISO 4217 code of a currency, eg. `EUR` or `USD`. Defaults to `EUR`
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+pub struct Currency(String);
+impl Default for Currency
+{
+    fn default() -> Self { return Self(String::from("EUR")); }
+}
+impl std::fmt::Display for Currency
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "{}", self.0) }
+}
+impl Currency
+{
+    /// Three letters, case does not matter: `usd` becomes `USD`
+    pub fn new(code: &str) -> Result<Self, Error>
+    {
+        let code = code.trim();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(Error::InvalidCurrency(String::from(code)));
+        }
+        return Ok(Self(code.to_ascii_uppercase()));
+    }
+
+    pub fn code(&self) -> &str { self.0.as_ref() }
+
+    /// The sign that is shown next to values, the code itself for currencies without a common sign
+    pub fn symbol(&self) -> &str
+    {
+        return match self.0.as_str() {
+            "EUR" => "€",
+            "USD" => "$",
+            "GBP" => "£",
+            "JPY" => "¥",
+            code => code,
+        };
+    }
+
+    pub fn is_default(&self) -> bool { return *self == Self::default(); }
+}
+
+/// Value of one unit of `currency` in `ExchangeRates.base`, valid from this month on
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ExchangeRate
+{
+    pub currency: Currency,
+    pub year: u16,
+    pub month: u8,
+    pub rate: f64,
+}
+
+/// Exchange rates per month, all quoted in `base`. They are only stored locally and have to be entered or imported by the user.
+///
+/// A rate stays valid until the next rate of the same currency, so it is not necessary to have one for every month.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+pub struct ExchangeRates
+{
+    pub base: Currency,
+    /// Sorted by currency, year and month. Use `set_rate()` and `remove_rate()` to keep it that way
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rates: Vec<ExchangeRate>,
+}
+impl ExchangeRates
+{
+    pub fn is_default(&self) -> bool { return *self == Self::default(); }
+
+    /// Adds the rate or replaces the rate of the same currency and month
+    pub fn set_rate(&mut self, currency: Currency, year: u16, month: u8, rate: f64) -> Result<(), Error>
+    {
+        if !(1..=12).contains(&month) {
+            return Err(Error::InvalidMonth(month));
+        }
+        if currency == self.base {
+            return Err(Error::InvalidExchangeRate(format!(
+                "{currency} is the base currency, its rate is always 1"
+            )));
+        }
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(Error::InvalidExchangeRate(format!("{rate} is not a valid rate, it has to be positive")));
+        }
+
+        let new_rate = ExchangeRate { currency, year, month, rate };
+        match self.rates.binary_search_by(|r| _sort_key(r).cmp(&_sort_key(&new_rate))) {
+            Ok(index) => self.rates[index] = new_rate,
+            Err(index) => self.rates.insert(index, new_rate),
+        }
+        return Ok(());
+    }
+
+    /// Returns the removed rate, `None` if there was no rate for this currency and month
+    pub fn remove_rate(&mut self, currency: &Currency, year: u16, month: u8) -> Option<ExchangeRate>
+    {
+        let index = self
+            .rates
+            .iter()
+            .position(|r| r.currency == *currency && r.year == year && r.month == month)?;
+        return Some(self.rates.remove(index));
+    }
+
+    /// Value of one unit of `currency` in `base`, using the newest rate at or before this month.
+    /// Returns `Err(MissingExchangeRate)` if there is none
+    pub fn rate(&self, currency: &Currency, year: u16, month: u8) -> Result<f64, Error>
+    {
+        if *currency == self.base {
+            return Ok(1.0);
+        }
+        return self
+            .rates
+            .iter()
+            .rev()
+            .find(|r| r.currency == *currency && (r.year, r.month) <= (year, month))
+            .map(|r| r.rate)
+            .ok_or_else(|| Error::MissingExchangeRate {
+                currency: currency.clone(),
+                year,
+                month,
+            });
+    }
+
+    /// Converts `amount` with the rates of this month. Not rounded, because prices can have more than two decimal places
+    pub fn convert(&self, amount: f64, from: &Currency, to: &Currency, year: u16, month: u8) -> Result<f64, Error>
+    {
+        if from == to {
+            return Ok(amount);
+        }
+        return Ok(amount * self.rate(from, year, month)? / self.rate(to, year, month)?);
+    }
+
+    /// Currencies that have at least one rate, sorted
+    pub fn currencies(&self) -> Vec<&Currency>
+    {
+        let mut currencies: Vec<&Currency> = self.rates.iter().map(|r| &r.currency).collect();
+        currencies.dedup();
+        return currencies;
+    }
+
+    /// Reads a semicolon separated file with the columns `Währung;Jahr;Monat;Kurs` (in any order) and sets all of its rates.
+    /// The rates are read in the format of `locale`.
+    ///
+    /// Nothing is changed if one of the lines is not valid. Returns the number of rates that were set
+    pub fn import_csv(&mut self, path: &PathBuf, locale: Locale) -> Result<usize, Error>
+    {
+        let mut imported = self.clone();
+        let columns = [CSV_HEADER_CURRENCY, CSV_HEADER_YEAR, CSV_HEADER_MONTH, CSV_HEADER_RATE];
+        let count = import_csv_lines(path, columns, |[currency, year, month, rate]| {
+            let currency = Currency::new(currency)?;
+            let year = year.parse::<u16>().map_err(|e| Error::InvalidExchangeRate(e.to_string()))?;
+            let month = month.parse::<u8>().map_err(|e| Error::InvalidExchangeRate(e.to_string()))?;
+            let rate = locale.parse_f64(rate)?;
+            return imported.set_rate(currency, year, month, rate);
+        })?;
+
+        *self = imported;
+        return Ok(count);
+    }
+}
+
+// ================================================== Private ================================================== //
+
+fn _sort_key(rate: &ExchangeRate) -> (&Currency, u16, u8) { (&rate.currency, rate.year, rate.month) }
@@ -1,32 +1,97 @@
 extern crate dirs;
 
+use crate::accounting::projection::month_range;
+use crate::backup;
+use crate::currency::Currency;
+use crate::currency::ExchangeRates;
+use crate::encryption;
+use crate::inflation::PriceIndex;
+use crate::inflation::RealAccountingMonth;
+use crate::investing::cost_basis::CostBasis;
+use crate::investing::cost_basis::CostBasisMethod;
+use crate::investing::inv_variant::InvestmentVariant;
+use crate::investing::performance;
+use crate::investing::performance::MonthValue;
+use crate::investing::performance::Performance;
+use crate::investing::running_costs;
+use crate::investing::running_costs::RunningCosts;
 use crate::investing::Investing;
+use crate::locale::Locale;
+use crate::migration;
+use crate::migration::MigrationReport;
+use crate::profiles::AppConfig;
+use crate::profiles::Selection;
 use crate::Accounting;
+use crate::DepotEntry;
+use crate::Error;
+use crate::Money;
+use chrono::Duration;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fs::OpenOptions;
 use std::io::Read;
-use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
-const FILENAME: &'static str = "finanzbuch.yaml";
+const FILENAME: &str = "finanzbuch.yaml";
 pub const FILE_VERSION: u8 = 4;
+const DEFAULT_BACKUP_COUNT: u8 = 5;
+/// A new backup is only created on write, if the newest one is at least this old
+const BACKUP_MIN_AGE_MINUTES: i64 = 15;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DataFile
 {
     /// One integer, just counting up. No x.y.z
     pub version: u8,
     pub accounting: Accounting,
     pub investing: Investing,
+    /// Shared by accounting and investing, only needed if they use more than one currency
+    #[serde(default, skip_serializing_if = "ExchangeRates::is_default")]
+    pub exchange_rates: ExchangeRates,
+    /// Consumer price index, only needed to show values in real terms
+    #[serde(default, skip_serializing_if = "PriceIndex::is_default")]
+    pub price_index: PriceIndex,
+    /// How numbers are entered and shown in the app, and the default for imported files
+    #[serde(default, skip_serializing_if = "Locale::is_default")]
+    pub locale: Locale,
     pub write_on_drop: bool,
+
+    /// How many backups of the previous file content are kept next to the data file. `0` disables backups
+    #[serde(default = "DataFile::default_backup_count")]
+    pub backup_count: u8,
+
+    /// The file this was read from, `write()` writes back into it. Not stored in the file itself
+    #[serde(skip)]
+    pub filepath: Option<PathBuf>,
+    /// Set by `read_encrypted()`, so that `write()` encrypts the file again. Not stored in the file itself
+    #[serde(skip)]
+    pub passphrase: Option<String>,
+}
+impl PartialEq for DataFile
+{
+    /// Only compares the content, not where it came from
+    fn eq(&self, other: &Self) -> bool
+    {
+        return self.version == other.version
+            && self.accounting == other.accounting
+            && self.investing == other.investing
+            && self.exchange_rates == other.exchange_rates
+            && self.price_index == other.price_index
+            && self.locale == other.locale
+            && self.write_on_drop == other.write_on_drop
+            && self.backup_count == other.backup_count;
+    }
 }
 impl Drop for DataFile
 {
     fn drop(&mut self)
     {
         if self.write_on_drop {
-            self.write();
+            // there is no way to return this error, so at least tell the user about it
+            if let Err(e) = self.write() {
+                eprintln!("Data could not be written when dropping DataFile: {e}");
+            }
         }
     }
 }
@@ -36,10 +101,16 @@ impl Default for DataFile
     fn default() -> Self
     {
         return Self {
-            version: 4,
+            version: FILE_VERSION,
             accounting: Accounting::default(),
             investing: Investing::default(),
+            exchange_rates: ExchangeRates::default(),
+            price_index: PriceIndex::default(),
+            locale: Locale::default(),
             write_on_drop: true,
+            backup_count: DEFAULT_BACKUP_COUNT,
+            filepath: None,
+            passphrase: None,
         };
     }
 }
@@ -51,88 +122,333 @@ impl DataFile
     pub fn default_no_write_on_drop() -> Self
     {
         return Self {
-            version: 4,
+            version: FILE_VERSION,
             accounting: Accounting::default(),
             investing: Investing::default(),
+            exchange_rates: ExchangeRates::default(),
+            price_index: PriceIndex::default(),
+            locale: Locale::default(),
             write_on_drop: false,
+            backup_count: DEFAULT_BACKUP_COUNT,
+            filepath: None,
+            passphrase: None,
         };
     }
 
+    pub fn default_backup_count() -> u8 { DEFAULT_BACKUP_COUNT }
+
+    /// Currency all sums of the depot overview are shown in
+    pub fn overview_currency(&self) -> &Currency { self.investing.overview_currency.as_ref().unwrap_or(&self.exchange_rates.base) }
+
+    /// Converts an amount of this entry into `overview_currency()`, with the exchange rate of this month
+    pub fn to_overview_currency(&self, entry: &DepotEntry, amount: f64, year: u16, month: u8) -> Result<f64, Error>
+    {
+        return self
+            .exchange_rates
+            .convert(amount, &entry.currency, self.overview_currency(), year, month);
+    }
+
+    /// `Depot::cost_basis()` with every entry converted into `overview_currency()`, with the exchange rate of this month
+    pub fn depot_cost_basis(&self, method: CostBasisMethod, year: u16, month: u8) -> Result<CostBasis, Error>
+    {
+        let mut sum = CostBasis::default();
+        for entry in self.investing.depot.entries.values() {
+            let cost_basis = entry.cost_basis(method, year, month);
+            let convert = |money: Money| -> Result<Money, Error> {
+                return Ok(Money::from_f64(self.to_overview_currency(entry, money.to_f64(), year, month)?));
+            };
+            sum += CostBasis {
+                invested: convert(cost_basis.invested)?,
+                current_value: convert(cost_basis.current_value)?,
+                realized_gain: convert(cost_basis.realized_gain)?,
+            };
+        }
+        return Ok(sum);
+    }
+
+    /// `Depot::performance()` with the values of every month converted into `overview_currency()`, with the exchange rate of that month
+    pub fn depot_performance(&self, variant: Option<&InvestmentVariant>, first: (u16, u8), last: (u16, u8)) -> Result<Performance, Error>
+    {
+        let values = self._converted_month_values(variant, first, last)?;
+        return Ok(performance::calculate(&values, first, last));
+    }
+
+    /// `Depot::prognosis()` with the values of every month converted into `overview_currency()`, with the exchange rate of that month
+    pub fn depot_prognosis(&self, growth_rate: u8, first: (u16, u8), last: (u16, u8)) -> Result<Vec<Money>, Error>
+    {
+        let values = self._converted_month_values(None, first, last)?;
+        return Ok(performance::prognosis(&values, growth_rate));
+    }
+
+    /// `Depot::running_costs()` with the values of every month converted into `overview_currency()`, with the exchange rate of that month
+    pub fn depot_running_costs(&self, first: (u16, u8), last: (u16, u8)) -> Result<RunningCosts, Error>
+    {
+        let mut all = Vec::new();
+        for entry in self.investing.depot.entries.values() {
+            let values = self._converted_entry_month_values(entry, first, last)?;
+            all.push(running_costs::calculate(entry, &values));
+        }
+        return Ok(running_costs::sum(all));
+    }
+
+    /// Value and cash flow of the whole depot in each month from `first` to `last`, converted into `overview_currency()`
+    /// and deflated into prices of `base`
+    pub fn real_depot_values(&self, first: (u16, u8), last: (u16, u8), base: (u16, u8)) -> Result<Vec<MonthValue>, Error>
+    {
+        let mut values = self._converted_month_values(None, first, last)?;
+        // the first one is the month before `first`
+        values.remove(0);
+        for v in values.iter_mut() {
+            v.value = self.price_index.deflate(v.value, v.year, v.month, base)?;
+            v.cash_flow = self.price_index.deflate(v.cash_flow, v.year, v.month, base)?;
+        }
+        return Ok(values);
+    }
+
+    /// Income and expenses of each month from `first` to `last`, deflated into prices of `base`.
+    /// Months without data have no income and expenses
+    pub fn real_accounting_months(&self, first: (u16, u8), last: (u16, u8), base: (u16, u8)) -> Result<Vec<RealAccountingMonth>, Error>
+    {
+        let mut months = Vec::new();
+        for (year, month) in month_range(first, last)? {
+            let (income, expenses) = match self.accounting.history.get(&year) {
+                Some(accounting_year) => {
+                    let accounting_month = &accounting_year.months[month as usize - 1];
+                    (accounting_month.income(), accounting_month.expenses())
+                }
+                None => (Money::ZERO, Money::ZERO),
+            };
+            months.push(RealAccountingMonth {
+                year,
+                month,
+                income: self.price_index.deflate(income, year, month, base)?,
+                expenses: self.price_index.deflate(expenses, year, month, base)?,
+            });
+        }
+        return Ok(months);
+    }
+
     /// Linux / MacOS: `/home/username/finanzbuch.yaml` <br>
     /// Windows: `C:\Users\username\finanzbuch.yaml`
-    pub fn home_path() -> PathBuf
+    pub fn home_path() -> Result<PathBuf, Error>
     {
         return match dirs::home_dir() {
-            Some(path) => path.join(FILENAME),
-            None => panic!(
-                "It was expected that this user has a home directory. \
-                This was not the case. This program does not work without a valid home directory."
-            ),
+            Some(path) => Ok(path.join(FILENAME)),
+            None => Err(Error::NoHomeDirectory),
         };
     }
 
-    /// - This is the default version of read(), searches in the users home path for the data file.
+    /// - This is the default version of read(), reads the file chosen by `FINANZBUCH_FILE` / `FINANZBUCH_PROFILE`,
+    ///   the last used profile or the one in the users home path, in this order (see `AppConfig::resolve()`)
     /// - Reads file content and tries to parse it into DataFile
     /// - Returns default values if file does not exist or is empty
-    pub fn read() -> Self { Self::read_from_custom_path(Self::home_path()) }
+    /// - Files of an older version are migrated, files of a newer version are refused
+    pub fn read() -> Result<Self, Error> { Self::read_from_custom_path(AppConfig::read()?.resolve(Selection::from_env())?.path) }
 
     /// - Same as read(), but with a custom path, for testing purposes
     /// - Reads file content and tries to parse it into DataFile
     /// - Returns default values if file does not exist or is empty
-    pub fn read_from_custom_path(filepath: PathBuf) -> Self
+    /// - Files of an older version are migrated, files of a newer version are refused
+    ///
+    /// Use `read_from_custom_path_with_report()` to get what was changed by the migration.
+    pub fn read_from_custom_path(filepath: PathBuf) -> Result<Self, Error>
     {
-        let mut file = match OpenOptions::new().create(false).read(true).open(&filepath) {
-            Ok(file) => file,
-            Err(e) => match e.kind() {
-                std::io::ErrorKind::NotFound => return Self::default(),
-                _ => panic!("error at opening data file > {:?}", e),
-            },
-        };
+        return Self::read_from_custom_path_with_report(filepath).map(|(datafile, _)| datafile);
+    }
 
-        // read content
-        let mut content: String = String::new();
-        match file.read_to_string(&mut content) {
-            Ok(size) => size,
-            Err(e) => panic!("error reading in file contents > {:?}", e),
+    /// Same as `read_from_custom_path()`, but returns what was changed to bring the file to the current `FILE_VERSION`.
+    ///
+    /// The migrated content is only held in memory, the file on disk stays in its old version until it is written.
+    pub fn read_from_custom_path_with_report(filepath: PathBuf) -> Result<(Self, MigrationReport), Error>
+    {
+        let Some(content) = Self::_read_bytes(&filepath)? else {
+            let mut datafile = Self::default();
+            datafile.filepath = Some(filepath);
+            return Ok((datafile, MigrationReport::unchanged()));
         };
-        if content.trim().is_empty() {
-            return Self::default();
+
+        if encryption::is_encrypted(&content) {
+            return Err(Error::PassphraseRequired(filepath));
         }
 
-        let datafile: Self = match serde_yaml::from_str(&content) {
-            Ok(datafile) => datafile,
-            Err(e) => panic!("DataFile file is borked, could not be parsed: {:?}", e),
+        let (mut datafile, report) = Self::_parse(&filepath, content)?;
+        datafile.filepath = Some(filepath);
+        return Ok((datafile, report));
+    }
+
+    /// Same as `read_from_custom_path()`, but can also read files that were written with `write_encrypted()`.
+    ///
+    /// Files that are not encrypted are read as usual, the passphrase is ignored for them.
+    /// If the file does not exist yet, it will be encrypted with this passphrase when `write()` is called.
+    pub fn read_encrypted(filepath: PathBuf, passphrase: &str) -> Result<Self, Error>
+    {
+        return Self::read_encrypted_with_report(filepath, passphrase).map(|(datafile, _)| datafile);
+    }
+
+    /// Same as `read_encrypted()`, but returns what was changed to bring the file to the current `FILE_VERSION`
+    pub fn read_encrypted_with_report(filepath: PathBuf, passphrase: &str) -> Result<(Self, MigrationReport), Error>
+    {
+        let Some(mut content) = Self::_read_bytes(&filepath)? else {
+            let mut datafile = Self::default();
+            datafile.filepath = Some(filepath);
+            datafile.passphrase = Some(String::from(passphrase));
+            return Ok((datafile, MigrationReport::unchanged()));
         };
 
-        return datafile;
+        let is_encrypted = encryption::is_encrypted(&content);
+        if is_encrypted {
+            content = encryption::decrypt(&content, passphrase)?;
+        }
+
+        let (mut datafile, report) = Self::_parse(&filepath, content)?;
+        datafile.filepath = Some(filepath);
+        datafile.passphrase = is_encrypted.then(|| String::from(passphrase));
+        return Ok((datafile, report));
     }
 
-    /// - This is the default version of write(), writes into a file in the users home directory
+    /// - This is the default version of write(), writes into the file this was read from, or into the users home directory
+    /// - The content is encrypted again, if it was read with `read_encrypted()` from an encrypted file
     /// 1. Parses the existing `DataFile` into a `String`
     /// 2. Writes this `String` into the file on disk
-    pub fn write(&self) { self.write_to_custom_path(Self::home_path()) }
+    pub fn write(&self) -> Result<(), Error>
+    {
+        let filepath = match &self.filepath {
+            Some(path) => path.clone(),
+            None => Self::home_path()?,
+        };
+
+        return match &self.passphrase {
+            Some(passphrase) => self.write_encrypted(filepath, passphrase),
+            None => self.write_to_custom_path(filepath),
+        };
+    }
 
     /// 1. Parses the existing `DataFile` into a `String`
-    /// 2. Writes this `String` into the file on disk
-    pub fn write_to_custom_path(&self, filepath: PathBuf)
+    /// 2. Saves the current content of the file on disk as a backup, if the newest backup is older than 15 minutes (see `backup_count`)
+    /// 3. Writes this `String` into the file on disk, without the risk of leaving a half written file behind
+    pub fn write_to_custom_path(&self, filepath: PathBuf) -> Result<(), Error>
     {
-        let mut file = match OpenOptions::new().create(true).truncate(true).write(true).open(&filepath) {
-            Ok(file) => file,
-            Err(e) => panic!("error at opening yaml file > {:?}", e),
+        let yaml = match serde_yaml::to_string(self) {
+            Ok(v) => v,
+            Err(e) => return Err(Error::Serialize(e.to_string())),
         };
 
-        // parse data
+        return self._write_bytes(filepath, yaml.as_bytes());
+    }
+
+    /// Same as `write_to_custom_path()`, but the content is encrypted with a key derived from `passphrase`.
+    ///
+    /// The file can only be read with `read_encrypted()` and the same passphrase.
+    /// Backups that were created while the file was not encrypted yet are encrypted as well.
+    pub fn write_encrypted(&self, filepath: PathBuf, passphrase: &str) -> Result<(), Error>
+    {
         let yaml = match serde_yaml::to_string(self) {
             Ok(v) => v,
-            Err(e) => panic!("error at serde_yaml::to_string > {:?}", e),
+            Err(e) => return Err(Error::Serialize(e.to_string())),
+        };
+
+        let encrypted = encryption::encrypt(yaml.as_bytes(), passphrase)?;
+        self._write_bytes(filepath.clone(), &encrypted)?;
+        backup::encrypt_plain(&filepath, passphrase)?;
+        return Ok(());
+    }
+
+    // ---------- Private ----------
+
+    /// `performance::month_values()` of all entries of this variant, converted into `overview_currency()` and added up
+    fn _converted_month_values(&self, variant: Option<&InvestmentVariant>, first: (u16, u8), last: (u16, u8)) -> Result<Vec<MonthValue>, Error>
+    {
+        let mut all = Vec::new();
+        for entry in self
+            .investing
+            .depot
+            .entries
+            .values()
+            .filter(|e| variant.map_or(true, |v| e.variant == *v))
+        {
+            all.push(self._converted_entry_month_values(entry, first, last)?);
+        }
+        return performance::sum_month_values(all, first, last);
+    }
+
+    /// `performance::month_values()` of this entry, converted into `overview_currency()`
+    fn _converted_entry_month_values(&self, entry: &DepotEntry, first: (u16, u8), last: (u16, u8)) -> Result<Vec<MonthValue>, Error>
+    {
+        // months without values need no exchange rate
+        let convert = |money: Money, year: u16, month: u8| -> Result<Money, Error> {
+            if money.is_zero() {
+                return Ok(money);
+            }
+            return Ok(Money::from_f64(self.to_overview_currency(entry, money.to_f64(), year, month)?));
         };
+        let mut values = performance::month_values(entry, first, last)?;
+        for v in values.iter_mut() {
+            v.value = convert(v.value, v.year, v.month)?;
+            v.cash_flow = convert(v.cash_flow, v.year, v.month)?;
+        }
+        return Ok(values);
+    }
 
-        // write data
-        match file.write_all(yaml.as_bytes()) {
-            Ok(_) => (),
-            Err(e) => panic!("error at writing yaml file > {:?}", e),
+    /// Returns `None` if the file does not exist
+    fn _read_bytes(filepath: &Path) -> Result<Option<Vec<u8>>, Error>
+    {
+        let mut file = match OpenOptions::new().create(false).read(true).open(filepath) {
+            Ok(file) => file,
+            Err(e) => match e.kind() {
+                std::io::ErrorKind::NotFound => return Ok(None),
+                _ => return Err(Error::io(filepath, e)),
+            },
         };
 
-        println!("Data written into {:?}", &filepath);
+        let mut content: Vec<u8> = Vec::new();
+        if let Err(e) = file.read_to_end(&mut content) {
+            return Err(Error::io(filepath, e));
+        }
+        return Ok(Some(content));
+    }
+
+    /// - Returns default values if the content is empty
+    /// - Migrates the content to the current `FILE_VERSION`
+    fn _parse(filepath: &Path, content: Vec<u8>) -> Result<(Self, MigrationReport), Error>
+    {
+        let content = match String::from_utf8(content) {
+            Ok(c) => c,
+            Err(e) => {
+                return Err(Error::Parse {
+                    path: filepath.to_path_buf(),
+                    line: None,
+                    column: None,
+                    message: e.to_string(),
+                })
+            }
+        };
+        if content.trim().is_empty() {
+            return Ok((Self::default(), MigrationReport::unchanged()));
+        }
+
+        let mut document: serde_yaml::Value = match serde_yaml::from_str(&content) {
+            Ok(document) => document,
+            Err(e) => return Err(Error::yaml(filepath, e)),
+        };
+
+        let report = migration::migrate(&mut document)?;
+
+        // from_value() does not know about lines and columns, so if nothing was migrated, parse the original content to keep them
+        let parsed = match report.is_empty() {
+            true => serde_yaml::from_str::<Self>(&content),
+            false => serde_yaml::from_value::<Self>(document),
+        };
+        let datafile: Self = match parsed {
+            Ok(datafile) => datafile,
+            Err(e) => return Err(Error::yaml(filepath, e)),
+        };
+
+        return Ok((datafile, report));
+    }
+
+    fn _write_bytes(&self, filepath: PathBuf, content: &[u8]) -> Result<(), Error>
+    {
+        backup::create_if_due(&filepath, self.backup_count, Duration::minutes(BACKUP_MIN_AGE_MINUTES))?;
+        return backup::write_atomic(&filepath, content);
     }
 }
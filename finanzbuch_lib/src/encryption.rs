@@ -0,0 +1,178 @@
+use crate::Error;
+use argon2::Algorithm;
+use argon2::Argon2;
+use argon2::Params;
+use argon2::Version;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::aead::OsRng;
+use chacha20poly1305::aead::Payload;
+use chacha20poly1305::KeyInit;
+use chacha20poly1305::XChaCha20Poly1305;
+use chacha20poly1305::XNonce;
+
+/// Every encrypted file starts with these bytes. A YAML file can never start like this.
+pub const MAGIC: &[u8; 8] = b"FBUCHENC";
+
+/// Increase this if the layout of the header or the algorithms change
+const FORMAT_VERSION: u8 = 1;
+const KDF_ARGON2ID: u8 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+/// magic + format version + kdf + 3 * u32 kdf params + salt + nonce
+const HEADER_LEN: usize = MAGIC.len() + 1 + 1 + 3 * 4 + SALT_LEN + NONCE_LEN;
+
+/// Parameters of Argon2id, which derives the key from the passphrase.
+/// They are stored in the header, so that they can be changed without breaking older files.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct KdfParams
+{
+    /// Memory in KiB
+    pub m_cost: u32,
+    /// Iterations
+    pub t_cost: u32,
+    /// Parallelism
+    pub p_cost: u32,
+}
+impl Default for KdfParams
+{
+    /// The defaults recommended by the argon2 crate (19 MiB, 2 iterations, 1 thread)
+    fn default() -> Self
+    {
+        return Self {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        };
+    }
+}
+
+/// Upper bounds for the `KdfParams` of a file (256 MiB, 10 iterations, 8 threads).
+/// The header is read before it can be authenticated, so a modified or broken file must not be able to make the key derivation take forever.
+pub const MAX_KDF_PARAMS: KdfParams = KdfParams {
+    m_cost: 256 * 1024,
+    t_cost: 10,
+    p_cost: 8,
+};
+
+/// `true` if the data starts with the header of an encrypted file
+pub fn is_encrypted(data: &[u8]) -> bool { data.starts_with(MAGIC) }
+
+/// Encrypts `plain` with a key derived from `passphrase`, using the default `KdfParams`
+pub fn encrypt(plain: &[u8], passphrase: &str) -> Result<Vec<u8>, Error> { encrypt_with_params(plain, passphrase, KdfParams::default()) }
+
+/// The resulting layout is:
+/// ```C
+/// | MAGIC (8) | format version (1) | kdf (1) | m_cost (4) | t_cost (4) | p_cost (4) | salt (16) | nonce (24) | ciphertext + tag |
+/// ```
+/// All numbers are little endian. The header is authenticated together with the ciphertext, so it cannot be changed unnoticed.
+pub fn encrypt_with_params(plain: &[u8], passphrase: &str, params: KdfParams) -> Result<Vec<u8>, Error>
+{
+    _check_params(params)?;
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut header: Vec<u8> = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(FORMAT_VERSION);
+    header.push(KDF_ARGON2ID);
+    header.extend_from_slice(&params.m_cost.to_le_bytes());
+    header.extend_from_slice(&params.t_cost.to_le_bytes());
+    header.extend_from_slice(&params.p_cost.to_le_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let cipher = XChaCha20Poly1305::new(&_derive_key(passphrase, &salt, params)?.into());
+    let ciphertext = match cipher.encrypt(XNonce::from_slice(&nonce), Payload { msg: plain, aad: &header }) {
+        Ok(c) => c,
+        Err(_) => return Err(Error::Encryption(String::from("the data could not be encrypted"))),
+    };
+
+    header.extend_from_slice(&ciphertext);
+    return Ok(header);
+}
+
+/// Reverses `encrypt()`.
+///
+/// Returns `Err(Decryption)` if the passphrase is wrong or the data was modified,
+/// these two cases cannot be told apart. `KdfParams` above `MAX_KDF_PARAMS` are rejected before the key is derived.
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, Error>
+{
+    if !is_encrypted(data) {
+        return Err(Error::Encryption(String::from("the data is not encrypted")));
+    }
+    if data.len() < HEADER_LEN {
+        return Err(Error::Decryption);
+    }
+
+    let (header, ciphertext) = data.split_at(HEADER_LEN);
+    let mut pos = MAGIC.len();
+
+    let format_version = header[pos];
+    let kdf = header[pos + 1];
+    pos += 2;
+    if format_version != FORMAT_VERSION || kdf != KDF_ARGON2ID {
+        return Err(Error::Encryption(format!(
+            "format version {format_version} with key derivation {kdf} is not supported by this version of the program"
+        )));
+    }
+
+    let mut next_u32 = || {
+        let value = u32::from_le_bytes([header[pos], header[pos + 1], header[pos + 2], header[pos + 3]]);
+        pos += 4;
+        value
+    };
+    let params = KdfParams {
+        m_cost: next_u32(),
+        t_cost: next_u32(),
+        p_cost: next_u32(),
+    };
+    _check_params(params)?;
+
+    let salt = &header[pos..pos + SALT_LEN];
+    let nonce = &header[pos + SALT_LEN..pos + SALT_LEN + NONCE_LEN];
+
+    let cipher = XChaCha20Poly1305::new(&_derive_key(passphrase, salt, params)?.into());
+    return match cipher.decrypt(
+        XNonce::from_slice(nonce),
+        Payload {
+            msg: ciphertext,
+            aad: header,
+        },
+    ) {
+        Ok(plain) => Ok(plain),
+        Err(_) => Err(Error::Decryption),
+    };
+}
+
+// ================================================== Private ================================================== //
+
+fn _check_params(params: KdfParams) -> Result<(), Error>
+{
+    if params.m_cost > MAX_KDF_PARAMS.m_cost || params.t_cost > MAX_KDF_PARAMS.t_cost || params.p_cost > MAX_KDF_PARAMS.p_cost {
+        return Err(Error::Encryption(format!(
+            "the key derivation parameters {params:?} are above the maximum of {MAX_KDF_PARAMS:?}"
+        )));
+    }
+    return Ok(());
+}
+
+fn _derive_key(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<[u8; KEY_LEN], Error>
+{
+    let argon_params = match Params::new(params.m_cost, params.t_cost, params.p_cost, Some(KEY_LEN)) {
+        Ok(p) => p,
+        Err(e) => return Err(Error::Encryption(format!("invalid key derivation parameters: {e}"))),
+    };
+
+    let mut key = [0u8; KEY_LEN];
+    if let Err(e) = Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params).hash_password_into(passphrase.as_bytes(), salt, &mut key) {
+        return Err(Error::Encryption(format!("the key could not be derived from the passphrase: {e}")));
+    }
+    return Ok(key);
+}
@@ -0,0 +1,208 @@
+use crate::currency::Currency;
+use crate::investing::savings_plan_section::SavingsPlanSection;
+use crate::locale::Locale;
+use crate::migration::MigrationError;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Every fallible public function of this library returns this error, so that callers can show a real message to the user
+#[derive(Debug)]
+pub enum Error
+{
+    /// A file could not be opened, read or written
+    Io
+    {
+        path: PathBuf, source: std::io::Error
+    },
+    /// The content of a file could not be parsed. `line` and `column` start at 1, if they are known
+    Parse
+    {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+    /// The data could not be converted into the format of the file
+    Serialize(String),
+    /// The data file could not be brought to the current version
+    Migration(MigrationError),
+    /// The file is encrypted and can only be read with `DataFile::read_encrypted()`
+    PassphraseRequired(PathBuf),
+    /// The passphrase is wrong or the encrypted data was modified
+    Decryption,
+    /// Encrypting failed or the encrypted file has a format that is not supported
+    Encryption(String),
+    /// The users home directory could not be found
+    NoHomeDirectory,
+    /// The users config directory could not be found, so the app config has no place to be stored
+    NoConfigDirectory,
+    /// There is no profile with this name in the app config
+    UnknownProfile(String),
+    /// The profile could not be added, eg. because the name is already taken
+    InvalidProfile(String),
+    /// Months have to be between 1 and 12
+    InvalidMonth(u8),
+    /// Days have to be between 1 and 31
+    InvalidDay(u8),
+    /// A String is not a date like `2024-01-31`
+    InvalidDate(String),
+    /// The transaction does not belong into this month or year
+    InvalidTransaction(String),
+    /// The category path is empty, already exists or does not fit to its parent
+    InvalidCategory(String),
+    /// There is no category with this path
+    UnknownCategory(String),
+    /// A String could not be read as an amount of money or a quantity
+    InvalidAmount(String),
+    /// A String is not a number in the format of this locale
+    InvalidNumber
+    {
+        value: String, locale: Locale, reason: String
+    },
+    /// Locales are given as codes like `de-DE`
+    UnknownLocale(String),
+    /// Currencies are given as three letter ISO 4217 codes, eg. `EUR`
+    InvalidCurrency(String),
+    /// The exchange rate could not be set or read
+    InvalidExchangeRate(String),
+    /// There is no exchange rate for this currency at or before this month
+    MissingExchangeRate
+    {
+        currency: Currency, year: u16, month: u8
+    },
+    /// The consumer price index could not be set or read
+    InvalidPriceIndex(String),
+    /// There is no consumer price index at or before this month
+    MissingPriceIndex
+    {
+        year: u16, month: u8
+    },
+    /// The expense ratio of a depot entry is not valid
+    InvalidExpenseRatio(String),
+    /// A String could not be converted into an `InvestmentVariant`
+    InvalidVariant(String),
+    /// The section itself is not valid, eg. because it ends before it starts
+    InvalidSavingsPlan(String),
+    /// The new section overlaps with this existing section
+    OverlappingSavingsPlan(SavingsPlanSection),
+}
+impl std::fmt::Display for Error
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self {
+            Error::Io { path, source } => write!(f, "Could not access {:?}: {source}", path),
+            Error::Parse {
+                path,
+                line: Some(line),
+                column: Some(column),
+                message,
+            } => write!(f, "Could not parse {:?} at line {line}, column {column}: {message}", path),
+            Error::Parse {
+                path,
+                line: Some(line),
+                message,
+                ..
+            } => write!(f, "Could not parse {:?} at line {line}: {message}", path),
+            Error::Parse { path, message, .. } => write!(f, "Could not parse {:?}: {message}", path),
+            Error::Serialize(msg) => write!(f, "Could not convert the data: {msg}"),
+            Error::Migration(e) => write!(f, "{e}"),
+            Error::PassphraseRequired(path) => write!(f, "{:?} is encrypted, a passphrase is required to read it", path),
+            Error::Decryption => write!(
+                f,
+                "The data could not be decrypted. Either the passphrase is wrong or the file was modified"
+            ),
+            Error::Encryption(msg) => write!(f, "Encryption failed: {msg}"),
+            Error::NoHomeDirectory => write!(
+                f,
+                "It was expected that this user has a home directory. \
+                This was not the case. This program does not work without a valid home directory."
+            ),
+            Error::NoConfigDirectory => write!(f, "The config directory of this user could not be found"),
+            Error::UnknownProfile(name) => write!(f, "There is no profile called {name}"),
+            Error::InvalidProfile(reason) => write!(f, "This profile is not valid: {reason}"),
+            Error::InvalidMonth(month) => write!(f, "{month} is not a valid month, only 1-12 are allowed"),
+            Error::InvalidDay(day) => write!(f, "{day} is not a valid day, only 1-31 are allowed"),
+            Error::InvalidDate(value) => write!(f, "{value:?} is not a date like 2024-01-31"),
+            Error::InvalidTransaction(msg) => write!(f, "Invalid transaction: {msg}"),
+            Error::InvalidCategory(msg) => write!(f, "Invalid category: {msg}"),
+            Error::UnknownCategory(path) => write!(f, "There is no category {path:?}"),
+            Error::InvalidAmount(value) => write!(f, "{value:?} is not a valid number"),
+            Error::InvalidNumber { value, locale, reason } => write!(f, "{value:?} is not a valid number in {locale}: {reason}"),
+            Error::UnknownLocale(code) => write!(
+                f,
+                "{code:?} is not a supported locale, use one of {}",
+                Locale::ALL.map(|l| l.code()).join(", ")
+            ),
+            Error::InvalidCurrency(code) => write!(f, "{code:?} is not a valid currency, a three letter code like EUR is expected"),
+            Error::InvalidExchangeRate(msg) => write!(f, "Invalid exchange rate: {msg}"),
+            Error::MissingExchangeRate { currency, year, month } => {
+                write!(f, "There is no exchange rate for {currency} in {year}-{month:02} or any month before")
+            }
+            Error::InvalidPriceIndex(msg) => write!(f, "Invalid price index: {msg}"),
+            Error::MissingPriceIndex { year, month } => {
+                write!(f, "There is no consumer price index for {year}-{month:02} or any month before")
+            }
+            Error::InvalidExpenseRatio(msg) => write!(f, "Invalid expense ratio: {msg}"),
+            Error::InvalidVariant(value) => write!(f, "{value} is not a possible InvestmentVariant"),
+            Error::InvalidSavingsPlan(reason) => write!(f, "This savings plan section is not valid: {reason}"),
+            Error::OverlappingSavingsPlan(existing) => write!(
+                f,
+                "This savings plan section overlaps with the existing section from {}-{}-{} to {}-{}-{}",
+                existing.start.year(),
+                existing.start.month(),
+                existing.start.day(),
+                existing.end.year(),
+                existing.end.month(),
+                existing.end.day(),
+            ),
+        }
+    }
+}
+impl std::error::Error for Error
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+    {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Migration(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl From<MigrationError> for Error
+{
+    fn from(e: MigrationError) -> Self { Error::Migration(e) }
+}
+impl Error
+{
+    pub(crate) fn io(path: &Path, source: std::io::Error) -> Self
+    {
+        Error::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub(crate) fn yaml(path: &Path, e: serde_yaml::Error) -> Self
+    {
+        let location = e.location();
+        return Error::Parse {
+            path: path.to_path_buf(),
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            message: e.to_string(),
+        };
+    }
+
+    pub(crate) fn csv(path: &Path, e: csv::Error) -> Self
+    {
+        let line = e.position().map(|p| p.line() as usize);
+        return Error::Parse {
+            path: path.to_path_buf(),
+            line,
+            column: None,
+            message: e.to_string(),
+        };
+    }
+}
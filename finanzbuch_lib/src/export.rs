@@ -0,0 +1,137 @@
+use crate::fast_date::FastDate;
+use crate::DataFile;
+use crate::Error;
+use crate::Money;
+use crate::Quantity;
+use csv::WriterBuilder;
+use serde::Serialize;
+
+/// Which data is exported. `None` means no restriction
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ExportFilter
+{
+    pub first_year: Option<u16>,
+    pub last_year: Option<u16>,
+    /// Name of a depot entry, only affects `InvestmentRow`s
+    pub entry: Option<String>,
+}
+impl ExportFilter
+{
+    pub fn contains_year(&self, year: u16) -> bool
+    {
+        return self.first_year.map_or(true, |first| year >= first) && self.last_year.map_or(true, |last| year <= last);
+    }
+
+    /// Case insensitive, like the lookup of depot entries
+    pub fn contains_entry(&self, name: &str) -> bool { return self.entry.as_ref().map_or(true, |entry| entry.to_lowercase() == name.to_lowercase()); }
+}
+
+/// One `AccountingMonth`
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct AccountingRow
+{
+    pub year: u16,
+    pub month: u8,
+    pub income: Money,
+    pub expenses: Money,
+    pub difference: Money,
+    pub note: String,
+    /// Generated from the recurring items and not changed since
+    pub planned: bool,
+}
+
+/// One `InvestmentMonth` of one `DepotEntry`
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct InvestmentRow
+{
+    pub entry: String,
+    pub variant: String,
+    pub year: u16,
+    pub month: u8,
+    pub units: Quantity,
+    pub price_per_unit: Quantity,
+    /// `units * price_per_unit`
+    pub volume: Money,
+    /// From the savings plan
+    pub planned_transactions: Money,
+    pub additional_transactions: Money,
+}
+
+/// Flat tables of the data file, sorted by entry name, year and month
+#[derive(Debug, PartialEq, Clone, Default, Serialize)]
+pub struct Export
+{
+    pub accounting: Vec<AccountingRow>,
+    pub investing: Vec<InvestmentRow>,
+}
+impl Export
+{
+    pub fn new(datafile: &DataFile, filter: &ExportFilter) -> Self
+    {
+        let mut accounting: Vec<AccountingRow> = Vec::new();
+        for (year, accounting_year) in datafile.accounting.history.iter().filter(|(y, _)| filter.contains_year(**y)) {
+            for month in accounting_year.months.iter() {
+                accounting.push(AccountingRow {
+                    year: *year,
+                    month: month.month_nr(),
+                    income: month.income(),
+                    expenses: month.expenses(),
+                    difference: month.difference(),
+                    note: String::from(month.note()),
+                    planned: month.is_planned(),
+                });
+            }
+        }
+
+        let mut investing: Vec<InvestmentRow> = Vec::new();
+        let mut entries: Vec<_> = datafile
+            .investing
+            .depot
+            .entries
+            .values()
+            .filter(|e| filter.contains_entry(e.name()))
+            .collect();
+        entries.sort_by(|a, b| a.name().cmp(b.name()));
+        for entry in entries {
+            for (year, investment_year) in entry.history.iter().filter(|(y, _)| filter.contains_year(**y)) {
+                for month in investment_year.months.iter() {
+                    investing.push(InvestmentRow {
+                        entry: String::from(entry.name()),
+                        variant: entry.variant.to_string(),
+                        year: *year,
+                        month: month.month_nr(),
+                        units: month.amount(),
+                        price_per_unit: month.price_per_unit(),
+                        volume: month.volume(),
+                        planned_transactions: entry.get_planned_transactions(FastDate::new_risky(*year, month.month_nr(), 1)),
+                        additional_transactions: month.additional_transactions(),
+                    });
+                }
+            }
+        }
+
+        return Self { accounting, investing };
+    }
+
+    /// Both tables in one document
+    pub fn to_json(&self) -> Result<String, Error> { return serde_json::to_string_pretty(self).map_err(|e| Error::Serialize(e.to_string())); }
+
+    /// Semicolon separated with a header line, like the files that can be imported. Empty if there are no rows
+    pub fn accounting_csv(&self) -> Result<String, Error> { return _to_csv(&self.accounting); }
+
+    /// Semicolon separated with a header line, like the files that can be imported. Empty if there are no rows
+    pub fn investing_csv(&self) -> Result<String, Error> { return _to_csv(&self.investing); }
+}
+
+// ================================================== Private ================================================== //
+
+fn _to_csv<T: Serialize>(rows: &[T]) -> Result<String, Error>
+{
+    let mut writer = WriterBuilder::new().delimiter(b';').from_writer(vec![]);
+    for row in rows {
+        writer.serialize(row).map_err(|e| Error::Serialize(e.to_string()))?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| Error::Serialize(e.to_string()))?;
+    return String::from_utf8(bytes).map_err(|e| Error::Serialize(e.to_string()));
+}
@@ -1,5 +1,7 @@
+use crate::Error;
 use serde::Deserialize;
 use serde::Serialize;
+use std::str::FromStr;
 
 const MASK_YEAR: u32 = 0b1111_1111_1111_1111_0000_0000_0000_0000;
 const MASK_MONTH: u32 = 0b0000_0000_0000_0000_1111_0000_0000_0000;
@@ -23,7 +25,7 @@ const DAYS_UNTIL_MONTH_START: [u16; 13] = [
 ];
 
 /// Two dates can be compared with simple comparison operators: `>  >=  <  <=  ==  !=`
-/// 
+///
 /// - `16` bit Year
 /// - `4` bit Month
 /// - `6` bit Day
@@ -31,7 +33,7 @@ const DAYS_UNTIL_MONTH_START: [u16; 13] = [
 ///     - For simplicity, every year is treated as if the start of the year is also the first day of the first week
 ///     - Since `(366 days / 7 days) > 52 weeks`, the max value allowed is 53, to indicate that the date is in the 53th week
 /// - Expects values to be starting at 1
-/// 
+///
 /// The highest possible value is 31. December 65535 (Week 53)
 ///
 /// ```C
@@ -54,6 +56,29 @@ impl Ord for FastDate
 {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.0.cmp(&other.0) }
 }
+impl FromStr for FastDate
+{
+    type Err = Error;
+
+    /// `YYYY-MM-DD` like `2024-01-31`, which is also the format of `<input type="date">`.
+    /// Returns `Err(InvalidMonth)` or `Err(InvalidDay)` if the numbers are out of range, the same as `new()`
+    fn from_str(string: &str) -> Result<Self, Self::Err>
+    {
+        let parts: Vec<&str> = string.trim().split('-').collect();
+        let [year, month, day] = parts.as_slice() else {
+            return Err(Error::InvalidDate(String::from(string)));
+        };
+        let (Ok(year), Ok(month), Ok(day)) = (year.parse::<u16>(), month.parse::<u8>(), day.parse::<u8>()) else {
+            return Err(Error::InvalidDate(String::from(string)));
+        };
+        return Self::new(year, month, day);
+    }
+}
+impl std::fmt::Display for FastDate
+{
+    /// `YYYY-MM-DD` like `2024-01-31`, can be read again with `parse()`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "{}-{:0>2}-{:0>2}", self.year(), self.month(), self.day()) }
+}
 impl FastDate
 {
     /// January 1st 2000 (Week 1)
@@ -62,25 +87,31 @@ impl FastDate
     /// This ***panics*** if
     /// - month > 12 or 0
     /// - day > 31 or 0
+    ///
+    /// Only use this for dates that are known to be valid, like constants or values that were already checked.
+    /// For everything else, use `new()`
     pub fn new_risky(year: u16, month: u8, day: u8) -> Self
     {
-        // ranges in rust are included..excluded
-        if !(1..32).contains(&day) || !(1..13).contains(&month) {
-            panic!("This datatype only allows 1-31 days, 1-12 months and 1-53 weeks. Input was day {day}, month {month}");
-        }
-
-        let week = Self::_calc_week(month, day);
-        return Self(0 | (year as u32) << 16 | (month as u32) << 12 | (day as u32) << 6 | week);
+        return match Self::new(year, month, day) {
+            Ok(date) => date,
+            Err(e) => panic!("This datatype only allows 1-31 days, 1-12 months and 1-53 weeks. {e}"),
+        };
     }
 
     /// This returns with Err if
-    /// - month > 12 or 0
-    /// - day > 31 or 0
-    pub fn new(year: u16, month: u8, day: u8) -> Result<Self, ()>
+    /// - month > 12 or 0 (`InvalidMonth`)
+    /// - day > 31 or 0 (`InvalidDay`)
+    ///
+    /// The day is ***not*** checked against the length of the month, so `new(2023, 2, 31)` is `Ok`.
+    /// Use `days_in_month()` where that matters
+    pub fn new(year: u16, month: u8, day: u8) -> Result<Self, Error>
     {
         // ranges in rust are included..excluded
-        if !(1..32).contains(&day) || !(1..13).contains(&month) {
-            return Err(());
+        if !(1..13).contains(&month) {
+            return Err(Error::InvalidMonth(month));
+        }
+        if !(1..32).contains(&day) {
+            return Err(Error::InvalidDay(day));
         }
         let week = Self::_calc_week(month, day);
         return Ok(Self(0 | (year as u32) << 16 | (month as u32) << 12 | (day as u32) << 6 | week));
@@ -90,6 +121,19 @@ impl FastDate
     /// 31. December 65535 (Week 53)
     pub fn new_max() -> Self { return Self::new_risky(u16::MAX, 12, 31); }
 
+    /// 28 - 31, or 0 if the month is not 1-12
+    pub fn days_in_month(year: u16, month: u8) -> u8
+    {
+        let leap_year = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        return match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap_year => 29,
+            2 => 28,
+            _ => 0,
+        };
+    }
+
     /// (Year, Month, Day)
     pub fn date(&self) -> (u16, u8, u8, u8) { (self.year(), self.month(), self.day(), self.week()) }
     pub fn year(&self) -> u16 { (self.0 >> 16) as u16 }
@@ -100,27 +144,28 @@ impl FastDate
     // reset value and assign new
     pub fn set_year(&mut self, year: u16) { self.0 = (self.0 & !MASK_YEAR) | (year as u32) << 16; }
 
-    /// Expects month to be `>= 1 && <= 12`, will return `Err` if thats not the case
-    pub fn set_month(&mut self, month: u8) -> Result<(), ()>
+    /// Expects month to be `>= 1 && <= 12`, will return `Err(InvalidMonth)` if thats not the case
+    pub fn set_month(&mut self, month: u8) -> Result<(), Error>
     {
         if month > 12 || month == 0 {
-            return Err(());
+            return Err(Error::InvalidMonth(month));
         }
         self.0 &= !MASK_MONTH; // reset value
-        self.0 |= (month as u32) << 8;
+        self.0 |= (month as u32) << 12;
 
         self._set_week();
         return Ok(());
     }
 
-    /// Expects day to be `>= 1 && <= 31`, will return `Err` if thats not the case
-    pub fn set_day(&mut self, day: u8) -> Result<(), ()>
+    /// Expects day to be `>= 1 && <= 31`, will return `Err(InvalidDay)` if thats not the case.
+    /// Like `new()`, this does not check the day against the length of the month
+    pub fn set_day(&mut self, day: u8) -> Result<(), Error>
     {
         if day > 31 || day == 0 {
-            return Err(());
+            return Err(Error::InvalidDay(day));
         }
         self.0 &= !MASK_DAY; // reset value
-        self.0 |= day as u32;
+        self.0 |= (day as u32) << 6;
 
         self._set_week();
         return Ok(());
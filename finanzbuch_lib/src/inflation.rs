@@ -0,0 +1,118 @@
+use crate::currency::CSV_HEADER_MONTH;
+use crate::currency::CSV_HEADER_YEAR;
+use crate::import_csv_lines;
+use crate::locale::Locale;
+use crate::Error;
+use crate::Money;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Column names of a price index CSV file, one value per line: `Jahr;Monat;Index`
+pub const CSV_HEADER_INDEX: &str = "Index";
+
+/// Consumer price index of one month, eg. `117.4` if prices are 17.4% higher than in the reference year of the index
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct PriceIndexValue
+{
+    pub year: u16,
+    pub month: u8,
+    pub index: f64,
+}
+
+/// Consumer price index per month, used to show values in real terms. Only stored locally,
+/// it has to be entered or imported by the user.
+///
+/// A value stays valid until the next one, so months that are not published yet count as months without inflation.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+pub struct PriceIndex
+{
+    /// Sorted by year and month. Use `set_value()` and `remove_value()` to keep it that way
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<PriceIndexValue>,
+}
+impl PriceIndex
+{
+    pub fn is_default(&self) -> bool { return *self == Self::default(); }
+
+    /// Adds the value or replaces the value of the same month
+    pub fn set_value(&mut self, year: u16, month: u8, index: f64) -> Result<(), Error>
+    {
+        if !(1..=12).contains(&month) {
+            return Err(Error::InvalidMonth(month));
+        }
+        if !index.is_finite() || index <= 0.0 {
+            return Err(Error::InvalidPriceIndex(format!("{index} is not a valid index, it has to be positive")));
+        }
+
+        let new_value = PriceIndexValue { year, month, index };
+        match self.values.binary_search_by(|v| (v.year, v.month).cmp(&(year, month))) {
+            Ok(i) => self.values[i] = new_value,
+            Err(i) => self.values.insert(i, new_value),
+        }
+        return Ok(());
+    }
+
+    /// Returns the removed value, `None` if there was no value for this month
+    pub fn remove_value(&mut self, year: u16, month: u8) -> Option<PriceIndexValue>
+    {
+        let i = self.values.iter().position(|v| v.year == year && v.month == month)?;
+        return Some(self.values.remove(i));
+    }
+
+    /// The newest index at or before this month. Returns `Err(MissingPriceIndex)` if there is none
+    pub fn index(&self, year: u16, month: u8) -> Result<f64, Error>
+    {
+        return self
+            .values
+            .iter()
+            .rev()
+            .find(|v| (v.year, v.month) <= (year, month))
+            .map(|v| v.index)
+            .ok_or(Error::MissingPriceIndex { year, month });
+    }
+
+    /// What `amount` of this month is worth in prices of `base`, eg. `base` = today shows old values in todays money.
+    /// Zero needs no index
+    pub fn deflate(&self, amount: Money, year: u16, month: u8, base: (u16, u8)) -> Result<Money, Error>
+    {
+        if amount.is_zero() {
+            return Ok(amount);
+        }
+        let factor = self.index(base.0, base.1)? / self.index(year, month)?;
+        return Ok(amount.mul_f64(factor));
+    }
+
+    /// Reads a semicolon separated file with the columns `Jahr;Monat;Index` (in any order) and sets all of its values.
+    /// The values are read in the format of `locale`.
+    ///
+    /// Nothing is changed if one of the lines is not valid. Returns the number of values that were set
+    pub fn import_csv(&mut self, path: &PathBuf, locale: Locale) -> Result<usize, Error>
+    {
+        let mut imported = self.clone();
+        let columns = [CSV_HEADER_YEAR, CSV_HEADER_MONTH, CSV_HEADER_INDEX];
+        let count = import_csv_lines(path, columns, |[year, month, index]| {
+            let year = year.parse::<u16>().map_err(|e| Error::InvalidPriceIndex(e.to_string()))?;
+            let month = month.parse::<u8>().map_err(|e| Error::InvalidPriceIndex(e.to_string()))?;
+            let index = locale.parse_f64(index)?;
+            return imported.set_value(year, month, index);
+        })?;
+
+        *self = imported;
+        return Ok(count);
+    }
+}
+
+/// Income and expenses of one accounting month, in prices of the base month. See `DataFile::real_accounting_months()`
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct RealAccountingMonth
+{
+    pub year: u16,
+    pub month: u8,
+    pub income: Money,
+    pub expenses: Money,
+}
+impl RealAccountingMonth
+{
+    pub fn difference(&self) -> Money { self.income - self.expenses }
+}
@@ -0,0 +1,193 @@
+use crate::fast_date::FastDate;
+use crate::Money;
+use crate::Quantity;
+use serde::Deserialize;
+use serde::Serialize;
+use std::ops::Add;
+use std::ops::AddAssign;
+
+use super::depot::DepotEntry;
+use super::depot_transaction::DepotTransactionKind;
+
+/// Which purchase the units of a sale are taken from
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy, Default)]
+pub enum CostBasisMethod
+{
+    /// The units that were bought first are sold first
+    #[default]
+    Fifo,
+    /// All units cost the same, the average price of all purchases
+    Average,
+}
+impl std::fmt::Display for CostBasisMethod
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self {
+            CostBasisMethod::Fifo => write!(f, "FIFO"),
+            CostBasisMethod::Average => write!(f, "Average"),
+        }
+    }
+}
+
+/// What the units of a position cost and what they are worth, see `DepotEntry::cost_basis()`
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct CostBasis
+{
+    /// What the units that are still held cost, including the fees of their purchase
+    pub invested: Money,
+    /// `volume()` of the month
+    pub current_value: Money,
+    /// Gains of sales after fees, plus dividends, minus fees and taxes that were not part of a buy or sell
+    pub realized_gain: Money,
+}
+impl CostBasis
+{
+    pub fn unrealized_gain(&self) -> Money { self.current_value - self.invested }
+
+    pub fn total_gain(&self) -> Money { self.unrealized_gain() + self.realized_gain }
+
+    /// 5 = 5%. The unrealized gain compared to `invested`, `None` if nothing is invested
+    pub fn gain_percent(&self) -> Option<f64>
+    {
+        if self.invested.is_zero() {
+            return None;
+        }
+        return Some(self.unrealized_gain().to_f64() / self.invested.to_f64() * 100.0);
+    }
+}
+impl Add for CostBasis
+{
+    type Output = CostBasis;
+    fn add(self, rhs: Self) -> Self::Output
+    {
+        return Self {
+            invested: self.invested + rhs.invested,
+            current_value: self.current_value + rhs.current_value,
+            realized_gain: self.realized_gain + rhs.realized_gain,
+        };
+    }
+}
+impl AddAssign for CostBasis
+{
+    fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; }
+}
+
+/// See `DepotEntry::cost_basis()`
+pub(crate) fn calculate(entry: &DepotEntry, method: CostBasisMethod, year: u16, month: u8) -> CostBasis
+{
+    let mut book = Book {
+        method,
+        lots: vec![],
+        realized_gain: Money::ZERO,
+    };
+
+    // before the first transaction, the savings plan bought at the price of the month
+    let first_transaction = entry.transactions().first().map(|t| (t.date.year(), t.date.month()));
+    if let Some((&first_year, _)) = entry.history.first_key_value() {
+        let (mut y, mut m) = (first_year, 1);
+        while (y, m) <= (year, month) && first_transaction.map_or(true, |first| (y, m) < first) {
+            let planned = entry.get_planned_transactions(FastDate::new_risky(y, m, 1));
+            let price = entry
+                .history
+                .get(&y)
+                .map(|i| i.months[m as usize - 1].price_per_unit())
+                .unwrap_or_default();
+            if let Some(units) = planned.abs().units_at(price) {
+                match planned.is_negative() {
+                    true => book.sell(units, planned.abs()),
+                    false => book.buy(units, planned),
+                }
+            }
+
+            (y, m) = match m {
+                12 => (y + 1, 1),
+                m => (y, m + 1),
+            };
+        }
+    }
+
+    for t in entry.transactions().iter().filter(|t| (t.date.year(), t.date.month()) <= (year, month)) {
+        match t.kind {
+            DepotTransactionKind::Buy => book.buy(t.units(), t.units().times(t.price()) + t.fees()),
+            DepotTransactionKind::Sell => book.sell(t.units(), t.units().times(t.price()) - t.fees()),
+            DepotTransactionKind::Dividend => book.realized_gain += t.units().times(t.price()) - t.fees(),
+            DepotTransactionKind::Fee | DepotTransactionKind::Tax => book.realized_gain -= t.fees(),
+            DepotTransactionKind::Split => {
+                book.split(t.units());
+                book.realized_gain -= t.fees();
+            }
+        }
+    }
+
+    let current_value = match entry.history.get(&year) {
+        Some(investment_year) => investment_year.months[month as usize - 1].volume(),
+        None => Money::ZERO,
+    };
+    return CostBasis {
+        invested: book.lots.iter().map(|l| l.cost).sum(),
+        current_value,
+        realized_gain: book.realized_gain,
+    };
+}
+
+// ================================================== Private ================================================== //
+
+/// Units of one purchase that were not sold yet
+struct Lot
+{
+    units: Quantity,
+    cost: Money,
+}
+
+struct Book
+{
+    method: CostBasisMethod,
+    /// Oldest first. With `Average` there is only one lot
+    lots: Vec<Lot>,
+    realized_gain: Money,
+}
+impl Book
+{
+    fn buy(&mut self, units: Quantity, cost: Money)
+    {
+        match (self.method, self.lots.first_mut()) {
+            (CostBasisMethod::Average, Some(lot)) => {
+                lot.units += units;
+                lot.cost += cost;
+            }
+            _ => self.lots.push(Lot { units, cost }),
+        }
+    }
+
+    /// `proceeds` after fees. Units that are not held are ignored
+    fn sell(&mut self, units: Quantity, proceeds: Money)
+    {
+        let mut remaining = units;
+        let mut cost = Money::ZERO;
+        while !remaining.is_zero() && !self.lots.is_empty() {
+            let lot = &mut self.lots[0];
+            if lot.units <= remaining {
+                remaining = remaining - lot.units;
+                cost += lot.cost;
+                self.lots.remove(0);
+                continue;
+            }
+            let lot_cost = lot.cost.share(remaining, lot.units).unwrap_or_default();
+            lot.units = lot.units - remaining;
+            lot.cost -= lot_cost;
+            cost += lot_cost;
+            remaining = Quantity::ZERO;
+        }
+        self.realized_gain += proceeds - cost;
+    }
+
+    /// The units of every lot change by the same ratio, the cost stays the same
+    fn split(&mut self, added_units: Quantity)
+    {
+        let held = self.lots.iter().fold(Quantity::ZERO, |sum, l| sum + l.units);
+        for lot in self.lots.iter_mut() {
+            lot.units = lot.units.share(held + added_units, held).unwrap_or_default();
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use crate::fast_date::FastDate;
+use crate::get_csv_contents_with_header;
+use crate::investing::depot::Depot;
+use crate::investing::inv_year::InvestmentYear;
+use crate::locale::Locale;
+use crate::Error;
+use crate::Money;
+use crate::Quantity;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Which columns of a broker export contain what. Columns are found by their name in the header line.
+///
+/// The presets are for the exports of some german brokers. If the export of a broker changes, create a custom profile.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BrokerProfile
+{
+    pub date: String,
+    /// Format of the date column, see `chrono::format::strftime`
+    pub date_format: String,
+    /// Rows are matched to a `DepotEntry` by its ISIN first, and by its name second. At least one of these is needed
+    pub isin: Option<String>,
+    pub name: Option<String>,
+    /// Number of shares that were bought or sold
+    pub units: String,
+    pub price: String,
+    /// Money that was moved by this row
+    pub cash_flow: String,
+
+    /// If given, rows with one of `sell_values` in this column are sales, no matter which sign units and cash flow have.
+    /// Without it, negative units are sales
+    pub kind: Option<String>,
+    pub sell_values: Vec<String>,
+    /// `true` if the export shows buying as negative cash flow (money leaves the account)
+    pub invert_cash_flow: bool,
+    /// How the numbers are written
+    pub locale: Locale,
+}
+impl Default for BrokerProfile
+{
+    fn default() -> Self
+    {
+        return Self {
+            date: String::from("Datum"),
+            date_format: String::from("%d.%m.%Y"),
+            isin: Some(String::from("ISIN")),
+            name: Some(String::from("Name")),
+            units: String::from("Stück"),
+            price: String::from("Kurs"),
+            cash_flow: String::from("Betrag"),
+            kind: None,
+            sell_values: vec![],
+            invert_cash_flow: false,
+            locale: Locale::DeDe,
+        };
+    }
+}
+impl BrokerProfile
+{
+    /// Transactions export of Scalable Capital
+    pub fn scalable_capital() -> Self
+    {
+        return Self {
+            date: String::from("date"),
+            date_format: String::from("%Y-%m-%d"),
+            isin: Some(String::from("isin")),
+            name: Some(String::from("description")),
+            units: String::from("shares"),
+            price: String::from("price"),
+            cash_flow: String::from("amount"),
+            kind: Some(String::from("type")),
+            sell_values: vec![String::from("Sell")],
+            invert_cash_flow: true,
+            locale: Locale::DeDe,
+        };
+    }
+
+    /// "Depotumsätze" export of comdirect
+    pub fn comdirect() -> Self
+    {
+        return Self {
+            date: String::from("Buchungstag"),
+            date_format: String::from("%d.%m.%Y"),
+            isin: None,
+            name: Some(String::from("Bezeichnung")),
+            units: String::from("Stück / Nom."),
+            price: String::from("Ausführungskurs"),
+            cash_flow: String::from("Umsatz in EUR"),
+            kind: None,
+            sell_values: vec![],
+            invert_cash_flow: true,
+            locale: Locale::DeDe,
+        };
+    }
+}
+
+/// A row of the export that was not imported
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnmatchedRow
+{
+    /// Line in the file, the header is line 1
+    pub line: usize,
+    pub isin: String,
+    pub name: String,
+    pub reason: String,
+}
+
+/// One month of one `DepotEntry` that was changed by the import
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChangedMonth
+{
+    pub entry_name: String,
+    pub year: u16,
+    pub month: u8,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ImportReport
+{
+    pub changed: Vec<ChangedMonth>,
+    pub unmatched: Vec<UnmatchedRow>,
+}
+
+/// Imports the export of a broker into the depot:
+/// - `amount` is the number of shares at the end of the month. It starts with the amount of the month before the first row
+///   and is carried forward through months without rows, up to the last row of this entry
+/// - `price_per_unit` is the price of the last row in the month, months without rows keep their price
+/// - `additional_transactions` is the sum of all cash flows in the month, without the planned transactions of the savings plan,
+///   because these are already contained in most exports
+/// - Missing years are created, afterwards all entries have the same years (see `Depot::ensure_uniform_histories()`)
+///
+/// Rows that cannot be read or do not belong to any `DepotEntry` are skipped and returned in the report
+pub fn import(depot: &mut Depot, path: &PathBuf, profile: &BrokerProfile) -> Result<ImportReport, Error>
+{
+    let mut report = ImportReport::default();
+    let rows = _read_rows(path, profile, &mut report.unmatched)?;
+
+    // depot key -> (year, month) -> sum of that month
+    let mut per_entry: BTreeMap<u64, BTreeMap<(u16, u8), MonthSum>> = BTreeMap::new();
+    for row in rows {
+        let Some(key) = _find_entry(depot, &row) else {
+            report.unmatched.push(UnmatchedRow {
+                line: row.line,
+                isin: row.isin,
+                name: row.name,
+                reason: String::from("there is no depot entry with this ISIN or name"),
+            });
+            continue;
+        };
+
+        let month_sum = per_entry.entry(key).or_default().entry((row.date.year(), row.date.month())).or_default();
+        month_sum.units += row.units;
+        month_sum.cash_flow += row.cash_flow;
+        if row.price.is_some() {
+            month_sum.price = row.price;
+        }
+    }
+
+    for (key, months) in per_entry {
+        let Some(entry) = depot.entries.get_mut(&key) else {
+            continue;
+        };
+        let (Some(&(first_year, first_month)), Some(&(last_year, last_month))) = (months.keys().next(), months.keys().last()) else {
+            continue;
+        };
+
+        let (year_before, month_before) = match first_month {
+            1 => (first_year - 1, 12),
+            m => (first_year, m - 1),
+        };
+        let mut holding = match entry.history.get(&year_before) {
+            Some(year) => year.months[month_before as usize - 1].amount(),
+            None => Quantity::ZERO,
+        };
+
+        let (mut year, mut month) = (first_year, first_month);
+        while (year, month) <= (last_year, last_month) {
+            let planned = entry.get_planned_transactions(FastDate::new_risky(year, month, 1));
+            let investment_month = &mut entry.history.entry(year).or_insert_with(|| InvestmentYear::default(year)).months[month as usize - 1];
+
+            if let Some(month_sum) = months.get(&(year, month)) {
+                holding += month_sum.units;
+                if let Some(price) = month_sum.price {
+                    investment_month.set_price_per_unit(price);
+                }
+                investment_month.set_additional_transactions(month_sum.cash_flow - planned);
+            }
+            investment_month.set_amount(holding);
+
+            report.changed.push(ChangedMonth {
+                entry_name: String::from(entry.name()),
+                year,
+                month,
+            });
+
+            (year, month) = match month {
+                12 => (year + 1, 1),
+                m => (year, m + 1),
+            };
+        }
+    }
+
+    depot.ensure_uniform_histories();
+    return Ok(report);
+}
+
+// ================================================== Private ================================================== //
+
+struct Row
+{
+    line: usize,
+    date: FastDate,
+    isin: String,
+    name: String,
+    /// negative if sold
+    units: Quantity,
+    price: Option<Quantity>,
+    /// positive if money was invested
+    cash_flow: Money,
+}
+
+#[derive(Default)]
+struct MonthSum
+{
+    units: Quantity,
+    price: Option<Quantity>,
+    cash_flow: Money,
+}
+
+/// Rows that cannot be read are added to `unmatched`, only a missing column is an error
+fn _read_rows(path: &PathBuf, profile: &BrokerProfile, unmatched: &mut Vec<UnmatchedRow>) -> Result<Vec<Row>, Error>
+{
+    let mut lines = get_csv_contents_with_header(path)?.into_iter();
+    let header = lines.next().unwrap_or_default();
+
+    let column = |name: &str| -> Result<usize, Error> {
+        return match header.iter().position(|h| h.trim_start_matches('\u{feff}').trim() == name) {
+            Some(index) => Ok(index),
+            None => Err(Error::Parse {
+                path: path.clone(),
+                line: Some(1),
+                column: None,
+                message: format!("there is no column called {name:?}, the columns are: {}", header.join(", ")),
+            }),
+        };
+    };
+    let optional_column = |name: &Option<String>| -> Result<Option<usize>, Error> {
+        return match name {
+            Some(name) => column(name).map(Some),
+            None => Ok(None),
+        };
+    };
+
+    let date_col = column(&profile.date)?;
+    let units_col = column(&profile.units)?;
+    let price_col = column(&profile.price)?;
+    let cash_flow_col = column(&profile.cash_flow)?;
+    let isin_col = optional_column(&profile.isin)?;
+    let name_col = optional_column(&profile.name)?;
+    let kind_col = optional_column(&profile.kind)?;
+
+    let mut rows: Vec<Row> = Vec::new();
+    for (index, cells) in lines.enumerate() {
+        if cells.iter().all(|c| c.trim().is_empty()) {
+            continue;
+        }
+
+        let cell = |col: Option<usize>| col.and_then(|c| cells.get(c)).map(|c| c.trim()).unwrap_or_default();
+        let mut unmatched_row = UnmatchedRow {
+            line: index + 2, // header is line 1
+            isin: String::from(cell(isin_col)),
+            name: String::from(cell(name_col)),
+            reason: String::new(),
+        };
+
+        let date = match NaiveDate::parse_from_str(cell(Some(date_col)), &profile.date_format) {
+            Ok(d) => FastDate::new(d.year() as u16, d.month() as u8, d.day() as u8)?,
+            Err(_) => {
+                unmatched_row.reason = format!("{:?} is not a date like {}", cell(Some(date_col)), profile.date_format);
+                unmatched.push(unmatched_row);
+                continue;
+            }
+        };
+
+        // empty cells are 0, eg. dividends have no units and price
+        let numbers = profile.locale.parse_quantity(cell(Some(units_col))).and_then(|units| {
+            let price = profile.locale.parse_quantity(cell(Some(price_col)))?;
+            let cash_flow = profile.locale.parse_money(cell(Some(cash_flow_col)))?;
+            return Ok((units, price, cash_flow));
+        });
+        let (mut units, price, mut cash_flow) = match numbers {
+            Ok(numbers) => numbers,
+            Err(e) => {
+                unmatched_row.reason = e.to_string();
+                unmatched.push(unmatched_row);
+                continue;
+            }
+        };
+
+        if profile.invert_cash_flow {
+            cash_flow = -cash_flow;
+        }
+        if kind_col.is_some() && profile.sell_values.iter().any(|v| v == cell(kind_col)) {
+            units = -units.abs();
+            cash_flow = -cash_flow.abs();
+        }
+
+        rows.push(Row {
+            line: unmatched_row.line,
+            date,
+            isin: unmatched_row.isin,
+            name: unmatched_row.name,
+            units,
+            price: (!price.is_zero()).then_some(price.abs()),
+            cash_flow,
+        });
+    }
+
+    return Ok(rows);
+}
+
+/// ISIN first, name second, both case insensitive
+fn _find_entry(depot: &Depot, row: &Row) -> Option<u64>
+{
+    if !row.isin.is_empty() {
+        let by_isin = depot
+            .entries
+            .iter()
+            .find(|(_, e)| e.isin.as_ref().is_some_and(|isin| isin.eq_ignore_ascii_case(&row.isin)));
+        if let Some((key, _)) = by_isin {
+            return Some(*key);
+        }
+    }
+    if !row.name.is_empty() {
+        let by_name = depot.entries.iter().find(|(_, e)| e.name().to_lowercase() == row.name.to_lowercase());
+        if let Some((key, _)) = by_name {
+            return Some(*key);
+        }
+    }
+    return None;
+}
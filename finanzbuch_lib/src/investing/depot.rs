@@ -1,8 +1,21 @@
+use crate::currency::Currency;
 use crate::fast_date::FastDate;
 use crate::CurrentDate;
+use crate::Error;
+use crate::Money;
+use crate::Quantity;
 
+use super::cost_basis;
+use super::cost_basis::CostBasis;
+use super::cost_basis::CostBasisMethod;
+use super::depot_transaction::DepotTransaction;
 use super::inv_variant::InvestmentVariant;
 use super::inv_year::InvestmentYear;
+use super::performance;
+use super::performance::Performance;
+use super::running_costs;
+use super::running_costs::ExpenseRatio;
+use super::running_costs::RunningCosts;
 use super::savings_plan_section::SavingsPlanSection;
 use super::SavingsPlanInterval;
 use core::panic;
@@ -109,6 +122,53 @@ impl Depot
 
         return Some(oldest_year);
     }
+
+    /// Returns of all entries together, or only of the entries of `variant`. See `DepotEntry::performance()`.
+    /// The values are added in the currencies of the entries, see `DataFile::depot_performance()` for depots with more than one currency
+    pub fn performance(&self, variant: Option<&InvestmentVariant>, first: (u16, u8), last: (u16, u8)) -> Result<Performance, Error>
+    {
+        let mut all = Vec::new();
+        for entry in self.entries.values().filter(|e| variant.map_or(true, |v| e.variant == *v)) {
+            all.push(performance::month_values(entry, first, last)?);
+        }
+        let values = performance::sum_month_values(all, first, last)?;
+        return Ok(performance::calculate(&values, first, last));
+    }
+
+    /// What the whole depot would be worth in each month from `first` to `last`, if every planned and additional transaction
+    /// had grown by `growth_rate` percent per year. The values are added in the currencies of the entries,
+    /// see `DataFile::depot_prognosis()` for depots with more than one currency
+    pub fn prognosis(&self, growth_rate: u8, first: (u16, u8), last: (u16, u8)) -> Result<Vec<Money>, Error>
+    {
+        let mut all = Vec::new();
+        for entry in self.entries.values() {
+            all.push(performance::month_values(entry, first, last)?);
+        }
+        let values = performance::sum_month_values(all, first, last)?;
+        return Ok(performance::prognosis(&values, growth_rate));
+    }
+
+    /// Sum of `DepotEntry::running_costs()` of all entries. The values are added in the currencies of the entries,
+    /// see `DataFile::depot_running_costs()` for depots with more than one currency
+    pub fn running_costs(&self, first: (u16, u8), last: (u16, u8)) -> Result<RunningCosts, Error>
+    {
+        let mut all = Vec::new();
+        for entry in self.entries.values() {
+            all.push(entry.running_costs(first, last)?);
+        }
+        return Ok(running_costs::sum(all));
+    }
+
+    /// Sum of `DepotEntry::cost_basis()` of all entries. The values are added in the currencies of the entries,
+    /// see `DataFile::depot_cost_basis()` for depots with more than one currency
+    pub fn cost_basis(&self, method: CostBasisMethod, year: u16, month: u8) -> CostBasis
+    {
+        let mut sum = CostBasis::default();
+        for entry in self.entries.values() {
+            sum += entry.cost_basis(method, year, month);
+        }
+        return sum;
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -118,7 +178,20 @@ pub struct DepotEntry
     // if this data is given out, then the name changes, then this element cannot be found anymore, because the hash didnt change
     name: String,
     pub variant: InvestmentVariant,
+    /// Used to find this entry when importing the export of a broker. Not every investment has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isin: Option<String>,
+    /// Currency of `price_per_unit` and all transactions of this entry
+    #[serde(default, skip_serializing_if = "Currency::is_default")]
+    pub currency: Currency,
     savings_plan: Vec<SavingsPlanSection>, // this has to be sorted after every modification
+    /// Optional and sorted by date. The months from the first transaction on are calculated from them,
+    /// see `add_transaction()`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    transactions: Vec<DepotTransaction>,
+    /// Optional and sorted by month, the total expense ratio (TER) of each month is the newest change at or before it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    expense_ratios: Vec<ExpenseRatio>,
 
     /// Key is `YearNr`
     ///
@@ -142,7 +215,11 @@ impl DepotEntry
         return Self {
             variant,
             name,
+            isin: None,
+            currency: Currency::default(),
             savings_plan,
+            transactions: vec![],
+            expense_ratios: vec![],
             history,
         };
     }
@@ -152,7 +229,11 @@ impl DepotEntry
         return Self {
             variant,
             name: String::from(name),
+            isin: None,
+            currency: Currency::default(),
             savings_plan: vec![],
+            transactions: vec![],
+            expense_ratios: vec![],
             history: BTreeMap::new(),
         };
     }
@@ -169,50 +250,43 @@ impl DepotEntry
     // ---------- Getters ----------
     pub fn name(&self) -> &str { &self.name }
     pub fn savings_plan(&self) -> &[SavingsPlanSection] { self.savings_plan.as_ref() }
+    /// Sorted by date
+    pub fn transactions(&self) -> &[DepotTransaction] { self.transactions.as_ref() }
+    /// Sorted by month
+    pub fn expense_ratios(&self) -> &[ExpenseRatio] { self.expense_ratios.as_ref() }
 
     // ---------- Remaining Methods ----------
 
-    /// Will only return with `Err(Some(SavingsPlanSection))` if the given `section`'s start / end date is inside an existing section.
-    /// If this is the case, the existing section is returned.
+    /// Will only return with `Err(OverlappingSavingsPlan)` if the given `section`'s start / end date is inside an existing section.
+    /// If this is the case, the existing section is returned inside the error.
     ///
-    /// If the given section has a wrong format (eg. start after end), `Err(None)` will be returned
-    pub fn add_savings_plan_section(&mut self, mut new: SavingsPlanSection) -> Result<(), Option<SavingsPlanSection>>
+    /// If the given section has a wrong format (eg. start after end), `Err(InvalidSavingsPlan)` will be returned
+    ///
+    /// With an annual interval, the end date is moved to the month and day of the start, at least one year after it.
+    /// The stored section can be read with `savings_plan()`
+    pub fn add_savings_plan_section(&mut self, mut new: SavingsPlanSection) -> Result<(), Error>
     {
         // Since the given FastDate's are already checked for correct month and day values, ::new_risky can be used here
 
         // end is before start
         if new.end <= new.start {
-            return Err(None);
+            return Err(Error::InvalidSavingsPlan(String::from("the end date has to be after the start date")));
         }
 
         // if annually, check that end is one year ahead of start, if not, override end
         if new.interval == SavingsPlanInterval::Annually {
             if new.end.year() == new.start.year() {
                 new.end = FastDate::new_risky(new.start.year() + 1, new.start.month(), new.start.day());
-                println!(
-                    "Annual interval was selected, but the end date is not at least one year after the start date. \
-                    End date has been set to: {}-{}-{}",
-                    new.end.year(),
-                    new.end.month(),
-                    new.end.day()
-                );
             }
 
             if (new.end.month() != new.start.month()) || (new.end.day() != new.start.day()) {
                 new.end = FastDate::new_risky(new.end.year(), new.start.month(), new.start.day());
-                println!(
-                    "Annual interval was selected, but the section does not end on the same month and date at which it starts. \
-                    End date has been set to: {}-{}-{}",
-                    new.end.year(),
-                    new.end.month(),
-                    new.end.day()
-                );
             }
         }
 
         // since months and years are inclusive, both month values cant be the same if in the same year
-        if new.start > new.end || new.end < new.start {
-            return Err(None); // start is not before end
+        if new.start > new.end {
+            return Err(Error::InvalidSavingsPlan(String::from("the end date has to be after the start date")));
         }
 
         // this entire function fails if the vec is not ordered
@@ -221,6 +295,7 @@ impl DepotEntry
 
         if self.savings_plan.len() == 0 {
             self.savings_plan.push(new.clone());
+            self._savings_plan_changed();
             return Ok(());
         }
 
@@ -229,11 +304,12 @@ impl DepotEntry
             if new.end < this.start {
                 // new is before this section
                 self.savings_plan.insert(current_id, new.clone());
+                break;
             }
             //
             else if (new.end == this.start) || (new.start < this.end && new.end > this.start) || (new.start == this.end) {
                 // overlapping (either because some dates are the same (not allow because inclusive), or because some dates are inside the other timeframe)
-                return Err(Some(this.clone()));
+                return Err(Error::OverlappingSavingsPlan(this.clone()));
             }
             //
             else if new.start > this.end {
@@ -257,6 +333,39 @@ impl DepotEntry
         }
 
         Self::_order_savings_plan(&mut self.savings_plan);
+        self._savings_plan_changed();
+        return Ok(());
+    }
+
+    /// Index as in `savings_plan()`. Returns `Err(InvalidSavingsPlan)` if there is no section with this index
+    pub fn remove_savings_plan_section(&mut self, index: usize) -> Result<SavingsPlanSection, Error>
+    {
+        if index >= self.savings_plan.len() {
+            return Err(Error::InvalidSavingsPlan(format!(
+                "there is no section number {}",
+                index.saturating_add(1)
+            )));
+        }
+        let removed = self.savings_plan.remove(index);
+        self._savings_plan_changed();
+        return Ok(removed);
+    }
+
+    /// Replaces the section at `index` of `savings_plan()`, with the same checks as `add_savings_plan_section()`.
+    /// The other sections are checked for overlaps again, nothing is changed if the new section is not valid
+    pub fn update_savings_plan_section(&mut self, index: usize, new: SavingsPlanSection) -> Result<(), Error>
+    {
+        if index >= self.savings_plan.len() {
+            return Err(Error::InvalidSavingsPlan(format!(
+                "there is no section number {}",
+                index.saturating_add(1)
+            )));
+        }
+        let old = self.savings_plan.remove(index);
+        if let Err(e) = self.add_savings_plan_section(new) {
+            self.savings_plan.insert(index, old);
+            return Err(e);
+        }
         return Ok(());
     }
 
@@ -264,7 +373,7 @@ impl DepotEntry
     /// - If there is a plan, but this plan is annually, the savings plans amount will only be returned if `month_nr` is `12`
     /// - If the plan is monthly, the savings plans amount is returned
     /// - If there is no plan, `0.0` is returned
-    pub fn get_planned_transactions(&self, date: FastDate) -> f64
+    pub fn get_planned_transactions(&self, date: FastDate) -> Money
     {
         for section in self.savings_plan() {
             // is the given date in this section?
@@ -278,7 +387,150 @@ impl DepotEntry
         }
 
         // no section that contains the date was found
-        return 0.0;
+        return Money::ZERO;
+    }
+
+    /// Adds the transaction after all transactions of the same day. Afterwards the months from its month on are calculated:
+    /// - `amount` is the sum of the units of all transactions up to the end of the month, it is 0 before the first transaction.
+    ///   It is carried forward up to the last month of the history
+    /// - `price_per_unit` is the price of the last buy or sell in the month, months without one keep their price
+    /// - `additional_transactions` is the sum of all cash flows in the month without the planned transactions of the savings plan,
+    ///   because these are part of the log as well. Months after the last transaction keep their value
+    ///
+    /// Missing years are created, use `Depot::ensure_uniform_histories()` afterwards.
+    /// Returns `Err(InvalidTransaction)` if more units would be sold than are held, nothing is changed then
+    pub fn add_transaction(&mut self, transaction: DepotTransaction) -> Result<(), Error>
+    {
+        let from = (transaction.date.year(), transaction.date.month());
+        let index = self.transactions.partition_point(|t| t.date <= transaction.date);
+        let mut transactions = self.transactions.clone();
+        transactions.insert(index, transaction);
+        _check_holdings(&transactions)?;
+
+        self.transactions = transactions;
+        self._calculate_months(from);
+        return Ok(());
+    }
+
+    /// Index as in `transactions()`. The months from its month on are calculated again, see `add_transaction()`.
+    /// Removing the last transaction keeps the values of the months, so they can be changed by hand afterwards
+    ///
+    /// Returns `Err(InvalidTransaction)` if the index does not exist or more units would be sold than are held without it
+    pub fn remove_transaction(&mut self, index: usize) -> Result<DepotTransaction, Error>
+    {
+        if index >= self.transactions.len() {
+            return Err(Error::InvalidTransaction(format!("there is no transaction with the index {index}")));
+        }
+        let mut transactions = self.transactions.clone();
+        let removed = transactions.remove(index);
+        _check_holdings(&transactions)?;
+
+        self.transactions = transactions;
+        let from = (removed.date.year(), removed.date.month());
+        self._calculate_months(from);
+        return Ok(removed);
+    }
+
+    /// Writes the values of the transactions into the months from `from` on, see `add_transaction()`.
+    /// The cash flow of `from` is always written, so that the month of a removed transaction is reset
+    fn _calculate_months(&mut self, from: (u16, u8))
+    {
+        let Some(last) = self.transactions.last() else {
+            return;
+        };
+        let last_transaction = (last.date.year(), last.date.month());
+        let cash_flows_until = from.max(last_transaction);
+        let last_history = self.history.last_key_value().map(|(y, _)| (*y, 12)).unwrap_or_default();
+        let until = last_history.max(last_transaction);
+
+        let mut holding = self
+            .transactions
+            .iter()
+            .filter(|t| (t.date.year(), t.date.month()) < from)
+            .fold(Quantity::ZERO, |sum, t| sum + t.units_change());
+
+        let (mut year, mut month) = from;
+        while (year, month) <= until {
+            let mut cash_flow = Money::ZERO;
+            let mut price = None;
+            for t in self.transactions.iter().filter(|t| (t.date.year(), t.date.month()) == (year, month)) {
+                holding += t.units_change();
+                cash_flow += t.cash_flow();
+                price = t.market_price().or(price);
+            }
+
+            let planned = self.get_planned_transactions(FastDate::new_risky(year, month, 1));
+            let investment_month = &mut self.history.entry(year).or_insert_with(|| InvestmentYear::default(year)).months[month as usize - 1];
+            investment_month.set_amount(holding);
+            if let Some(price) = price {
+                investment_month.set_price_per_unit(price);
+            }
+            if (year, month) <= cash_flows_until {
+                investment_month.set_additional_transactions(cash_flow - planned);
+            }
+
+            (year, month) = match month {
+                12 => (year + 1, 1),
+                m => (year, m + 1),
+            };
+        }
+    }
+
+    /// Cost of the held units and gains up to the end of this month, in the currency of this entry.
+    ///
+    /// Before the first transaction (or without any), the savings plan buys for the price of each month.
+    /// From then on only the transactions are used, because they already contain the buys of the savings plan.
+    /// The current value is the `volume()` of the month
+    pub fn cost_basis(&self, method: CostBasisMethod, year: u16, month: u8) -> CostBasis { return cost_basis::calculate(self, method, year, month); }
+
+    /// Time-weighted and money-weighted return from `first` to `last` (both included), in the currency of this entry.
+    ///
+    /// The value of a month is its `volume()`, its cash flow are the planned and additional transactions.
+    /// Returns `Err(InvalidMonth)` if one of the months is not 1-12
+    pub fn performance(&self, first: (u16, u8), last: (u16, u8)) -> Result<Performance, Error>
+    {
+        let values = performance::month_values(self, first, last)?;
+        return Ok(performance::calculate(&values, first, last));
+    }
+
+    /// Adds the change or replaces the change of the same month
+    pub fn set_expense_ratio(&mut self, expense_ratio: ExpenseRatio)
+    {
+        let key = (expense_ratio.year, expense_ratio.month);
+        match self.expense_ratios.binary_search_by(|r| (r.year, r.month).cmp(&key)) {
+            Ok(i) => self.expense_ratios[i] = expense_ratio,
+            Err(i) => self.expense_ratios.insert(i, expense_ratio),
+        }
+    }
+
+    /// Returns the removed change, `None` if there was no change in this month
+    pub fn remove_expense_ratio(&mut self, year: u16, month: u8) -> Option<ExpenseRatio>
+    {
+        let i = self.expense_ratios.iter().position(|r| r.year == year && r.month == month)?;
+        return Some(self.expense_ratios.remove(i));
+    }
+
+    /// The newest change at or before this month, `None` if there are no costs
+    pub fn expense_ratio(&self, year: u16, month: u8) -> Option<&ExpenseRatio>
+    {
+        return self.expense_ratios.iter().rev().find(|r| (r.year, r.month) <= (year, month));
+    }
+
+    /// Estimated costs of the expense ratio of each month from `first` to `last`,
+    /// and what the entry would be worth without them
+    pub fn running_costs(&self, first: (u16, u8), last: (u16, u8)) -> Result<RunningCosts, Error>
+    {
+        let values = performance::month_values(self, first, last)?;
+        return Ok(running_costs::calculate(self, &values));
+    }
+
+    /// The planned transactions are part of the cash flows of the transactions, see `add_transaction()`
+    fn _savings_plan_changed(&mut self)
+    {
+        if let Some(first) = self.transactions.first() {
+            let from = (first.date.year(), first.date.month());
+            self._calculate_months(from);
+        }
     }
 
     /// orders the given `savings_plan` ascending
@@ -288,3 +540,24 @@ impl DepotEntry
         savings_plan.sort_unstable_by(|a, b| a.start.cmp(&b.start));
     }
 }
+
+// ================================================== Private ================================================== //
+
+/// `Err(InvalidTransaction)` if the units become negative at some point. `transactions` have to be sorted by date
+fn _check_holdings(transactions: &[DepotTransaction]) -> Result<(), Error>
+{
+    let mut holding = Quantity::ZERO;
+    for t in transactions {
+        holding += t.units_change();
+        if holding < Quantity::ZERO {
+            return Err(Error::InvalidTransaction(format!(
+                "after the {} on {}-{:0>2}-{:0>2} there would be {holding} units",
+                t.kind.to_string().to_lowercase(),
+                t.date.year(),
+                t.date.month(),
+                t.date.day()
+            )));
+        }
+    }
+    return Ok(());
+}
@@ -0,0 +1,135 @@
+use crate::fast_date::FastDate;
+use crate::Error;
+use crate::Money;
+use crate::Quantity;
+use serde::Deserialize;
+use serde::Serialize;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum DepotTransactionKind
+{
+    Buy,
+    Sell,
+    Dividend,
+    Fee,
+    Tax,
+    Split,
+}
+impl FromStr for DepotTransactionKind
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        match s.to_lowercase().as_str() {
+            "buy" => Ok(Self::Buy),
+            "sell" => Ok(Self::Sell),
+            "dividend" => Ok(Self::Dividend),
+            "fee" => Ok(Self::Fee),
+            "tax" => Ok(Self::Tax),
+            "split" => Ok(Self::Split),
+            _ => Err(Error::InvalidTransaction(format!(
+                "{s} is not a kind of transaction, use buy, sell, dividend, fee, tax or split"
+            ))),
+        }
+    }
+}
+impl std::fmt::Display for DepotTransactionKind
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self {
+            DepotTransactionKind::Buy => write!(f, "Buy"),
+            DepotTransactionKind::Sell => write!(f, "Sell"),
+            DepotTransactionKind::Dividend => write!(f, "Dividend"),
+            DepotTransactionKind::Fee => write!(f, "Fee"),
+            DepotTransactionKind::Tax => write!(f, "Tax"),
+            DepotTransactionKind::Split => write!(f, "Split"),
+        }
+    }
+}
+
+/// One transaction of a `DepotEntry`, as it is listed on the statement of the broker
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct DepotTransaction
+{
+    pub date: FastDate,
+    pub kind: DepotTransactionKind,
+    /// - Buy and sell: the traded units
+    /// - Dividend: the units the dividend was paid for
+    /// - Split: the units that were added, negative for a reverse split
+    #[serde(default, skip_serializing_if = "Quantity::is_zero")]
+    units: Quantity,
+    /// Buy and sell: price of one unit. Dividend: payout per unit
+    #[serde(default, skip_serializing_if = "Quantity::is_zero")]
+    price: Quantity,
+    /// Fees and taxes of this transaction. For `Fee` and `Tax` this is the whole amount
+    #[serde(default, skip_serializing_if = "Money::is_zero")]
+    fees: Money,
+}
+impl DepotTransaction
+{
+    /// Units (except for splits), price and fees are stored as absolute values.
+    ///
+    /// Returns `Err(InvalidTransaction)` if a value that this kind needs is 0:
+    /// units and price for buy, sell and dividend, fees for fee and tax, units for split
+    pub fn new(date: FastDate, kind: DepotTransactionKind, units: Quantity, price: Quantity, fees: Money) -> Result<Self, Error>
+    {
+        let missing = match kind {
+            DepotTransactionKind::Buy | DepotTransactionKind::Sell | DepotTransactionKind::Dividend if units.is_zero() => Some("units"),
+            DepotTransactionKind::Buy | DepotTransactionKind::Sell | DepotTransactionKind::Dividend if price.is_zero() => Some("a price"),
+            DepotTransactionKind::Fee | DepotTransactionKind::Tax if fees.is_zero() => Some("an amount in fees"),
+            DepotTransactionKind::Split if units.is_zero() => Some("units"),
+            _ => None,
+        };
+        if let Some(missing) = missing {
+            return Err(Error::InvalidTransaction(format!(
+                "a {} needs {missing}",
+                kind.to_string().to_lowercase()
+            )));
+        }
+
+        return Ok(Self {
+            date,
+            kind,
+            units: if kind == DepotTransactionKind::Split { units } else { units.abs() },
+            price: price.abs(),
+            fees: fees.abs(),
+        });
+    }
+
+    pub fn units(&self) -> Quantity { self.units }
+    pub fn price(&self) -> Quantity { self.price }
+    pub fn fees(&self) -> Money { self.fees }
+
+    /// How the number of held units changes: positive for buys, negative for sells
+    pub fn units_change(&self) -> Quantity
+    {
+        return match self.kind {
+            DepotTransactionKind::Buy | DepotTransactionKind::Split => self.units,
+            DepotTransactionKind::Sell => -self.units,
+            DepotTransactionKind::Dividend | DepotTransactionKind::Fee | DepotTransactionKind::Tax => Quantity::ZERO,
+        };
+    }
+
+    /// Positive if money was invested, like the cash flow of `csv_import`.
+    /// Sells and dividends take money out, fees and taxes are paid in addition to it
+    pub fn cash_flow(&self) -> Money
+    {
+        return match self.kind {
+            DepotTransactionKind::Buy => self.units.times(self.price) + self.fees,
+            DepotTransactionKind::Sell | DepotTransactionKind::Dividend => self.fees - self.units.times(self.price),
+            DepotTransactionKind::Fee | DepotTransactionKind::Tax | DepotTransactionKind::Split => self.fees,
+        };
+    }
+
+    /// The price of one unit on the market, only buys and sells have one
+    pub fn market_price(&self) -> Option<Quantity>
+    {
+        return match self.kind {
+            DepotTransactionKind::Buy | DepotTransactionKind::Sell => Some(self.price),
+            _ => None,
+        };
+    }
+}
@@ -1,7 +1,9 @@
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::SanitizeInput;
+use crate::Error;
+use crate::Money;
+use crate::Quantity;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct InvestmentMonth
@@ -11,58 +13,63 @@ pub struct InvestmentMonth
 
     /// Dont round off, this value actually needs full precision
     /// only positive allowed
-    amount: f64,
+    amount: Quantity,
 
     /// what was the price per share at the time of adding this data?
     /// Dont round off, this value actually needs full precision
     /// only positive allowed
-    price_per_unit: f64,
+    price_per_unit: Quantity,
 
     /// (eg. dividends), these are not excluded from amount and price
     /// negative and positive allowed
-    additional_transactions: f64,
+    additional_transactions: Money,
 }
 impl InvestmentMonth
 {
-    /// Will panic if month_nr not 1-12
-    pub fn default(month_nr: u8) -> Self
+    /// Returns `Err(InvalidMonth)` if month_nr not 1-12
+    pub fn default(month_nr: u8) -> Result<Self, Error>
     {
         if month_nr > 12 || month_nr == 0 {
-            panic!("month_nr out of bounds");
-        }
-        Self {
-            month_nr,
-            amount: 0.0,
-            price_per_unit: 0.0,
-            additional_transactions: 0.0,
+            return Err(Error::InvalidMonth(month_nr));
         }
+        Ok(Self::default_unchecked(month_nr))
     }
 
-    /// Will panic if month_nr not 1-12
-    pub fn new(month_nr: u8, amount: f64, price_per_unit: f64, additional_transactions: f64) -> Self
+    /// Returns `Err(InvalidMonth)` if month_nr not 1-12
+    pub fn new(month_nr: u8, amount: Quantity, price_per_unit: Quantity, additional_transactions: Money) -> Result<Self, Error>
     {
         if month_nr > 12 || month_nr == 0 {
-            panic!("month_nr out of bounds");
+            return Err(Error::InvalidMonth(month_nr));
         }
-        Self {
+        Ok(Self {
             month_nr,
             amount: amount.abs(),
             price_per_unit: price_per_unit.abs(),
-            additional_transactions: SanitizeInput::f64_to_monetary_f64(additional_transactions),
+            additional_transactions,
+        })
+    }
+
+    /// The month_nr has to be checked by the caller
+    pub(crate) fn default_unchecked(month_nr: u8) -> Self
+    {
+        Self {
+            month_nr,
+            amount: Quantity::ZERO,
+            price_per_unit: Quantity::ZERO,
+            additional_transactions: Money::ZERO,
         }
     }
 
     // ---------- Getters ----------
     pub fn month_nr(&self) -> u8 { self.month_nr }
-    pub fn amount(&self) -> f64 { self.amount }
-    pub fn price_per_unit(&self) -> f64 { self.price_per_unit }
-    pub fn additional_transactions(&self) -> f64 { self.additional_transactions }
+    pub fn amount(&self) -> Quantity { self.amount }
+    pub fn price_per_unit(&self) -> Quantity { self.price_per_unit }
+    pub fn additional_transactions(&self) -> Money { self.additional_transactions }
+    /// `amount * price_per_unit`, rounded to whole cents
+    pub fn volume(&self) -> Money { self.amount.times(self.price_per_unit) }
 
     // ---------- Setters ----------
-    pub fn set_amount(&mut self, amount: f64) { self.amount = amount.abs(); }
-    pub fn set_price_per_unit(&mut self, price_per_unit: f64) { self.price_per_unit = price_per_unit.abs(); }
-    pub fn set_additional_transactions(&mut self, additional_transactions: f64)
-    {
-        self.additional_transactions = SanitizeInput::f64_to_monetary_f64(additional_transactions);
-    }
+    pub fn set_amount(&mut self, amount: Quantity) { self.amount = amount.abs(); }
+    pub fn set_price_per_unit(&mut self, price_per_unit: Quantity) { self.price_per_unit = price_per_unit.abs(); }
+    pub fn set_additional_transactions(&mut self, additional_transactions: Money) { self.additional_transactions = additional_transactions; }
 }
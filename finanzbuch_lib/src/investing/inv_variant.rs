@@ -1,3 +1,4 @@
+use crate::Error;
 use serde::Deserialize;
 use serde::Serialize;
 use std::str::FromStr;
@@ -15,7 +16,7 @@ pub enum InvestmentVariant
 }
 impl FromStr for InvestmentVariant
 {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err>
     {
@@ -27,7 +28,7 @@ impl FromStr for InvestmentVariant
             "option" => Ok(Self::Option),
             "commoditiy" => Ok(Self::Commoditiy),
             "crypto" => Ok(Self::Crypto),
-            _ => Err(format!("{s} is not a possible InvestmentVariant")),
+            _ => Err(Error::InvalidVariant(s.to_string())),
         }
     }
 }
@@ -1,9 +1,15 @@
+pub mod cost_basis;
+pub mod csv_import;
 pub mod depot;
+pub mod depot_transaction;
 pub mod inv_months;
 pub mod inv_variant;
 pub mod inv_year;
+pub mod performance;
+pub mod running_costs;
 pub mod savings_plan_section;
 
+use crate::currency::Currency;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -42,6 +48,14 @@ pub struct Investing
     ///
     /// A year will always have all 12 months.
     pub depot: Depot,
+
+    /// Currency the depot overview is shown in, `None` uses the base currency of `DataFile.exchange_rates`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overview_currency: Option<Currency>,
+
+    /// Show the depot overview in prices of the current month, see `DataFile.price_index`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inflation_adjusted: bool,
 }
 impl Default for Investing
 {
@@ -50,6 +64,8 @@ impl Default for Investing
         return Self {
             comparisons: vec![],
             depot: Depot::new(),
+            overview_currency: None,
+            inflation_adjusted: false,
         };
     }
 }
@@ -0,0 +1,254 @@
+use crate::accounting::projection::month_range;
+use crate::fast_date::FastDate;
+use crate::Error;
+use crate::Money;
+use chrono::NaiveDate;
+use std::ops::Add;
+
+use super::depot::DepotEntry;
+
+/// XIRR is searched between -99.99% and +1.000.000% per year
+const XIRR_MIN: f64 = -0.9999;
+const XIRR_MAX: f64 = 10_000.0;
+const XIRR_PRECISION: f64 = 1e-10;
+
+/// Returns of a range of months, see `DepotEntry::performance()`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Performance
+{
+    /// First month of the range as `(year, month)`
+    pub first: (u16, u8),
+    /// Last month of the range
+    pub last: (u16, u8),
+    /// Value at the end of the month before `first`
+    pub start_value: Money,
+    /// Value at the end of `last`
+    pub end_value: Money,
+    /// Planned and additional transactions of the range, positive if money was invested
+    pub cash_flow: Money,
+    /// Time-weighted return of the whole range, 0.05 = 5%. It does not depend on when and how much money was invested
+    pub time_weighted: f64,
+    /// Money-weighted return per year (XIRR), 0.05 = 5%. `None` if there is no solution, eg. because no money was invested
+    pub xirr: Option<f64>,
+}
+impl Performance
+{
+    pub fn months(&self) -> usize { month_range(self.first, self.last).map(|m| m.len()).unwrap_or_default() }
+
+    /// What the values gained without the money that was invested
+    pub fn gain(&self) -> Money { self.end_value - self.start_value - self.cash_flow }
+
+    /// `time_weighted` per year, 0.05 = 5%
+    pub fn annualized_time_weighted(&self) -> f64
+    {
+        let months = self.months();
+        if months == 0 {
+            return 0.0;
+        }
+        return (1.0 + self.time_weighted).powf(12.0 / months as f64) - 1.0;
+    }
+
+    /// Money-weighted return of the whole range, calculated from `xirr`. 0.05 = 5%
+    pub fn money_weighted(&self) -> Option<f64>
+    {
+        let years = _years_between(_first_day(self.first), _first_day(_next_month(self.last)));
+        return self.xirr.map(|xirr| (1.0 + xirr).powf(years) - 1.0);
+    }
+}
+
+/// Value and cash flow of one month
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct MonthValue
+{
+    pub year: u16,
+    pub month: u8,
+    /// `volume()` at the end of the month
+    pub value: Money,
+    /// Planned and additional transactions, positive if money was invested
+    pub cash_flow: Money,
+}
+impl Add for MonthValue
+{
+    type Output = MonthValue;
+    fn add(self, rhs: Self) -> Self::Output
+    {
+        return Self {
+            value: self.value + rhs.value,
+            cash_flow: self.cash_flow + rhs.cash_flow,
+            ..self
+        };
+    }
+}
+
+/// The month before `first` and all months up to `last`. Months without data have no value and no cash flow
+pub(crate) fn month_values(entry: &DepotEntry, first: (u16, u8), last: (u16, u8)) -> Result<Vec<MonthValue>, Error>
+{
+    let mut values = Vec::new();
+    for (year, month) in _range_with_month_before(first, last)? {
+        let value = match entry.history.get(&year) {
+            Some(investment_year) => {
+                let investment_month = &investment_year.months[month as usize - 1];
+                MonthValue {
+                    year,
+                    month,
+                    value: investment_month.volume(),
+                    cash_flow: entry.get_planned_transactions(FastDate::new_risky(year, month, 1)) + investment_month.additional_transactions(),
+                }
+            }
+            None => MonthValue {
+                year,
+                month,
+                ..Default::default()
+            },
+        };
+        values.push(value);
+    }
+    return Ok(values);
+}
+
+/// Adds the values of several entries month by month. All of them need the same months
+pub(crate) fn sum_month_values(all: Vec<Vec<MonthValue>>, first: (u16, u8), last: (u16, u8)) -> Result<Vec<MonthValue>, Error>
+{
+    let mut sum: Vec<MonthValue> = _range_with_month_before(first, last)?
+        .into_iter()
+        .map(|(year, month)| MonthValue {
+            year,
+            month,
+            ..Default::default()
+        })
+        .collect();
+    for values in all {
+        for (s, v) in sum.iter_mut().zip(values) {
+            *s = *s + v;
+        }
+    }
+    return Ok(sum);
+}
+
+/// `values` as returned by `month_values()`, the first one is the month before `first`.
+///
+/// Cash flows are assumed at the start of their month, so they earn the return of that month
+pub(crate) fn calculate(values: &[MonthValue], first: (u16, u8), last: (u16, u8)) -> Performance
+{
+    let start_value = values.first().map(|v| v.value).unwrap_or_default();
+    let end_value = match values.len() {
+        0 | 1 => start_value,
+        _ => values.last().map(|v| v.value).unwrap_or_default(),
+    };
+    let months = values.get(1..).unwrap_or_default();
+
+    let mut growth = 1.0;
+    let mut value_before = start_value;
+    for month in months {
+        let invested = value_before + month.cash_flow;
+        // nothing was invested during this month, so it has no return
+        if invested.is_positive() {
+            growth *= month.value.to_f64() / invested.to_f64();
+        }
+        value_before = month.value;
+    }
+
+    // from the view of the investor: money that goes into the depot is negative
+    let start = _first_day(first);
+    let mut flows: Vec<(f64, f64)> = vec![(0.0, -start_value.to_f64())];
+    for month in months {
+        flows.push((_years_between(start, _first_day((month.year, month.month))), -month.cash_flow.to_f64()));
+    }
+    flows.push((_years_between(start, _first_day(_next_month(last))), end_value.to_f64()));
+
+    return Performance {
+        first,
+        last,
+        start_value,
+        end_value,
+        cash_flow: months.iter().map(|m| m.cash_flow).sum(),
+        time_weighted: growth - 1.0,
+        xirr: _xirr(&flows),
+    };
+}
+
+/// What the depot would be worth if it had grown by `growth_rate` percent per year, one value for each month after
+/// the first of `values` (as returned by `month_values()`).
+///
+/// Starts with the value of the month before and adds the cash flows of each month, which grow from the start of their month
+pub(crate) fn prognosis(values: &[MonthValue], growth_rate: u8) -> Vec<Money>
+{
+    // growth_rate is for one year: 1.07^(1/12) = 1.005654145 and 1.005654145^12 = 1.07
+    let rate_monthly = (1.0 + growth_rate as f64 / 100.0).powf(1.0 / 12.0);
+
+    let mut prognosis = Vec::new();
+    let mut value = values.first().map(|v| v.value.to_f64()).unwrap_or_default();
+    for month in values.iter().skip(1) {
+        value = (value + month.cash_flow.to_f64()) * rate_monthly;
+        prognosis.push(Money::from_f64(value));
+    }
+    return prognosis;
+}
+
+// ================================================== Private ================================================== //
+
+/// `Err(InvalidMonth)` if one of the months is not 1-12
+fn _range_with_month_before(first: (u16, u8), last: (u16, u8)) -> Result<Vec<(u16, u8)>, Error>
+{
+    month_range(first, last)?;
+    let before = match first.1 {
+        1 => (first.0.saturating_sub(1), 12),
+        m => (first.0, m - 1),
+    };
+    return month_range(before, last);
+}
+
+fn _next_month((year, month): (u16, u8)) -> (u16, u8)
+{
+    return match month {
+        12 => (year.saturating_add(1), 1),
+        m => (year, m + 1),
+    };
+}
+
+fn _first_day((year, month): (u16, u8)) -> NaiveDate { NaiveDate::from_ymd_opt(year as i32, month as u32, 1).unwrap_or_default() }
+
+fn _years_between(from: NaiveDate, to: NaiveDate) -> f64 { (to - from).num_days() as f64 / 365.0 }
+
+/// Annual rate at which the present value of all `(years, amount)` is 0.
+/// Newton's method first, bisection if it does not converge
+fn _xirr(flows: &[(f64, f64)]) -> Option<f64>
+{
+    let has_positive = flows.iter().any(|(_, amount)| *amount > 0.0);
+    let has_negative = flows.iter().any(|(_, amount)| *amount < 0.0);
+    if !has_positive || !has_negative {
+        return None;
+    }
+
+    let present_value = |rate: f64| -> f64 { flows.iter().map(|(years, amount)| amount / (1.0 + rate).powf(*years)).sum() };
+    let derivative = |rate: f64| -> f64 { flows.iter().map(|(years, amount)| -years * amount / (1.0 + rate).powf(years + 1.0)).sum() };
+
+    let mut rate = 0.1;
+    for _ in 0..100 {
+        let slope = derivative(rate);
+        if slope == 0.0 || !slope.is_finite() {
+            break;
+        }
+        let next = rate - present_value(rate) / slope;
+        if !next.is_finite() || next <= XIRR_MIN || next >= XIRR_MAX {
+            break;
+        }
+        if (next - rate).abs() < XIRR_PRECISION {
+            return Some(next);
+        }
+        rate = next;
+    }
+
+    let (mut low, mut high) = (XIRR_MIN, XIRR_MAX);
+    if present_value(low).signum() == present_value(high).signum() {
+        return None;
+    }
+    while high - low > XIRR_PRECISION {
+        let middle = (low + high) / 2.0;
+        match present_value(middle).signum() == present_value(low).signum() {
+            true => low = middle,
+            false => high = middle,
+        }
+    }
+    return Some((low + high) / 2.0);
+}
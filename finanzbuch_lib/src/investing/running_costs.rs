@@ -0,0 +1,136 @@
+use crate::Error;
+use crate::Money;
+use serde::Deserialize;
+use serde::Serialize;
+use std::ops::Add;
+
+use super::depot::DepotEntry;
+use super::performance::MonthValue;
+
+/// Total expense ratio (TER) of an entry, valid from this month on until the next change
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ExpenseRatio
+{
+    pub year: u16,
+    pub month: u8,
+    /// Per year, 0.2 = 0.2%
+    pub percent: f64,
+}
+impl ExpenseRatio
+{
+    /// `percent` has to be between 0 and 100
+    pub fn new(year: u16, month: u8, percent: f64) -> Result<Self, Error>
+    {
+        if !(1..=12).contains(&month) {
+            return Err(Error::InvalidMonth(month));
+        }
+        if !percent.is_finite() || !(0.0..100.0).contains(&percent) {
+            return Err(Error::InvalidExpenseRatio(format!(
+                "{percent} is not a valid expense ratio, it has to be between 0 and 100"
+            )));
+        }
+        return Ok(Self { year, month, percent });
+    }
+
+    /// Part of the value that is paid in one month, 0.2% per year are 0.000167 per month
+    pub fn monthly_fraction(&self) -> f64 { self.percent / 100.0 / 12.0 }
+}
+
+/// Estimated running costs of one month, see `DepotEntry::running_costs()`
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct MonthCost
+{
+    pub year: u16,
+    pub month: u8,
+    /// `volume()` at the end of the month, the costs are already paid
+    pub value: Money,
+    /// What was paid for the expense ratio during this month
+    pub cost: Money,
+    /// What the value would be if no costs had been paid since the start of the range, including the growth of the costs
+    pub value_without_costs: Money,
+}
+impl Add for MonthCost
+{
+    type Output = MonthCost;
+    fn add(self, rhs: Self) -> Self::Output
+    {
+        return Self {
+            value: self.value + rhs.value,
+            cost: self.cost + rhs.cost,
+            value_without_costs: self.value_without_costs + rhs.value_without_costs,
+            ..self
+        };
+    }
+}
+
+/// Running costs of a range of months, see `DepotEntry::running_costs()`
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RunningCosts
+{
+    pub months: Vec<MonthCost>,
+}
+impl RunningCosts
+{
+    /// Sum of `cost` of all months
+    pub fn total_cost(&self) -> Money { self.months.iter().map(|m| m.cost).sum() }
+
+    /// How much less the value is at the end of the range because of the costs, including the growth they missed
+    pub fn drag(&self) -> Money
+    {
+        return match self.months.last() {
+            Some(last) => last.value_without_costs - last.value,
+            None => Money::ZERO,
+        };
+    }
+}
+
+/// Adds the costs of several entries month by month. All of them need the same months
+pub(crate) fn sum(all: Vec<RunningCosts>) -> RunningCosts
+{
+    let mut all = all.into_iter();
+    let Some(mut sum) = all.next() else {
+        return RunningCosts::default();
+    };
+    for costs in all {
+        for (s, c) in sum.months.iter_mut().zip(costs.months) {
+            *s = *s + c;
+        }
+    }
+    return sum;
+}
+
+/// `values` as returned by `performance::month_values()`, the first one is the month before the range.
+///
+/// The costs are taken from the value at the end of each month. Without costs, the value of every month
+/// would have grown by the same return plus the costs of that month
+pub(crate) fn calculate(entry: &DepotEntry, values: &[MonthValue]) -> RunningCosts
+{
+    let mut value_before = values.first().map(|v| v.value).unwrap_or_default();
+    let mut without_costs_before = value_before.to_f64();
+
+    let mut months = Vec::new();
+    for month in values.iter().skip(1) {
+        let fraction = entry
+            .expense_ratio(month.year, month.month)
+            .map(|r| r.monthly_fraction())
+            .unwrap_or_default();
+
+        // nothing was invested during this month, so there is no return. The costs that were saved until now stay the same
+        let invested = value_before + month.cash_flow;
+        let without_costs = match invested.is_positive() {
+            true => (without_costs_before + month.cash_flow.to_f64()) * month.value.to_f64() / invested.to_f64() / (1.0 - fraction),
+            false => month.value.to_f64() + without_costs_before - value_before.to_f64(),
+        };
+
+        months.push(MonthCost {
+            year: month.year,
+            month: month.month,
+            value: month.value,
+            cost: month.value.mul_f64(fraction / (1.0 - fraction)),
+            value_without_costs: Money::from_f64(without_costs),
+        });
+        value_before = month.value;
+        without_costs_before = without_costs;
+    }
+    return RunningCosts { months };
+}
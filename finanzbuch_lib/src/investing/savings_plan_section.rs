@@ -1,4 +1,5 @@
 use crate::fast_date::FastDate;
+use crate::Money;
 
 use super::SavingsPlanInterval;
 use serde::Deserialize;
@@ -14,6 +15,6 @@ pub struct SavingsPlanSection
     pub end: FastDate,
 
     /// can be negative
-    pub amount: f64,
+    pub amount: Money,
     pub interval: SavingsPlanInterval,
 }
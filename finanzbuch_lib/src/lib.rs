@@ -1,13 +1,27 @@
 // these have to be public so that the tests in /tests can use this
 pub mod accounting;
+pub mod backup;
+pub mod currency;
 pub mod datafile;
+pub mod encryption;
+pub mod error;
+pub mod export;
 pub mod fast_date;
+pub mod inflation;
 pub mod investing;
+pub mod locale;
+pub mod migration;
+pub mod money;
+pub mod profiles;
 
 pub use crate::accounting::accounting_month::AccountingMonth;
 pub use crate::accounting::Accounting;
 pub use crate::datafile::DataFile;
+pub use crate::error::Error;
 pub use crate::investing::depot::DepotEntry;
+pub use crate::locale::Locale;
+pub use crate::money::Money;
+pub use crate::money::Quantity;
 
 // TODO check what has to be pub
 
@@ -17,7 +31,6 @@ use chrono::Utc;
 use csv::ReaderBuilder;
 use std::fs::File;
 use std::io::Read;
-use std::num::ParseFloatError;
 use std::path::PathBuf;
 use std::time::SystemTime;
 
@@ -46,85 +59,94 @@ impl SanitizeInput
     #[inline(always)]
     /// Rounds to two decimal places, sign not changed
     pub fn f64_to_monetary_f64(float: f64) -> f64 { (float * 100.0).round() / 100.0 }
-
-    /// - Can parse xx.x and xx,x
-    /// - Ignores everything thats not a digit or `.` `,` `+` `-`
-    /// - Does not round
-    /// - Only returns absolute value if stated by `return_abs_value: true`
-    /// - Empty Strings result in value 0.0
-    pub fn string_to_f64(string: &str, return_abs_value: bool) -> Result<f64, ParseFloatError>
-    {
-        if string.is_empty() {
-            return Ok(0.0);
-        }
-        let mut filtered = string.replace(",", ".");
-        filtered.retain(|c| c == '.' || c == '-' || c == '+' || c.is_ascii_digit());
-
-        return match filtered.parse::<f64>() {
-            Ok(expenses) => match return_abs_value {
-                true => Ok(expenses.abs()),
-                false => Ok(expenses),
-            },
-            Err(e) => Err(e),
-        };
-    }
 }
 
 /// Returns all of the csv cells like this: `Lines<Cells>`
-pub fn get_csv_contents_with_header(path: &PathBuf) -> Vec<Vec<String>>
+pub fn get_csv_contents_with_header(path: &PathBuf) -> Result<Vec<Vec<String>>, Error>
 {
-    // open file for reading
-    let mut file: File = match File::options().read(true).truncate(false).open(path) {
-        Ok(file) => file,
-        Err(_) => panic!("Could not open {:?}", path),
-    };
-
-    // read file content
-    let content_string: String = {
-        let mut temp: String = String::new();
-        file.read_to_string(&mut temp).expect(format!("Error reading file {:?}", path).as_str());
-        temp
-    };
+    let content_string: String = _read_csv_to_string(path)?;
 
     let mut reader = ReaderBuilder::new().delimiter(b';').from_reader(content_string.as_bytes());
 
     // get headers
     let header: Vec<String> = match reader.headers() {
         Ok(val) => val.iter().map(|val| val.to_string()).collect(),
-        Err(e) => panic!("Could not get CSV Header: {e}"),
+        Err(e) => return Err(Error::csv(path, e)),
     };
 
     // Get values
     let mut content_vec: Vec<Vec<String>> = Vec::new();
     for record in reader.records() {
-        let line: Vec<String> = record.expect("Could not transform csv record").iter().map(|s| s.to_string()).collect();
+        let line: Vec<String> = match record {
+            Ok(record) => record.iter().map(|s| s.to_string()).collect(),
+            Err(e) => return Err(Error::csv(path, e)),
+        };
         content_vec.push(line);
     }
 
     content_vec.insert(0, header);
 
-    return content_vec;
+    return Ok(content_vec);
+}
+
+/// Reads the csv file at `path` and calls `import_line` with the trimmed cells of `columns` of every line, in the order of `columns`.
+/// The columns can be in any order in the file. Errors of `import_line` are returned with their line number.
+/// Returns the number of lines
+pub(crate) fn import_csv_lines<const N: usize>(
+    path: &PathBuf,
+    columns: [&str; N],
+    mut import_line: impl FnMut([&str; N]) -> Result<(), Error>,
+) -> Result<usize, Error>
+{
+    let content = get_csv_contents_with_header(path)?;
+    let Some((header, lines)) = content.split_first() else {
+        return Ok(0);
+    };
+
+    let mut column_indices = [0; N];
+    for (index, name) in column_indices.iter_mut().zip(columns) {
+        let Some(position) = header.iter().position(|h| h.trim() == name) else {
+            return Err(Error::Parse {
+                path: path.clone(),
+                line: Some(1),
+                column: None,
+                message: format!("the column {name:?} is missing"),
+            });
+        };
+        *index = position;
+    }
+
+    for (i, line) in lines.iter().enumerate() {
+        let cells = column_indices.map(|col| line.get(col).map(|c| c.trim()).unwrap_or_default());
+        if let Err(e) = import_line(cells) {
+            // + 1 for the header, + 1 because lines start at 1
+            return Err(Error::Parse {
+                path: path.clone(),
+                line: Some(i + 2),
+                column: None,
+                message: e.to_string(),
+            });
+        }
+    }
+
+    return Ok(lines.len());
 }
 
 // ================================================== Private ================================================== //
 
-fn _read_csv_to_string(path: &PathBuf) -> String
+fn _read_csv_to_string(path: &PathBuf) -> Result<String, Error>
 {
     // open file for reading
     let mut file: File = match File::options().read(true).truncate(false).open(path) {
         Ok(file) => file,
-        Err(_) => panic!("Could not open {:?}", path),
+        Err(e) => return Err(Error::io(path, e)),
     };
 
     // read file content
-    let content: String = {
-        let mut temp: String = String::new();
-        match file.read_to_string(&mut temp) {
-            Ok(v) => v,
-            Err(_) => panic!("Error reading file {:?}", path),
-        };
-        temp
-    };
+    let mut content: String = String::new();
+    if let Err(e) = file.read_to_string(&mut content) {
+        return Err(Error::io(path, e));
+    }
 
-    return content;
+    return Ok(content);
 }
@@ -0,0 +1,247 @@
+use crate::Error;
+use crate::Money;
+use crate::Quantity;
+use serde::Deserialize;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Signs that are removed before and after a number, together with currency codes like `EUR` or `Fr.`
+const CURRENCY_SIGNS: [char; 4] = ['€', '$', '£', '¥'];
+/// Spaces can always separate thousands, like `1 234,56`
+const SPACES: [char; 3] = [' ', '\u{a0}', '\u{202f}'];
+
+/// How numbers are written: which character separates the decimals and which one the thousands
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, Copy, Default)]
+pub enum Locale
+{
+    /// `1.234,56`
+    #[default]
+    #[serde(rename = "de-DE")]
+    DeDe,
+    /// `1,234.56`
+    #[serde(rename = "en-US")]
+    EnUs,
+    /// `1'234.56`
+    #[serde(rename = "de-CH")]
+    DeCh,
+}
+impl std::fmt::Display for Locale
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "{}", self.code()) }
+}
+impl FromStr for Locale
+{
+    type Err = Error;
+
+    /// Case does not matter and `_` works as well: `de_de` is `de-DE`
+    fn from_str(string: &str) -> Result<Self, Self::Err>
+    {
+        let code = string.trim().replace('_', "-");
+        return Self::ALL
+            .into_iter()
+            .find(|l| l.code().eq_ignore_ascii_case(&code))
+            .ok_or_else(|| Error::UnknownLocale(String::from(string)));
+    }
+}
+impl Locale
+{
+    pub const ALL: [Locale; 3] = [Locale::DeDe, Locale::EnUs, Locale::DeCh];
+
+    pub fn is_default(&self) -> bool { return *self == Self::default(); }
+
+    pub fn code(&self) -> &'static str
+    {
+        return match self {
+            Locale::DeDe => "de-DE",
+            Locale::EnUs => "en-US",
+            Locale::DeCh => "de-CH",
+        };
+    }
+
+    pub fn decimal_separator(&self) -> char
+    {
+        return match self {
+            Locale::DeDe => ',',
+            Locale::EnUs | Locale::DeCh => '.',
+        };
+    }
+
+    /// Used by `format_*()`. `parse_*()` accepts spaces as well, and `’` in de-CH
+    pub fn thousands_separator(&self) -> char
+    {
+        return match self {
+            Locale::DeDe => '.',
+            Locale::EnUs => ',',
+            Locale::DeCh => '\'',
+        };
+    }
+
+    /// - Thousands separators are optional, but if they are used, every group after them needs three digits
+    /// - Currency signs and codes before or after the number are ignored: `-1.234,56 €`, `CHF 12.50`
+    /// - The sign can be in front or at the end: `12,50-`
+    /// - More than two decimal places are rounded half away from zero
+    /// - Empty Strings result in 0
+    pub fn parse_money(&self, string: &str) -> Result<Money, Error>
+    {
+        let normalized = self._normalize(string)?;
+        return normalized.parse::<Money>().map_err(|_| self._error(string, "it is too large"));
+    }
+
+    /// Same rules as `parse_money()`, with up to eight decimal places
+    pub fn parse_quantity(&self, string: &str) -> Result<Quantity, Error>
+    {
+        let normalized = self._normalize(string)?;
+        return normalized.parse::<Quantity>().map_err(|_| self._error(string, "it is too large"));
+    }
+
+    /// Same rules as `parse_money()`, without rounding. For factors like exchange rates
+    pub fn parse_f64(&self, string: &str) -> Result<f64, Error>
+    {
+        let normalized = self._normalize(string)?;
+        return normalized.parse::<f64>().map_err(|e| self._error(string, &e.to_string()));
+    }
+
+    /// With thousands separators and two decimal places: `-1.234,56` in de-DE
+    pub fn format_money(&self, money: Money) -> String { return self._format(&money.to_string()); }
+
+    /// Shows at least `min_decimal_places`, so that the separators of a column can be aligned
+    pub fn format_quantity(&self, quantity: Quantity, min_decimal_places: usize) -> String
+    {
+        let mut plain = quantity.to_string();
+        let places = quantity.decimal_places();
+        if places < min_decimal_places {
+            if places == 0 {
+                plain.push('.');
+            }
+            plain.push_str(&"0".repeat(min_decimal_places - places));
+        }
+        return self._format(&plain);
+    }
+
+    /// Turns a number of this locale into one that `Money` and `Quantity` can parse: `-1234.56`
+    fn _normalize(&self, string: &str) -> Result<String, Error>
+    {
+        if string.trim().is_empty() {
+            return Ok(String::from("0"));
+        }
+
+        // currency and sign can be in any order: `-12 €`, `€ -12`, `12 €-`
+        let mut rest = string;
+        let mut signs: Vec<char> = Vec::new();
+        loop {
+            let before = rest;
+            rest = _trim_currency(rest);
+            if let Some(sign) = rest.chars().next().filter(|c| matches!(c, '-' | '+' | '−')) {
+                signs.push(sign);
+                rest = &rest[sign.len_utf8()..];
+            }
+            if let Some(sign) = rest.chars().next_back().filter(|c| matches!(c, '-' | '+' | '−')) {
+                signs.push(sign);
+                rest = &rest[..rest.len() - sign.len_utf8()];
+            }
+            if rest == before {
+                break;
+            }
+        }
+        if signs.len() > 1 {
+            return Err(self._error(string, "it has more than one sign"));
+        }
+        let negative = signs.first().is_some_and(|s| *s != '+');
+
+        let decimal = self.decimal_separator();
+        if let Some(c) = rest
+            .chars()
+            .find(|c| !c.is_ascii_digit() && *c != decimal && !self._is_thousands_separator(*c))
+        {
+            return Err(self._error(string, &format!("{c:?} is not part of a number")));
+        }
+        let (whole, fraction) = match rest.split_once(decimal) {
+            Some((_, fraction)) if fraction.contains(decimal) => {
+                return Err(self._error(string, &format!("{decimal:?} separates the decimals and can only be used once")));
+            }
+            Some((whole, fraction)) => (whole, fraction),
+            None => (rest, ""),
+        };
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(self._error(string, "it has no digits"));
+        }
+        if let Some(c) = fraction.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(self._error(string, &format!("{c:?} separates thousands and can not be used after {decimal:?}")));
+        }
+
+        let groups: Vec<&str> = whole.split(|c| self._is_thousands_separator(c)).collect();
+        // `,5` is fine, `.123,5` is not
+        let first_is_valid = groups.len() == 1 || (!groups[0].is_empty() && groups[0].len() <= 3);
+        if !first_is_valid || groups[1..].iter().any(|g| g.len() != 3) {
+            let mut reason = format!("{:?} separates thousands, so it needs three digits after it", self.thousands_separator());
+            if fraction.is_empty() && groups.len() == 2 {
+                reason.push_str(&format!(". Decimals are separated by {decimal:?}"));
+            }
+            return Err(self._error(string, &reason));
+        }
+
+        let sign = if negative { "-" } else { "" };
+        return match fraction.is_empty() {
+            true => Ok(format!("{sign}{}", groups.concat())),
+            false => Ok(format!("{sign}{}.{fraction}", groups.concat())),
+        };
+    }
+
+    fn _is_thousands_separator(&self, c: char) -> bool
+    {
+        return c == self.thousands_separator() || SPACES.contains(&c) || (*self == Locale::DeCh && c == '’');
+    }
+
+    /// `plain` is a number like `-1234.5`, as `Money` and `Quantity` display themselves
+    fn _format(&self, plain: &str) -> String
+    {
+        let (sign, digits) = match plain.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", plain),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let mut grouped = String::new();
+        for (index, digit) in whole.chars().enumerate() {
+            if index > 0 && (whole.len() - index) % 3 == 0 {
+                grouped.push(self.thousands_separator());
+            }
+            grouped.push(digit);
+        }
+        return match fraction.is_empty() {
+            true => format!("{sign}{grouped}"),
+            false => format!("{sign}{grouped}{}{fraction}", self.decimal_separator()),
+        };
+    }
+
+    fn _error(&self, value: &str, reason: &str) -> Error
+    {
+        return Error::InvalidNumber {
+            value: String::from(value),
+            locale: *self,
+            reason: String::from(reason),
+        };
+    }
+}
+
+// ================================================== Private ================================================== //
+
+/// Removes spaces, currency signs and codes at both ends. The dot of abbreviations like `Fr.` is removed as well
+fn _trim_currency(string: &str) -> &str
+{
+    let is_currency = |c: char| c.is_whitespace() || c.is_alphabetic() || CURRENCY_SIGNS.contains(&c);
+    let ends_with_letter = |s: &str| s.chars().next_back().is_some_and(|c| c.is_alphabetic());
+
+    let mut rest = string.trim();
+    if let Some(abbreviation) = rest.strip_suffix('.').filter(|r| ends_with_letter(r)) {
+        rest = abbreviation;
+    }
+    rest = rest.trim_end_matches(is_currency);
+
+    let start = rest.trim_start_matches(is_currency);
+    let removed = &rest[..rest.len() - start.len()];
+    return match start.strip_prefix('.') {
+        Some(after_dot) if ends_with_letter(removed) => after_dot.trim_start(),
+        _ => start,
+    };
+}
@@ -0,0 +1,285 @@
+use crate::datafile::FILE_VERSION;
+use crate::investing::depot::Depot;
+use serde_yaml::Mapping;
+use serde_yaml::Value;
+
+type MigrationStep = fn(&mut Value, &mut Vec<String>) -> Result<(), MigrationError>;
+
+/// Every step upgrades a document from `version` to `version + 1`.
+/// Index 0 upgrades from version 1 to 2, index 1 from 2 to 3 and so on.
+///
+/// When `FILE_VERSION` is increased, a step has to be added here.
+const STEPS: [MigrationStep; (FILE_VERSION - 1) as usize] = [_step_1_to_2, _step_2_to_3, _step_3_to_4];
+
+/// Fields of an `AccountingMonth` in the current structure, everything else was calculated in older versions
+const ACCOUNTING_MONTH_FIELDS: [&str; 4] = ["month_nr", "income", "expenses", "note"];
+
+/// Fields of an `AccountingYear` in the current structure, everything else was calculated in older versions
+const ACCOUNTING_YEAR_FIELDS: [&str; 2] = ["year_nr", "months"];
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum MigrationError
+{
+    /// The file was written by a newer version of this program. It will not be touched.
+    NewerVersion
+    {
+        found: u8, supported: u8
+    },
+    /// The version is not a number between 1 and 255
+    InvalidVersion(String),
+    /// The document does not have the structure that was expected for its version
+    Malformed(String),
+}
+impl std::fmt::Display for MigrationError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self {
+            MigrationError::NewerVersion { found, supported } => write!(
+                f,
+                "The data file has version {found}, but this program only supports versions up to {supported}. \
+                Please update the program, the file was not modified."
+            ),
+            MigrationError::InvalidVersion(v) => write!(f, "The data file has an invalid version: {v}"),
+            MigrationError::Malformed(msg) => write!(f, "The data file could not be migrated: {msg}"),
+        }
+    }
+}
+impl std::error::Error for MigrationError {}
+
+/// Describes what was done while bringing a document to the current `FILE_VERSION`
+#[derive(Debug, PartialEq, Clone)]
+pub struct MigrationReport
+{
+    pub from_version: u8,
+    pub to_version: u8,
+
+    /// One human readable line per change
+    pub changes: Vec<String>,
+}
+impl MigrationReport
+{
+    /// Report of a document that already has the current version
+    pub fn unchanged() -> Self
+    {
+        return Self {
+            from_version: FILE_VERSION,
+            to_version: FILE_VERSION,
+            changes: vec![],
+        };
+    }
+
+    /// `true` if the document already had the current version and nothing was changed
+    pub fn is_empty(&self) -> bool { self.from_version == self.to_version && self.changes.is_empty() }
+}
+impl std::fmt::Display for MigrationReport
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        writeln!(f, "Data file migrated from version {} to {}", self.from_version, self.to_version)?;
+        for change in self.changes.iter() {
+            writeln!(f, "- {change}")?;
+        }
+        return Ok(());
+    }
+}
+
+/// Reads the `version` of the given document and upgrades it step by step to `FILE_VERSION`.
+///
+/// - A document without a `version` is treated as version 1
+/// - A document with a newer version than `FILE_VERSION` is not modified and `Err(NewerVersion)` is returned
+/// - The `version` of the document will be `FILE_VERSION` afterwards
+pub fn migrate(document: &mut Value) -> Result<MigrationReport, MigrationError>
+{
+    let from_version = read_version(document)?;
+
+    if from_version > FILE_VERSION {
+        return Err(MigrationError::NewerVersion {
+            found: from_version,
+            supported: FILE_VERSION,
+        });
+    }
+
+    let mut changes: Vec<String> = Vec::new();
+    for version in from_version..FILE_VERSION {
+        STEPS[version as usize - 1](document, &mut changes)?;
+        changes.push(format!("version {} -> {}", version, version + 1));
+    }
+
+    _as_mapping(document, "the document")?.insert(Value::from("version"), Value::from(FILE_VERSION));
+
+    return Ok(MigrationReport {
+        from_version,
+        to_version: FILE_VERSION,
+        changes,
+    });
+}
+
+/// Returns the `version` of the document, `1` if there is none
+pub fn read_version(document: &Value) -> Result<u8, MigrationError>
+{
+    let Some(version) = document.get("version") else {
+        return Ok(1);
+    };
+
+    return match version.as_u64() {
+        Some(v) if (1..=u8::MAX as u64).contains(&v) => Ok(v as u8),
+        _ => Err(MigrationError::InvalidVersion(format!("{:?}", version))),
+    };
+}
+
+// ================================================== Steps ================================================== //
+
+/// Adds all sections that the following versions expect, with default values
+fn _step_1_to_2(document: &mut Value, changes: &mut Vec<String>) -> Result<(), MigrationError>
+{
+    let root = _as_mapping(document, "the document")?;
+    _insert_if_missing(root, "write_on_drop", Value::from(true), "", changes);
+
+    let accounting = _get_or_insert_mapping(root, "accounting", "", changes)?;
+    _insert_if_missing(accounting, "goal", Value::from(1.0), "accounting", changes);
+    _insert_if_missing(accounting, "history", Value::Mapping(Mapping::new()), "accounting", changes);
+    _insert_if_missing(accounting, "recurring_income", Value::Sequence(vec![]), "accounting", changes);
+    _insert_if_missing(accounting, "recurring_expenses", Value::Sequence(vec![]), "accounting", changes);
+
+    let investing = _get_or_insert_mapping(root, "investing", "", changes)?;
+    _insert_if_missing(investing, "comparisons", Value::Sequence(vec![]), "investing", changes);
+    _insert_if_missing(investing, "depot", Value::Mapping(Mapping::new()), "investing", changes);
+
+    return Ok(());
+}
+
+/// Removes all fields of accounting years and months that are calculated in runtime since version 3
+fn _step_2_to_3(document: &mut Value, changes: &mut Vec<String>) -> Result<(), MigrationError>
+{
+    let Some(history) = document.get_mut("accounting").and_then(|a| a.get_mut("history")) else {
+        return Ok(());
+    };
+
+    for (year_key, year) in _as_mapping(history, "accounting.history")?.iter_mut() {
+        let year_name = _key_to_string(year_key);
+        let year = _as_mapping(year, &format!("accounting.history.{year_name}"))?;
+        _retain_fields(year, &ACCOUNTING_YEAR_FIELDS, &format!("accounting.history.{year_name}"), changes);
+
+        let Some(months) = year.get_mut("months") else {
+            continue;
+        };
+        let Some(months) = months.as_sequence_mut() else {
+            return Err(MigrationError::Malformed(format!("accounting.history.{year_name}.months is not a list")));
+        };
+
+        for (i, month) in months.iter_mut().enumerate() {
+            let path = format!("accounting.history.{year_name}.months[{i}]");
+            _retain_fields(_as_mapping(month, &path)?, &ACCOUNTING_MONTH_FIELDS, &path, changes);
+        }
+    }
+
+    return Ok(());
+}
+
+/// Version 3 stored the depot entries with their name as key:
+/// ```YAML
+/// depot:
+///   entry name:
+///     variant: Etf
+/// ```
+/// Version 4 uses the hash of the name (`Depot::name_to_key`) as key and stores the name inside the entry:
+/// ```YAML
+/// depot:
+///   entries:
+///     1726800346756713850:
+///       name: entry name
+///       variant: Etf
+/// ```
+fn _step_3_to_4(document: &mut Value, changes: &mut Vec<String>) -> Result<(), MigrationError>
+{
+    let Some(depot) = document.get_mut("investing").and_then(|i| i.get_mut("depot")) else {
+        return Ok(());
+    };
+    let depot = _as_mapping(depot, "investing.depot")?;
+
+    // already in the structure of version 4, only the version number was wrong or missing
+    if depot.len() == 1 && depot.get("entries").is_some_and(|e| e.is_mapping()) {
+        return Ok(());
+    }
+
+    let mut entries = Mapping::new();
+    for (name, mut entry) in std::mem::take(depot).into_iter() {
+        let Some(name) = name.as_str() else {
+            return Err(MigrationError::Malformed(format!("depot entry name {:?} is not a string", name)));
+        };
+
+        let entry_map = _as_mapping(&mut entry, &format!("investing.depot.{name}"))?;
+        entry_map.insert(Value::from("name"), Value::from(name));
+
+        let key = Depot::name_to_key(name);
+        changes.push(format!("investing.depot.{name} moved to investing.depot.entries.{key}"));
+        entries.insert(Value::from(key), entry);
+    }
+
+    depot.insert(Value::from("entries"), Value::Mapping(entries));
+    return Ok(());
+}
+
+// ================================================== Private ================================================== //
+
+fn _as_mapping<'a>(value: &'a mut Value, path: &str) -> Result<&'a mut Mapping, MigrationError>
+{
+    return match value.as_mapping_mut() {
+        Some(m) => Ok(m),
+        None => Err(MigrationError::Malformed(format!("{path} is not a mapping"))),
+    };
+}
+
+fn _get_or_insert_mapping<'a>(
+    parent: &'a mut Mapping,
+    key: &str,
+    parent_path: &str,
+    changes: &mut Vec<String>,
+) -> Result<&'a mut Mapping, MigrationError>
+{
+    _insert_if_missing(parent, key, Value::Mapping(Mapping::new()), parent_path, changes);
+    let path = _join_path(parent_path, key);
+    return _as_mapping(parent.get_mut(key).expect("key was just inserted"), &path);
+}
+
+fn _insert_if_missing(parent: &mut Mapping, key: &str, default: Value, parent_path: &str, changes: &mut Vec<String>)
+{
+    if parent.contains_key(key) {
+        return;
+    }
+    let default_str = serde_yaml::to_string(&default).unwrap_or_default();
+    changes.push(format!(
+        "{} added with default value {}",
+        _join_path(parent_path, key),
+        default_str.trim()
+    ));
+    parent.insert(Value::from(key), default);
+}
+
+/// Removes every field whose key is not in `allowed`
+fn _retain_fields(map: &mut Mapping, allowed: &[&str], path: &str, changes: &mut Vec<String>)
+{
+    let removed: Vec<Value> = map.keys().filter(|k| !allowed.iter().any(|a| k.as_str() == Some(a))).cloned().collect();
+    for key in removed {
+        changes.push(format!("{}.{} removed", path, _key_to_string(&key)));
+        map.remove(&key);
+    }
+}
+
+fn _join_path(parent_path: &str, key: &str) -> String
+{
+    return match parent_path.is_empty() {
+        true => key.to_string(),
+        false => format!("{parent_path}.{key}"),
+    };
+}
+
+fn _key_to_string(key: &Value) -> String
+{
+    return match key {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        other => format!("{:?}", other),
+    };
+}
@@ -0,0 +1,345 @@
+use crate::Error;
+use serde::de::Visitor;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use std::iter::Sum;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+use std::str::FromStr;
+
+const CENTS_PER_UNIT: i64 = 100;
+/// `Quantity` is stored with this many decimal places
+const QUANTITY_DECIMALS: u32 = 8;
+const QUANTITY_SCALE: i64 = 10_i64.pow(QUANTITY_DECIMALS);
+
+/// An exact amount of money, stored as whole cents. Sums of `Money` never collect rounding errors.
+///
+/// In the data file it is written as a number with at most two decimal places (`12.34`), like the `f64` values before,
+/// and read back into the same cents.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct Money(i64);
+impl Money
+{
+    pub const ZERO: Money = Money(0);
+
+    pub fn from_cents(cents: i64) -> Self { Self(cents) }
+
+    pub fn cents(&self) -> i64 { self.0 }
+
+    /// Rounded to whole cents, half away from zero: `2.675` becomes `2.68`. NaN and infinite values become 0
+    pub fn from_f64(float: f64) -> Self { Self(_f64_to_fixed(float, 2)) }
+
+    /// For calculations that are not about exact amounts, like percentages and charts
+    pub fn to_f64(&self) -> f64 { self.0 as f64 / CENTS_PER_UNIT as f64 }
+
+    pub fn abs(&self) -> Self { Self(self.0.abs()) }
+
+    pub fn is_zero(&self) -> bool { self.0 == 0 }
+
+    pub fn is_positive(&self) -> bool { self.0 > 0 }
+
+    pub fn is_negative(&self) -> bool { self.0 < 0 }
+
+    /// Rounded to whole cents, half away from zero. For percentages and exchange rates
+    pub fn mul_f64(&self, factor: f64) -> Self { Self::from_f64(self.to_f64() * factor) }
+
+    /// Rounded to whole cents, half away from zero. `None` if `divisor` is 0
+    pub fn div_rounded(&self, divisor: i64) -> Option<Self>
+    {
+        if divisor == 0 {
+            return None;
+        }
+        return Some(Self(_div_round_half_away(self.0 as i128, divisor as i128) as i64));
+    }
+
+    /// `self * part / whole`, rounded to whole cents. Eg. the cost of some of the units of a purchase. `None` if `whole` is 0
+    pub fn share(&self, part: Quantity, whole: Quantity) -> Option<Self>
+    {
+        if whole.is_zero() {
+            return None;
+        }
+        return Some(Self(_div_round_half_away(self.0 as i128 * part.0 as i128, whole.0 as i128) as i64));
+    }
+
+    /// How many units can be bought for this amount at `price`, rounded to eight decimal places. `None` if `price` is 0
+    pub fn units_at(&self, price: Quantity) -> Option<Quantity>
+    {
+        if price.is_zero() {
+            return None;
+        }
+        // cents have 2 decimal places, the result QUANTITY_DECIMALS and the price divides them away again
+        let dividend = self.0 as i128 * (QUANTITY_SCALE as i128 * QUANTITY_SCALE as i128 / CENTS_PER_UNIT as i128);
+        return Some(Quantity(_div_round_half_away(dividend, price.0 as i128) as i64));
+    }
+}
+impl std::fmt::Display for Money
+{
+    /// Always two decimal places: `-12.30`. Width and alignment work like for integers, the precision is ignored
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let abs = self.0.unsigned_abs();
+        let text = format!("{}.{:02}", abs / CENTS_PER_UNIT as u64, abs % CENTS_PER_UNIT as u64);
+        return f.pad_integral(self.0 >= 0, "", &text);
+    }
+}
+impl FromStr for Money
+{
+    type Err = Error;
+
+    /// - Can parse `1234.56`, `1234,56`, `1.234,56` and `1,234.56`. If both `.` and `,` are used, the last one separates the decimals
+    /// - Ignores everything that is not a digit or `.` `,` `+` `-`, so `12,50 €` works too
+    /// - More than two decimal places are rounded half away from zero
+    /// - Empty Strings result in 0
+    fn from_str(string: &str) -> Result<Self, Self::Err>
+    {
+        return _parse_fixed(string, 2)
+            .map(Self)
+            .ok_or_else(|| Error::InvalidAmount(String::from(string)));
+    }
+}
+impl Add for Money
+{
+    type Output = Money;
+    fn add(self, rhs: Self) -> Self::Output { Self(self.0 + rhs.0) }
+}
+impl Sub for Money
+{
+    type Output = Money;
+    fn sub(self, rhs: Self) -> Self::Output { Self(self.0 - rhs.0) }
+}
+impl Neg for Money
+{
+    type Output = Money;
+    fn neg(self) -> Self::Output { Self(-self.0) }
+}
+impl AddAssign for Money
+{
+    fn add_assign(&mut self, rhs: Self) { self.0 += rhs.0; }
+}
+impl SubAssign for Money
+{
+    fn sub_assign(&mut self, rhs: Self) { self.0 -= rhs.0; }
+}
+impl Sum for Money
+{
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self { Self(iter.map(|m| m.0).sum()) }
+}
+impl<'a> Sum<&'a Money> for Money
+{
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Self { Self(iter.map(|m| m.0).sum()) }
+}
+impl Serialize for Money
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { serializer.serialize_f64(self.to_f64()) }
+}
+impl<'de> Deserialize<'de> for Money
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        return deserializer.deserialize_any(FixedVisitor(2)).map(Self);
+    }
+}
+
+/// Units of an investment or the price of one unit, with eight decimal places.
+///
+/// These are never rounded to cents, only `times()` gives an amount of money
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct Quantity(i64);
+impl Quantity
+{
+    pub const ZERO: Quantity = Quantity(0);
+
+    /// Rounded to eight decimal places, half away from zero. NaN and infinite values become 0
+    pub fn from_f64(float: f64) -> Self { Self(_f64_to_fixed(float, QUANTITY_DECIMALS)) }
+
+    pub fn to_f64(&self) -> f64 { self.0 as f64 / QUANTITY_SCALE as f64 }
+
+    pub fn abs(&self) -> Self { Self(self.0.abs()) }
+
+    pub fn is_zero(&self) -> bool { self.0 == 0 }
+
+    /// Number of decimal places that are actually used: `12.50` has 1
+    pub fn decimal_places(&self) -> usize
+    {
+        let mut places = QUANTITY_DECIMALS as usize;
+        let mut rest = self.0;
+        while places > 0 && rest % 10 == 0 {
+            rest /= 10;
+            places -= 1;
+        }
+        return places;
+    }
+
+    /// `self * part / whole`, rounded to eight decimal places. `None` if `whole` is 0
+    pub fn share(&self, part: Quantity, whole: Quantity) -> Option<Self>
+    {
+        if whole.is_zero() {
+            return None;
+        }
+        return Some(Self(_div_round_half_away(self.0 as i128 * part.0 as i128, whole.0 as i128) as i64));
+    }
+
+    /// `units.times(price)`, rounded to whole cents. Calculated without floats, so it is exact
+    pub fn times(&self, other: Quantity) -> Money
+    {
+        // both have QUANTITY_DECIMALS places, the product has twice as many, cents have 2
+        let divisor = (QUANTITY_SCALE as i128 * QUANTITY_SCALE as i128) / CENTS_PER_UNIT as i128;
+        return Money(_div_round_half_away(self.0 as i128 * other.0 as i128, divisor) as i64);
+    }
+}
+impl std::fmt::Display for Quantity
+{
+    /// Without trailing zeros: `12.5`, `3`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let abs = self.0.unsigned_abs();
+        let (whole, fraction) = (abs / QUANTITY_SCALE as u64, abs % QUANTITY_SCALE as u64);
+        let text = match self.decimal_places() {
+            0 => format!("{whole}"),
+            places => format!("{whole}.{}", &format!("{fraction:08}")[..places]),
+        };
+        return f.pad_integral(self.0 >= 0, "", &text);
+    }
+}
+impl FromStr for Quantity
+{
+    type Err = Error;
+
+    /// Same rules as for `Money`, with eight decimal places
+    fn from_str(string: &str) -> Result<Self, Self::Err>
+    {
+        return _parse_fixed(string, QUANTITY_DECIMALS)
+            .map(Self)
+            .ok_or_else(|| Error::InvalidAmount(String::from(string)));
+    }
+}
+impl Add for Quantity
+{
+    type Output = Quantity;
+    fn add(self, rhs: Self) -> Self::Output { Self(self.0 + rhs.0) }
+}
+impl Sub for Quantity
+{
+    type Output = Quantity;
+    fn sub(self, rhs: Self) -> Self::Output { Self(self.0 - rhs.0) }
+}
+impl Neg for Quantity
+{
+    type Output = Quantity;
+    fn neg(self) -> Self::Output { Self(-self.0) }
+}
+impl AddAssign for Quantity
+{
+    fn add_assign(&mut self, rhs: Self) { self.0 += rhs.0; }
+}
+impl Serialize for Quantity
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { serializer.serialize_f64(self.to_f64()) }
+}
+impl<'de> Deserialize<'de> for Quantity
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        return deserializer.deserialize_any(FixedVisitor(QUANTITY_DECIMALS)).map(Self);
+    }
+}
+
+// ================================================== Private ================================================== //
+
+/// Reads numbers and Strings into an integer with this many decimal places
+struct FixedVisitor(u32);
+impl<'de> Visitor<'de> for FixedVisitor
+{
+    type Value = i64;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result { write!(formatter, "a number") }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> { Ok(_f64_to_fixed(v, self.0)) }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E>
+    {
+        return v.checked_mul(10_i64.pow(self.0)).ok_or_else(|| E::custom(format!("{v} is too large")));
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E>
+    {
+        let v = i64::try_from(v).map_err(|_| E::custom(format!("{v} is too large")))?;
+        return self.visit_i64(v);
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E>
+    {
+        return _parse_fixed(v, self.0).ok_or_else(|| E::custom(format!("{v:?} is not a number")));
+    }
+}
+
+/// The shortest representation of a float is the decimal number the user meant, so this is parsed instead of multiplying the float
+fn _f64_to_fixed(float: f64, decimals: u32) -> i64
+{
+    if !float.is_finite() {
+        return 0;
+    }
+    return _parse_fixed(&float.to_string(), decimals).unwrap_or(0);
+}
+
+/// Parses a decimal number exactly into an integer with `decimals` decimal places, rounding half away from zero.
+/// `None` if it is not a number or too large
+fn _parse_fixed(string: &str, decimals: u32) -> Option<i64>
+{
+    if string.trim().is_empty() {
+        return Some(0);
+    }
+    let mut filtered: String = string
+        .chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '+' | '-'))
+        .collect();
+
+    let negative = filtered.starts_with('-');
+    if filtered.starts_with('-') || filtered.starts_with('+') {
+        filtered.remove(0);
+    }
+
+    // the last separator separates the decimals, unless it is the only kind of separator and is used more than once
+    let decimal_separator = match (filtered.rfind('.'), filtered.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(if dot > comma { '.' } else { ',' }),
+        (Some(_), None) if filtered.matches('.').count() == 1 => Some('.'),
+        (None, Some(_)) if filtered.matches(',').count() == 1 => Some(','),
+        _ => None,
+    };
+    let (whole, fraction) = match decimal_separator {
+        Some(separator) => filtered.rsplit_once(separator)?,
+        None => (filtered.as_str(), ""),
+    };
+    let whole: String = whole.chars().filter(|c| *c != '.' && *c != ',').collect();
+    if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) || (whole.is_empty() && fraction.is_empty()) {
+        return None;
+    }
+
+    let mut value: i64 = 0;
+    for digit in whole
+        .chars()
+        .chain(fraction.chars().chain(std::iter::repeat('0')).take(decimals as usize))
+    {
+        value = value.checked_mul(10)?.checked_add(digit.to_digit(10)? as i64)?;
+    }
+    if fraction.chars().nth(decimals as usize).is_some_and(|c| c >= '5') {
+        value = value.checked_add(1)?;
+    }
+
+    return Some(if negative { -value } else { value });
+}
+
+fn _div_round_half_away(dividend: i128, divisor: i128) -> i128
+{
+    let quotient = dividend / divisor;
+    let remainder = dividend % divisor;
+    if remainder.abs() * 2 >= divisor.abs() {
+        return quotient + if (dividend < 0) != (divisor < 0) { -1 } else { 1 };
+    }
+    return quotient;
+}
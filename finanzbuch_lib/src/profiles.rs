@@ -0,0 +1,232 @@
+use crate::backup;
+use crate::DataFile;
+use crate::Error;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Path of a data file, overrides everything else
+pub const ENV_FILE: &str = "FINANZBUCH_FILE";
+/// Name of a profile in the app config
+pub const ENV_PROFILE: &str = "FINANZBUCH_PROFILE";
+
+const CONFIG_DIR: &str = "finanzbuch";
+const CONFIG_FILENAME: &str = "config.yaml";
+
+/// A named data file, eg. one for each person and one for the household
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Profile
+{
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Settings of the program itself, not of one data file.
+///
+/// Stored in the users config directory:
+/// - Linux: `$XDG_CONFIG_HOME/finanzbuch/config.yaml` or `~/.config/finanzbuch/config.yaml`
+/// - MacOS: `~/Library/Application Support/finanzbuch/config.yaml`
+/// - Windows: `C:\Users\username\AppData\Roaming\finanzbuch\config.yaml`
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+pub struct AppConfig
+{
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    /// Opened when nothing else was requested
+    #[serde(default)]
+    pub last_profile: Option<String>,
+}
+
+/// Which data file the user asked for, on the command line or with an environment variable
+#[derive(Debug, PartialEq, Clone)]
+pub enum Selection
+{
+    File(PathBuf),
+    Profile(String),
+}
+
+/// The data file that should be opened
+#[derive(Debug, PartialEq, Clone)]
+pub struct Location
+{
+    /// `None` if the file does not belong to a profile
+    pub profile: Option<String>,
+    pub path: PathBuf,
+}
+
+impl AppConfig
+{
+    pub fn default_path() -> Result<PathBuf, Error>
+    {
+        return match dirs::config_dir() {
+            Some(dir) => Ok(dir.join(CONFIG_DIR).join(CONFIG_FILENAME)),
+            None => Err(Error::NoConfigDirectory),
+        };
+    }
+
+    /// - Reads the app config from the users config directory
+    /// - Returns default values if the file does not exist or is empty
+    pub fn read() -> Result<Self, Error> { Self::read_from_custom_path(&Self::default_path()?) }
+
+    /// Same as `read()`, but with a custom path, for testing purposes
+    pub fn read_from_custom_path(filepath: &Path) -> Result<Self, Error>
+    {
+        let content = match std::fs::read_to_string(filepath) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::io(filepath, e)),
+        };
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        return match serde_yaml::from_str(&content) {
+            Ok(config) => Ok(config),
+            Err(e) => Err(Error::yaml(filepath, e)),
+        };
+    }
+
+    /// Writes the app config into the users config directory, which is created if necessary
+    pub fn write(&self) -> Result<(), Error> { self.write_to_custom_path(&Self::default_path()?) }
+
+    /// Same as `write()`, but with a custom path, for testing purposes
+    pub fn write_to_custom_path(&self, filepath: &Path) -> Result<(), Error>
+    {
+        let yaml = match serde_yaml::to_string(self) {
+            Ok(v) => v,
+            Err(e) => return Err(Error::Serialize(e.to_string())),
+        };
+
+        if let Some(dir) = filepath.parent() {
+            if let Err(e) = std::fs::create_dir_all(dir) {
+                return Err(Error::io(dir, e));
+            }
+        }
+        return backup::write_atomic(filepath, yaml.as_bytes());
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&Profile> { self.profiles.iter().find(|p| p.name == name) }
+
+    /// The profile whose data file is at `path`, if there is one
+    pub fn profile_by_path(&self, path: &Path) -> Option<&Profile> { self.profiles.iter().find(|p| p.path == path) }
+
+    /// - The name has to be unique and not empty
+    /// - The data file is not created here, this happens when it is written the first time
+    pub fn add_profile(&mut self, name: &str, path: PathBuf) -> Result<(), Error>
+    {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::InvalidProfile(String::from("the name is empty")));
+        }
+        if path.as_os_str().is_empty() {
+            return Err(Error::InvalidProfile(String::from("the path is empty")));
+        }
+        if self.profile(name).is_some() {
+            return Err(Error::InvalidProfile(format!("there already is a profile called {name}")));
+        }
+
+        self.profiles.push(Profile {
+            name: String::from(name),
+            path,
+        });
+        return Ok(());
+    }
+
+    /// Removes the profile from the list, its data file is not deleted
+    pub fn remove_profile(&mut self, name: &str) -> Result<Profile, Error>
+    {
+        let Some(index) = self.profiles.iter().position(|p| p.name == name) else {
+            return Err(Error::UnknownProfile(String::from(name)));
+        };
+
+        if self.last_profile.as_deref() == Some(name) {
+            self.last_profile = None;
+        }
+        return Ok(self.profiles.remove(index));
+    }
+
+    /// Decides which data file should be opened, the first one that is given wins:
+    /// 1. `selection` (see `Selection::from_args()` and `Selection::from_env()`)
+    /// 2. `last_profile`, if this profile still exists
+    /// 3. `DataFile::home_path()`
+    pub fn resolve(&self, selection: Option<Selection>) -> Result<Location, Error>
+    {
+        match selection {
+            Some(Selection::File(path)) => {
+                return Ok(Location {
+                    profile: self.profile_by_path(&path).map(|p| p.name.clone()),
+                    path,
+                })
+            }
+            Some(Selection::Profile(name)) => {
+                return match self.profile(&name) {
+                    Some(profile) => Ok(Location {
+                        profile: Some(profile.name.clone()),
+                        path: profile.path.clone(),
+                    }),
+                    None => Err(Error::UnknownProfile(name)),
+                };
+            }
+            None => (),
+        }
+
+        if let Some(profile) = self.last_profile.as_deref().and_then(|name| self.profile(name)) {
+            return Ok(Location {
+                profile: Some(profile.name.clone()),
+                path: profile.path.clone(),
+            });
+        }
+
+        return Ok(Location {
+            profile: None,
+            path: DataFile::home_path()?,
+        });
+    }
+}
+
+impl Selection
+{
+    /// Looks for `--file <path>` or `--profile <name>` (also `--file=<path>`). If both are given, `--file` wins.
+    ///
+    /// All other arguments are ignored, so this can be used with `std::env::args()` directly.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Option<Self>
+    {
+        let mut file: Option<String> = None;
+        let mut profile: Option<String> = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--file" => file = args.next(),
+                "--profile" => profile = args.next(),
+                _ => {
+                    if let Some(value) = arg.strip_prefix("--file=") {
+                        file = Some(String::from(value));
+                    } else if let Some(value) = arg.strip_prefix("--profile=") {
+                        profile = Some(String::from(value));
+                    }
+                }
+            }
+        }
+
+        return Self::_from_values(file, profile);
+    }
+
+    /// Reads `FINANZBUCH_FILE` and `FINANZBUCH_PROFILE`. If both are set, the file wins.
+    pub fn from_env() -> Option<Self> { Self::_from_values(std::env::var(ENV_FILE).ok(), std::env::var(ENV_PROFILE).ok()) }
+
+    // ---------- Private ----------
+
+    /// Empty values count as not given
+    fn _from_values(file: Option<String>, profile: Option<String>) -> Option<Self>
+    {
+        if let Some(file) = file.filter(|f| !f.trim().is_empty()) {
+            return Some(Selection::File(PathBuf::from(file)));
+        }
+        if let Some(profile) = profile.filter(|p| !p.trim().is_empty()) {
+            return Some(Selection::Profile(profile));
+        }
+        return None;
+    }
+}
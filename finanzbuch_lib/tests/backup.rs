@@ -0,0 +1,108 @@
+use finanzbuch_lib::backup;
+use finanzbuch_lib::DataFile;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Every test gets its own directory, so that they dont see each others backups
+fn prepare_dir(name: &str) -> PathBuf
+{
+    let dir = PathBuf::from(format!("/tmp/finanzbuch_backup_{name}"));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    return dir.join("finanzbuch.yaml");
+}
+
+#[test]
+fn write_leaves_no_temporary_file()
+{
+    let path = prepare_dir("no_tmp");
+    DataFile::default_no_write_on_drop().write_to_custom_path(path.clone()).unwrap();
+
+    let files: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert_eq!(files, vec![std::ffi::OsString::from("finanzbuch.yaml")]);
+}
+
+#[test]
+fn first_write_after_a_while_creates_backup()
+{
+    let path = prepare_dir("first_write");
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.write_to_custom_path(path.clone()).unwrap();
+    assert!(backup::list(&path).unwrap().is_empty()); // there was nothing to backup
+
+    datafile.accounting.goal = 0.5;
+    datafile.write_to_custom_path(path.clone()).unwrap();
+    datafile.accounting.goal = 0.25;
+    datafile.write_to_custom_path(path.clone()).unwrap();
+
+    // the second write was too close to the first backup
+    let backups = backup::list(&path).unwrap();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(
+        std::fs::read_to_string(&backups[0].path).unwrap(),
+        serde_yaml::to_string(&DataFile::default_no_write_on_drop()).unwrap()
+    );
+}
+
+#[test]
+fn rotation_keeps_newest()
+{
+    let path = prepare_dir("rotation");
+    for i in 0..5 {
+        std::fs::write(&path, format!("{i}")).unwrap();
+        backup::create(&path, 3).unwrap();
+        std::thread::sleep(Duration::from_millis(5)); // backups are named by milliseconds
+    }
+
+    let backups = backup::list(&path).unwrap();
+    assert_eq!(backups.len(), 3);
+    let contents: Vec<String> = backups.iter().map(|b| std::fs::read_to_string(&b.path).unwrap()).collect();
+    assert_eq!(contents, vec!["4", "3", "2"]);
+}
+
+#[test]
+fn restore()
+{
+    let path = prepare_dir("restore");
+    std::fs::write(&path, "old").unwrap();
+    let old = backup::create(&path, 5).unwrap().unwrap();
+    std::fs::write(&path, "new").unwrap();
+    std::thread::sleep(Duration::from_millis(5));
+
+    backup::restore(&path, &old, 5).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+
+    // the content before restoring is kept as newest backup
+    let newest = backup::list(&path).unwrap().remove(0);
+    assert_eq!(std::fs::read_to_string(&newest.path).unwrap(), "new");
+}
+
+#[test]
+fn no_backups_if_disabled()
+{
+    let path = prepare_dir("disabled");
+    std::fs::write(&path, "content").unwrap();
+    assert_eq!(backup::create(&path, 0).unwrap(), None);
+    assert!(backup::list(&path).unwrap().is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn write_keeps_permissions()
+{
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = prepare_dir("permissions");
+    std::fs::write(&path, "version: 4").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+    backup::write_atomic(&path, b"version: 4\n").unwrap();
+    assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+    let backup = backup::create(&path, 1).unwrap().unwrap();
+    assert_eq!(std::fs::metadata(&backup.path).unwrap().permissions().mode() & 0o777, 0o600);
+}
@@ -0,0 +1,145 @@
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::csv_import;
+use finanzbuch_lib::investing::csv_import::BrokerProfile;
+use finanzbuch_lib::investing::depot::Depot;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::savings_plan_section::SavingsPlanSection;
+use finanzbuch_lib::investing::SavingsPlanInterval;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::DepotEntry;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+use std::path::PathBuf;
+
+const EXPORT: &str = "Datum;ISIN;Name;Stück;Kurs;Betrag\n\
+    15.11.2022;IE00B4L5Y983;iShares Core MSCI World;10;70,00;700,00\n\
+    15.01.2023;IE00B4L5Y983;;2,5;80,00;200,00\n\
+    20.01.2023;;Some Bond;1;1.000,00;1.000,00\n\
+    25.01.2023;DE0000000000;Unknown;1;1;1\n\
+    01.02.2023;IE00B4L5Y983;;abc;80,00;200,00\n\
+    15.03.2023;IE00B4L5Y983;;-4;90,00;-360,00\n";
+
+fn write_export(name: &str, content: &str) -> PathBuf
+{
+    let path = PathBuf::from(format!("/tmp/finanzbuch_broker_import_{name}.csv"));
+    std::fs::write(&path, content).unwrap();
+    return path;
+}
+
+fn depot() -> Depot
+{
+    let mut depot = Depot::new();
+    let mut world = DepotEntry::default("World", InvestmentVariant::Etf);
+    world.isin = Some(String::from("IE00B4L5Y983"));
+    depot.add_entry("World", world);
+    depot.add_entry("Some Bond", DepotEntry::default("Some Bond", InvestmentVariant::Bond));
+    return depot;
+}
+
+#[test]
+fn holdings_are_carried_forward()
+{
+    let path = write_export("holdings", EXPORT);
+    let mut depot = depot();
+    let report = csv_import::import(&mut depot, &path, &BrokerProfile::default()).unwrap();
+
+    let world = depot.get_entry_from_str("World").unwrap();
+    let y2022 = &world.history.get(&2022).unwrap().months;
+    let y2023 = &world.history.get(&2023).unwrap().months;
+
+    assert_eq!(y2022[10].amount(), Quantity::from_f64(10.0));
+    assert_eq!(y2022[10].price_per_unit(), Quantity::from_f64(70.0));
+    assert_eq!(y2022[11].amount(), Quantity::from_f64(10.0)); // no row in december
+    assert_eq!(y2022[11].price_per_unit(), Quantity::from_f64(0.0));
+    assert_eq!(y2023[0].amount(), Quantity::from_f64(12.5));
+    assert_eq!(y2023[0].additional_transactions(), Money::from_f64(200.0));
+    assert_eq!(y2023[2].amount(), Quantity::from_f64(8.5));
+    assert_eq!(y2023[2].additional_transactions(), Money::from_f64(-360.0));
+    assert_eq!(y2023[3].amount(), Quantity::from_f64(0.0)); // after the last row
+
+    let bond = depot.get_entry_from_str("Some Bond").unwrap();
+    assert_eq!(bond.history.get(&2023).unwrap().months[0].price_per_unit(), Quantity::from_f64(1000.0));
+    assert!(bond.history.contains_key(&2022)); // uniform histories
+
+    assert_eq!(report.changed.iter().filter(|c| c.entry_name == "World").count(), 5); // 2022-11 to 2023-03
+    let unmatched_lines: Vec<usize> = report.unmatched.iter().map(|u| u.line).collect();
+    assert_eq!(unmatched_lines, vec![6, 5]);
+    assert!(report
+        .unmatched
+        .iter()
+        .any(|u| u.reason.contains("\"abc\" is not a valid number in de-DE")));
+}
+
+#[test]
+fn numbers_in_the_locale_of_the_profile()
+{
+    let path = write_export(
+        "locale",
+        "Datum;ISIN;Name;Stück;Kurs;Betrag\n15.01.2023;IE00B4L5Y983;;2.5;1,080.00;\"2,700.00\"\n",
+    );
+    let mut english = depot();
+    let profile = BrokerProfile {
+        locale: Locale::EnUs,
+        ..BrokerProfile::default()
+    };
+    csv_import::import(&mut english, &path, &profile).unwrap();
+    let january = &english.get_entry_from_str("World").unwrap().history.get(&2023).unwrap().months[0];
+    assert_eq!(january.amount(), Quantity::from_f64(2.5));
+    assert_eq!(january.price_per_unit(), Quantity::from_f64(1080.0));
+    assert_eq!(january.additional_transactions(), Money::from_f64(2700.0));
+
+    // the same file is not valid in de-DE
+    let mut german = depot();
+    let report = csv_import::import(&mut german, &path, &BrokerProfile::default()).unwrap();
+    assert_eq!(report.unmatched.len(), 1);
+}
+
+#[test]
+fn savings_plan_is_not_counted_twice()
+{
+    let path = write_export("savings_plan", "Datum;ISIN;Name;Stück;Kurs;Betrag\n01.05.2023;IE00B4L5Y983;;1;100;150\n");
+    let mut depot = depot();
+    depot
+        .get_entry_mut_from_str("World")
+        .unwrap()
+        .add_savings_plan_section(SavingsPlanSection {
+            start: FastDate::new_risky(2023, 1, 1),
+            end: FastDate::new_risky(2023, 12, 31),
+            amount: Money::from_f64(100.0),
+            interval: SavingsPlanInterval::Monthly,
+        })
+        .unwrap();
+
+    csv_import::import(&mut depot, &path, &BrokerProfile::default()).unwrap();
+    let may = &depot.get_entry_from_str("World").unwrap().history.get(&2023).unwrap().months[4];
+    assert_eq!(may.additional_transactions(), Money::from_f64(50.0));
+}
+
+#[test]
+fn sell_column_and_inverted_cash_flow()
+{
+    let path = write_export(
+        "scalable",
+        "date;time;status;reference;description;assetType;type;isin;shares;price;amount;fee;tax;currency\n\
+        2023-01-10;10:00:00;Executed;1;World;Security;Buy;IE00B4L5Y983;5;80,00;-400,00;0;0;EUR\n\
+        2023-01-20;10:00:00;Executed;2;World;Security;Sell;IE00B4L5Y983;2;90,00;180,00;0;0;EUR\n",
+    );
+    let mut depot = depot();
+    csv_import::import(&mut depot, &path, &BrokerProfile::scalable_capital()).unwrap();
+
+    let january = &depot.get_entry_from_str("World").unwrap().history.get(&2023).unwrap().months[0];
+    assert_eq!(january.amount(), Quantity::from_f64(3.0));
+    assert_eq!(january.price_per_unit(), Quantity::from_f64(90.0));
+    assert_eq!(january.additional_transactions(), Money::from_f64(220.0));
+}
+
+#[test]
+fn missing_column()
+{
+    let path = write_export("missing_column", "Datum;ISIN;Stück\n");
+    assert!(matches!(
+        csv_import::import(&mut depot(), &path, &BrokerProfile::default()),
+        Err(Error::Parse { line: Some(1), .. })
+    ));
+}
@@ -0,0 +1,109 @@
+use finanzbuch_lib::accounting::category::Category;
+use finanzbuch_lib::accounting::category::CategoryKind;
+use finanzbuch_lib::accounting::ledger::LedgerTransaction;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::Accounting;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+
+fn transaction(month: u8, amount: f64, category: &str) -> LedgerTransaction
+{
+    return LedgerTransaction::new(
+        FastDate::new_risky(2024, month, 1),
+        Money::from_f64(amount),
+        String::new(),
+        String::new(),
+        String::from(category),
+    );
+}
+
+fn accounting() -> Accounting
+{
+    let mut accounting = Accounting::default();
+    accounting.goal = 0.8;
+    accounting.add_category("Wohnen", CategoryKind::Expense).unwrap();
+    accounting.add_category("Wohnen > Miete", CategoryKind::Expense).unwrap();
+    accounting.add_category("Wohnen>Strom", CategoryKind::Expense).unwrap();
+    accounting.add_category("Gehalt", CategoryKind::Income).unwrap();
+    accounting.set_category_budget("Wohnen", Some(Money::from_f64(1000.0))).unwrap();
+    accounting.set_category_budget("Wohnen > Strom", Some(Money::from_f64(-80.0))).unwrap();
+    accounting.set_category_budget("Gehalt", Some(Money::from_f64(2000.0))).unwrap();
+
+    let year = accounting.add_or_get_year(2024);
+    year.add_transaction(transaction(1, 2000.0, "Gehalt")).unwrap();
+    year.add_transaction(transaction(1, -900.0, "Wohnen > Miete")).unwrap();
+    year.add_transaction(transaction(1, -50.0, "Wohnen > Strom")).unwrap();
+    year.add_transaction(transaction(2, 2000.0, "Gehalt")).unwrap();
+    year.add_transaction(transaction(2, -900.0, "Wohnen > Miete")).unwrap();
+    year.add_transaction(transaction(2, -150.0, "Wohnen>Strom")).unwrap();
+    year.add_transaction(transaction(2, 20.0, "Wohnen > Strom")).unwrap(); // refund
+    year.add_transaction(transaction(2, -600.0, "")).unwrap();
+    return accounting;
+}
+
+#[test]
+fn paths()
+{
+    let category = Category::new(" Wohnen>Miete ", CategoryKind::Expense).unwrap();
+    assert_eq!(category.path(), "Wohnen > Miete");
+    assert_eq!(category.name(), "Miete");
+    assert_eq!(category.parent(), Some("Wohnen"));
+
+    let parent = Category::new("Wohnen", CategoryKind::Expense).unwrap();
+    assert!(parent.contains("Wohnen > Miete"));
+    assert!(!parent.contains("Wohnenzimmer"));
+    assert!(matches!(
+        Category::new("Wohnen > ", CategoryKind::Expense),
+        Err(Error::InvalidCategory(_))
+    ));
+}
+
+#[test]
+fn add_and_remove()
+{
+    let mut accounting = accounting();
+    assert!(accounting.add_category("Wohnen", CategoryKind::Expense).is_err());
+    assert!(accounting.add_category("Auto > Benzin", CategoryKind::Expense).is_err()); // no parent
+    assert!(accounting.add_category("Wohnen > Untermiete", CategoryKind::Income).is_err()); // other kind
+    assert!(matches!(accounting.set_category_budget("Auto", None), Err(Error::UnknownCategory(_))));
+
+    let paths: Vec<&str> = accounting.categories.iter().map(|c| c.path()).collect();
+    assert_eq!(paths, vec!["Gehalt", "Wohnen", "Wohnen > Miete", "Wohnen > Strom"]);
+
+    accounting.remove_category("Wohnen").unwrap();
+    assert_eq!(accounting.categories.len(), 1);
+    assert!(accounting.remove_category("Wohnen").is_err());
+}
+
+#[test]
+fn reports()
+{
+    let accounting = accounting();
+    let reports = accounting.history.get(&2024).unwrap().get_category_reports(&accounting.categories);
+
+    let wohnen = reports.iter().find(|r| r.path == "Wohnen").unwrap();
+    assert_eq!(wohnen.months[0..3], [Money::from_f64(950.0), Money::from_f64(1030.0), Money::ZERO]);
+    assert_eq!(wohnen.sum(), Money::from_f64(1980.0));
+    assert_eq!(wohnen.remaining(1), Some(Money::from_f64(50.0)));
+    assert!(wohnen.misses_budget(2));
+    assert!(!wohnen.misses_budget_year());
+
+    let strom = reports.iter().find(|r| r.path == "Wohnen > Strom").unwrap();
+    assert_eq!(strom.budget, Some(Money::from_f64(80.0)));
+    assert_eq!(strom.months[1], Money::from_f64(130.0));
+
+    let gehalt = reports.iter().find(|r| r.path == "Gehalt").unwrap();
+    assert!(!gehalt.misses_budget(1));
+    assert!(gehalt.misses_budget(3));
+    assert!(gehalt.misses_budget_year());
+}
+
+#[test]
+fn goal_breaches()
+{
+    let breaches = accounting().get_goal_breaches(2024);
+    // january: 950 / 2000, february: 1630 / 2000
+    assert_eq!(breaches.len(), 1);
+    assert_eq!(breaches[0].month, 2);
+    assert_eq!(breaches[0].over_budget, vec!["Wohnen", "Wohnen > Strom"]);
+}
@@ -0,0 +1,35 @@
+// Helpers shared by the test files, include them with `mod common;`.
+// Every test file is its own crate, so not all of them use every helper
+#![allow(dead_code)]
+
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::inv_year::InvestmentYear;
+use finanzbuch_lib::DepotEntry;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+use std::path::PathBuf;
+
+pub fn money(value: &str) -> Money { value.parse().unwrap() }
+pub fn quantity(value: &str) -> Quantity { value.parse().unwrap() }
+
+/// Writes `content` into `/tmp/finanzbuch_{name}.csv`, `name` has to be unique among all tests
+pub fn write_csv(name: &str, content: &str) -> PathBuf
+{
+    let path = PathBuf::from(format!("/tmp/finanzbuch_{name}.csv"));
+    std::fs::write(&path, content).unwrap();
+    return path;
+}
+
+/// A depot entry with the given history, `months` are `(year, month, amount, price, additional_transactions)`
+pub fn depot_entry(variant: InvestmentVariant, months: &[(u16, u8, &str, &str, &str)]) -> DepotEntry
+{
+    let mut entry = DepotEntry::default("Entry", variant);
+    for (year, month, amount, price, additional) in months {
+        let investment_year = entry.history.entry(*year).or_insert_with(|| InvestmentYear::default(*year));
+        let investment_month = &mut investment_year.months[*month as usize - 1];
+        investment_month.set_amount(quantity(amount));
+        investment_month.set_price_per_unit(quantity(price));
+        investment_month.set_additional_transactions(money(additional));
+    }
+    return entry;
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use finanzbuch_lib::currency::Currency;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::cost_basis::CostBasisMethod;
+use finanzbuch_lib::investing::depot_transaction::DepotTransaction;
+use finanzbuch_lib::investing::depot_transaction::DepotTransactionKind;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::inv_year::InvestmentYear;
+use finanzbuch_lib::investing::savings_plan_section::SavingsPlanSection;
+use finanzbuch_lib::investing::SavingsPlanInterval;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::DepotEntry;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+
+use common::money;
+use common::quantity;
+
+fn add(entry: &mut DepotEntry, month: u8, kind: DepotTransactionKind, units: &str, price: &str, fees: &str)
+{
+    let date = FastDate::new_risky(2024, month, 15);
+    let transaction = DepotTransaction::new(date, kind, quantity(units), quantity(price), money(fees)).unwrap();
+    entry.add_transaction(transaction).unwrap();
+}
+
+/// Buys 10 for 10 and 10 for 20, sells 15 for 30 in March and the price is 40 in March
+fn traded_entry(name: &str) -> DepotEntry
+{
+    let mut entry = DepotEntry::default(name, InvestmentVariant::Stock);
+    entry.history.insert(2024, InvestmentYear::default(2024));
+    add(&mut entry, 1, DepotTransactionKind::Buy, "10", "10", "0");
+    add(&mut entry, 2, DepotTransactionKind::Buy, "10", "20", "0");
+    add(&mut entry, 3, DepotTransactionKind::Sell, "15", "30", "0");
+    entry.history.get_mut(&2024).unwrap().months[2].set_price_per_unit(quantity("40"));
+    return entry;
+}
+
+#[test]
+fn fifo_sells_the_oldest_units_first()
+{
+    let cost_basis = traded_entry("A").cost_basis(CostBasisMethod::Fifo, 2024, 3);
+    assert_eq!(cost_basis.invested, money("100"));
+    assert_eq!(cost_basis.current_value, money("200"));
+    assert_eq!(cost_basis.unrealized_gain(), money("100"));
+    assert_eq!(cost_basis.realized_gain, money("250"));
+    assert_eq!(cost_basis.total_gain(), money("350"));
+    assert_eq!(cost_basis.gain_percent(), Some(100.0));
+}
+
+#[test]
+fn average_cost()
+{
+    let cost_basis = traded_entry("A").cost_basis(CostBasisMethod::Average, 2024, 3);
+    assert_eq!(cost_basis.invested, money("75"));
+    assert_eq!(cost_basis.realized_gain, money("225"));
+    assert_eq!(cost_basis.unrealized_gain(), money("125"));
+    assert!((cost_basis.gain_percent().unwrap() - 166.666).abs() < 0.001);
+
+    // before the sale
+    let february = traded_entry("A").cost_basis(CostBasisMethod::Average, 2024, 2);
+    assert_eq!((february.invested, february.realized_gain), (money("300"), Money::ZERO));
+}
+
+#[test]
+fn fees_dividends_and_splits()
+{
+    let mut entry = DepotEntry::default("B", InvestmentVariant::Stock);
+    add(&mut entry, 1, DepotTransactionKind::Buy, "10", "10", "1");
+    add(&mut entry, 2, DepotTransactionKind::Split, "10", "0", "0");
+    add(&mut entry, 3, DepotTransactionKind::Dividend, "20", "0.25", "0.5");
+    add(&mut entry, 4, DepotTransactionKind::Tax, "0", "0", "1.2");
+    add(&mut entry, 5, DepotTransactionKind::Sell, "10", "8", "2");
+
+    let cost_basis = entry.cost_basis(CostBasisMethod::Fifo, 2024, 5);
+    // half of the units, including the fee of the purchase
+    assert_eq!(cost_basis.invested, money("50.5"));
+    // sale 78 - 50.5, dividend 4.5, tax -1.2
+    assert_eq!(cost_basis.realized_gain, money("30.8"));
+}
+
+#[test]
+fn savings_plan_before_the_first_transaction()
+{
+    let mut entry = DepotEntry::default("C", InvestmentVariant::Etf);
+    let mut year = InvestmentYear::default(2024);
+    year.months[0].set_price_per_unit(quantity("50"));
+    year.months[1].set_price_per_unit(quantity("100"));
+    year.months[1].set_amount(quantity("3"));
+    entry.history.insert(2024, year);
+    let section = SavingsPlanSection {
+        start: FastDate::new_risky(2024, 1, 1),
+        end: FastDate::new_risky(2024, 12, 31),
+        amount: money("100"),
+        interval: SavingsPlanInterval::Monthly,
+    };
+    entry.add_savings_plan_section(section).unwrap();
+
+    let cost_basis = entry.cost_basis(CostBasisMethod::Fifo, 2024, 2);
+    assert_eq!((cost_basis.invested, cost_basis.current_value), (money("200"), money("300")));
+
+    // from the first transaction on, the buys of the savings plan are in the log
+    add(&mut entry, 2, DepotTransactionKind::Buy, "1", "100", "0");
+    let cost_basis = entry.cost_basis(CostBasisMethod::Fifo, 2024, 2);
+    assert_eq!(cost_basis.invested, money("200"));
+    assert_eq!(entry.cost_basis(CostBasisMethod::Fifo, 2024, 1).invested, money("100"));
+}
+
+#[test]
+fn whole_depot()
+{
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.investing.depot.add_entry("A", traded_entry("A"));
+    let mut usd = traded_entry("B");
+    usd.currency = Currency::new("USD").unwrap();
+    datafile.investing.depot.add_entry("B", usd);
+    datafile.exchange_rates.set_rate(Currency::new("USD").unwrap(), 2024, 1, 0.5).unwrap();
+
+    let sum = datafile.investing.depot.cost_basis(CostBasisMethod::Fifo, 2024, 3);
+    assert_eq!((sum.invested, sum.realized_gain), (money("200"), money("500")));
+
+    let converted = datafile.depot_cost_basis(CostBasisMethod::Fifo, 2024, 3).unwrap();
+    assert_eq!((converted.invested, converted.current_value), (money("150"), money("300")));
+    assert_eq!(converted.gain_percent(), Some(100.0));
+}
+
+#[test]
+fn exact_shares()
+{
+    assert_eq!(money("100").share(quantity("1"), quantity("3")), Some(money("33.33")));
+    assert_eq!(money("100").share(quantity("1"), Quantity::ZERO), None);
+    assert_eq!(money("100").units_at(quantity("30")), Some(quantity("3.33333333")));
+    assert_eq!(quantity("7").share(quantity("2"), quantity("3")), Some(quantity("4.66666667")));
+}
@@ -0,0 +1,129 @@
+use finanzbuch_lib::accounting::csv_import;
+use finanzbuch_lib::accounting::csv_import::ColumnMapping;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::Accounting;
+use finanzbuch_lib::AccountingMonth;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+use std::path::PathBuf;
+
+const STATEMENT: &str = "\u{feff}Buchungstag;Verwendungszweck;Betrag\n\
+    01.03.2024;Gehalt;2.500,00\n\
+    03.03.2024;Miete;-950,00\n\
+    15.03.2024;Supermarkt;-123,45\n\
+    ;;\n\
+    02.04.2024;Supermarkt;-20,10\n";
+
+fn write_statement(name: &str, content: &str) -> PathBuf
+{
+    let path = PathBuf::from(format!("/tmp/finanzbuch_csv_import_{name}.csv"));
+    std::fs::write(&path, content).unwrap();
+    return path;
+}
+
+#[test]
+fn read_and_aggregate()
+{
+    let path = write_statement("aggregate", STATEMENT);
+    let transactions = csv_import::read_transactions(&path, &ColumnMapping::default()).unwrap();
+    assert_eq!(transactions.len(), 4);
+    assert_eq!(transactions[0].amount, Money::from_f64(2500.0));
+    assert_eq!(transactions[1].purpose, "Miete");
+
+    let months = csv_import::aggregate(&transactions);
+    assert_eq!(months.len(), 2);
+    assert_eq!((months[0].year, months[0].month), (2024, 3));
+    assert_eq!(months[0].income, Money::from_f64(2500.0));
+    assert_eq!(months[0].expenses, Money::from_f64(1073.45));
+    assert_eq!(months[1].transactions.len(), 1);
+}
+
+#[test]
+fn preview_does_not_change_anything()
+{
+    let path = write_statement("preview", STATEMENT);
+    let months = csv_import::aggregate(&csv_import::read_transactions(&path, &ColumnMapping::default()).unwrap());
+
+    let mut accounting = Accounting::default();
+    accounting
+        .add_or_get_year(2024)
+        .insert_or_overwrite_month(AccountingMonth::new(3, Money::from_f64(1.0), Money::from_f64(2.0), String::from("keep me")).unwrap());
+    let control = accounting.clone();
+
+    let previews = csv_import::preview(&accounting, &months);
+    assert_eq!(accounting, control);
+    assert_eq!(previews[0].overwritten.as_ref().unwrap().income(), Money::from_f64(1.0));
+    assert_eq!(previews[1].overwritten, None);
+
+    csv_import::apply(&mut accounting, &months);
+    let march = &accounting.history.get(&2024).unwrap().months[2];
+    assert_eq!(march.income(), Money::from_f64(2500.0));
+    assert_eq!(march.note(), "keep me");
+}
+
+#[test]
+fn unknown_column()
+{
+    let path = write_statement("unknown_column", STATEMENT);
+    let mapping = ColumnMapping {
+        amount: String::from("Umsatz"),
+        ..ColumnMapping::default()
+    };
+    assert!(matches!(
+        csv_import::read_transactions(&path, &mapping),
+        Err(Error::Parse { line: Some(1), .. })
+    ));
+}
+
+#[test]
+fn invalid_line_is_reported()
+{
+    let path = write_statement(
+        "invalid_line",
+        "Buchungstag;Verwendungszweck;Betrag\n01.03.2024;Gehalt;100\n2024-03-02;Miete;-50\n",
+    );
+    assert!(matches!(
+        csv_import::read_transactions(&path, &ColumnMapping::default()),
+        Err(Error::Parse { line: Some(3), .. })
+    ));
+}
+
+#[test]
+fn amounts_in_the_locale_of_the_mapping()
+{
+    let path = write_statement(
+        "locale",
+        "Buchungstag;Verwendungszweck;Betrag\n01.03.2024;Gehalt;\"2,500.00\"\n03.03.2024;Miete;-950.5\n",
+    );
+    let mapping = ColumnMapping {
+        locale: Locale::EnUs,
+        ..ColumnMapping::default()
+    };
+    let transactions = csv_import::read_transactions(&path, &mapping).unwrap();
+    assert_eq!(transactions[0].amount, Money::from_f64(2500.0));
+    assert_eq!(transactions[1].amount, Money::from_f64(-950.5));
+
+    // "2,500.00" is not a number in de-DE
+    match csv_import::read_transactions(&path, &ColumnMapping::default()) {
+        Err(Error::Parse { line, message, .. }) => {
+            assert_eq!(line, Some(2));
+            assert!(message.contains("is not a valid number in de-DE"), "{message}");
+        }
+        other => panic!("expected a parse error, got {other:?}"),
+    }
+}
+
+#[test]
+fn apply_fills_the_ledger()
+{
+    let path = write_statement("ledger", STATEMENT);
+    let months = csv_import::aggregate(&csv_import::read_transactions(&path, &ColumnMapping::default()).unwrap());
+
+    let mut accounting = Accounting::default();
+    csv_import::apply(&mut accounting, &months);
+
+    let march = &accounting.history.get(&2024).unwrap().months[2];
+    assert_eq!(march.transactions().len(), 3);
+    assert_eq!(march.transactions()[1].description, "Miete");
+    assert_eq!(march.expenses(), Money::from_f64(1073.45));
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use finanzbuch_lib::currency::Currency;
+use finanzbuch_lib::currency::ExchangeRates;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::DepotEntry;
+use finanzbuch_lib::Error;
+use std::path::PathBuf;
+
+use common::write_csv;
+
+fn usd() -> Currency { Currency::new("USD").unwrap() }
+fn chf() -> Currency { Currency::new("CHF").unwrap() }
+
+fn rates() -> ExchangeRates
+{
+    let mut rates = ExchangeRates::default();
+    rates.set_rate(usd(), 2023, 1, 0.9).unwrap();
+    rates.set_rate(usd(), 2023, 6, 0.95).unwrap();
+    rates.set_rate(chf(), 2023, 1, 1.25).unwrap();
+    return rates;
+}
+
+#[test]
+fn currency_codes()
+{
+    assert_eq!(Currency::new(" usd ").unwrap().code(), "USD");
+    assert_eq!(Currency::default().code(), "EUR");
+    assert_eq!(Currency::default().symbol(), "€");
+    assert_eq!(chf().symbol(), "CHF");
+    assert!(matches!(Currency::new("EURO"), Err(Error::InvalidCurrency(_))));
+    assert!(matches!(Currency::new("€"), Err(Error::InvalidCurrency(_))));
+}
+
+#[test]
+fn rates_stay_valid_until_the_next_one()
+{
+    let rates = rates();
+    assert_eq!(rates.rate(&usd(), 2023, 1).unwrap(), 0.9);
+    assert_eq!(rates.rate(&usd(), 2023, 5).unwrap(), 0.9);
+    assert_eq!(rates.rate(&usd(), 2023, 6).unwrap(), 0.95);
+    assert_eq!(rates.rate(&usd(), 2030, 1).unwrap(), 0.95);
+    assert_eq!(rates.rate(&Currency::default(), 1990, 1).unwrap(), 1.0);
+    assert!(matches!(rates.rate(&usd(), 2022, 12), Err(Error::MissingExchangeRate { .. })));
+    assert!(matches!(
+        rates.rate(&Currency::new("GBP").unwrap(), 2023, 6),
+        Err(Error::MissingExchangeRate { .. })
+    ));
+}
+
+#[test]
+fn set_and_remove_rates()
+{
+    let mut rates = rates();
+    rates.set_rate(usd(), 2023, 1, 0.8).unwrap();
+    assert_eq!(rates.rates.len(), 3);
+    assert_eq!(rates.rate(&usd(), 2023, 2).unwrap(), 0.8);
+    assert_eq!(rates.currencies(), vec![&chf(), &usd()]);
+
+    assert!(matches!(rates.set_rate(usd(), 2023, 13, 1.0), Err(Error::InvalidMonth(13))));
+    assert!(matches!(rates.set_rate(usd(), 2023, 1, 0.0), Err(Error::InvalidExchangeRate(_))));
+    assert!(matches!(
+        rates.set_rate(Currency::default(), 2023, 1, 2.0),
+        Err(Error::InvalidExchangeRate(_))
+    ));
+
+    assert_eq!(rates.remove_rate(&usd(), 2023, 6).unwrap().rate, 0.95);
+    assert_eq!(rates.remove_rate(&usd(), 2023, 6), None);
+    assert_eq!(rates.rate(&usd(), 2023, 8).unwrap(), 0.8);
+}
+
+#[test]
+fn convert_between_currencies()
+{
+    let rates = rates();
+    assert_eq!(rates.convert(100.0, &usd(), &Currency::default(), 2023, 3).unwrap(), 90.0);
+    assert_eq!(rates.convert(90.0, &Currency::default(), &usd(), 2023, 3).unwrap(), 100.0);
+    // neither of both is the base currency
+    assert_eq!(rates.convert(125.0, &chf(), &usd(), 2023, 3).unwrap(), 125.0 * 1.25 / 0.9);
+    // no rate needed if nothing is converted
+    assert_eq!(rates.convert(10.0, &usd(), &usd(), 2000, 1).unwrap(), 10.0);
+}
+
+#[test]
+fn import_csv()
+{
+    let path = write_csv(
+        "exchange_rates_valid",
+        "Jahr;Monat;Währung;Kurs\n2023;1;usd;0,9\n2023;2;USD;0,91\n2024;1;CHF;1.050,5\n",
+    );
+    let mut rates = ExchangeRates::default();
+    assert_eq!(rates.import_csv(&path, Locale::DeDe).unwrap(), 3);
+    assert_eq!(rates.rate(&usd(), 2023, 2).unwrap(), 0.91);
+    assert_eq!(rates.rate(&chf(), 2024, 1).unwrap(), 1050.5);
+
+    let path = write_csv("exchange_rates_valid_en", "Jahr;Monat;Währung;Kurs\n2023;1;USD;1,050.5\n");
+    assert_eq!(rates.import_csv(&path, Locale::EnUs).unwrap(), 1);
+    assert_eq!(rates.rate(&usd(), 2023, 1).unwrap(), 1050.5);
+}
+
+#[test]
+fn import_csv_changes_nothing_on_errors()
+{
+    let mut rates = rates();
+    let before = rates.clone();
+
+    let path = write_csv(
+        "exchange_rates_invalid_line",
+        "Währung;Jahr;Monat;Kurs\nUSD;2024;1;0,9\nUSD;2024;13;0,9\n",
+    );
+    match rates.import_csv(&path, Locale::DeDe) {
+        Err(Error::Parse { line, .. }) => assert_eq!(line, Some(3)),
+        other => panic!("expected a parse error, got {other:?}"),
+    }
+
+    let path = write_csv("exchange_rates_missing_column", "Währung;Jahr;Kurs\nUSD;2024;0,9\n");
+    assert!(matches!(rates.import_csv(&path, Locale::DeDe), Err(Error::Parse { line: Some(1), .. })));
+    assert_eq!(rates, before);
+}
+
+#[test]
+fn depot_entries_are_converted_into_the_overview_currency()
+{
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.exchange_rates = rates();
+    let mut entry = DepotEntry::default("S&P 500", InvestmentVariant::Etf);
+    entry.currency = usd();
+
+    assert_eq!(datafile.overview_currency(), &Currency::default());
+    assert_eq!(datafile.to_overview_currency(&entry, 100.0, 2023, 7).unwrap(), 95.0);
+
+    datafile.investing.overview_currency = Some(usd());
+    assert_eq!(datafile.to_overview_currency(&entry, 100.0, 2023, 7).unwrap(), 100.0);
+}
+
+#[test]
+fn currencies_are_stored_in_the_data_file()
+{
+    let path = PathBuf::from("/tmp/finanzbuch_currency_roundtrip.yaml");
+    let _ = std::fs::remove_file(&path);
+
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.exchange_rates = rates();
+    datafile.accounting.currency = chf();
+    let mut entry = DepotEntry::default("S&P 500", InvestmentVariant::Etf);
+    entry.currency = usd();
+    datafile.investing.depot.add_entry("S&P 500", entry);
+    datafile.write_to_custom_path(path.clone()).unwrap();
+
+    let read = DataFile::read_from_custom_path(path).unwrap();
+    assert_eq!(read, datafile);
+    assert_eq!(read.investing.depot.get_entry_from_str("S&P 500").unwrap().currency, usd());
+}
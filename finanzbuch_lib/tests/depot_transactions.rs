@@ -0,0 +1,190 @@
+mod common;
+
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::depot_transaction::DepotTransaction;
+use finanzbuch_lib::investing::depot_transaction::DepotTransactionKind;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::inv_year::InvestmentYear;
+use finanzbuch_lib::investing::savings_plan_section::SavingsPlanSection;
+use finanzbuch_lib::investing::SavingsPlanInterval;
+use finanzbuch_lib::DepotEntry;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+
+use common::money;
+use common::quantity;
+
+fn transaction(date: (u16, u8, u8), kind: DepotTransactionKind, units: &str, price: &str, fees: &str) -> DepotTransaction
+{
+    let date = FastDate::new_risky(date.0, date.1, date.2);
+    return DepotTransaction::new(date, kind, quantity(units), quantity(price), money(fees)).unwrap();
+}
+
+fn entry() -> DepotEntry
+{
+    let mut entry = DepotEntry::default("MSCI World", InvestmentVariant::Etf);
+    entry.history.insert(2023, InvestmentYear::default(2023));
+    entry.history.insert(2024, InvestmentYear::default(2024));
+    return entry;
+}
+
+#[test]
+fn cash_flows_of_each_kind()
+{
+    let buy = transaction((2024, 1, 2), DepotTransactionKind::Buy, "2.5", "80", "1");
+    assert_eq!((buy.units_change(), buy.cash_flow()), (quantity("2.5"), money("201")));
+
+    let sell = transaction((2024, 1, 2), DepotTransactionKind::Sell, "-1", "100", "1");
+    assert_eq!((sell.units_change(), sell.cash_flow()), (quantity("-1"), money("-99")));
+
+    let dividend = transaction((2024, 1, 2), DepotTransactionKind::Dividend, "10", "0.35", "0.9");
+    assert_eq!((dividend.units_change(), dividend.cash_flow()), (Quantity::ZERO, money("-2.6")));
+
+    let tax = transaction((2024, 1, 2), DepotTransactionKind::Tax, "0", "0", "3.2");
+    assert_eq!(tax.cash_flow(), money("3.2"));
+
+    let split = transaction((2024, 1, 2), DepotTransactionKind::Split, "-4", "0", "0");
+    assert_eq!((split.units_change(), split.cash_flow()), (quantity("-4"), Money::ZERO));
+    assert_eq!(split.market_price(), None);
+
+    assert_eq!("Dividend".parse::<DepotTransactionKind>().unwrap(), DepotTransactionKind::Dividend);
+    assert!(matches!("kauf".parse::<DepotTransactionKind>(), Err(Error::InvalidTransaction(_))));
+}
+
+#[test]
+fn values_the_kind_needs()
+{
+    let date = FastDate::new_risky(2024, 1, 2);
+    let result = DepotTransaction::new(date.clone(), DepotTransactionKind::Buy, quantity("1"), Quantity::ZERO, Money::ZERO);
+    match result {
+        Err(Error::InvalidTransaction(msg)) => assert_eq!(msg, "a buy needs a price"),
+        other => panic!("expected InvalidTransaction, got {other:?}"),
+    }
+    assert!(DepotTransaction::new(date, DepotTransactionKind::Fee, Quantity::ZERO, Quantity::ZERO, Money::ZERO).is_err());
+}
+
+#[test]
+fn months_are_calculated_from_the_transactions()
+{
+    let mut entry = entry();
+    entry.history.get_mut(&2023).unwrap().months[0].set_amount(quantity("99"));
+    entry.history.get_mut(&2023).unwrap().months[11].set_price_per_unit(quantity("70"));
+
+    entry
+        .add_transaction(transaction((2023, 11, 15), DepotTransactionKind::Buy, "10", "75", "1"))
+        .unwrap();
+    entry
+        .add_transaction(transaction((2024, 2, 1), DepotTransactionKind::Buy, "2", "81.5", "0"))
+        .unwrap();
+    entry
+        .add_transaction(transaction((2024, 2, 20), DepotTransactionKind::Sell, "4", "82", "1"))
+        .unwrap();
+    entry
+        .add_transaction(transaction((2023, 12, 10), DepotTransactionKind::Dividend, "10", "0.5", "0"))
+        .unwrap();
+
+    let y2023 = &entry.history[&2023];
+    // months before the first transaction are not touched
+    assert_eq!(y2023.months[0].amount(), quantity("99"));
+    assert_eq!(y2023.months[10].amount(), quantity("10"));
+    assert_eq!(y2023.months[10].price_per_unit(), quantity("75"));
+    assert_eq!(y2023.months[10].additional_transactions(), money("751"));
+    // dividends have no market price
+    assert_eq!(y2023.months[11].amount(), quantity("10"));
+    assert_eq!(y2023.months[11].price_per_unit(), quantity("70"));
+    assert_eq!(y2023.months[11].additional_transactions(), money("-5"));
+
+    let y2024 = &entry.history[&2024];
+    assert_eq!(y2024.months[0].amount(), quantity("10"));
+    assert_eq!(y2024.months[0].additional_transactions(), Money::ZERO);
+    assert_eq!(y2024.months[1].amount(), quantity("8"));
+    assert_eq!(y2024.months[1].price_per_unit(), quantity("82"));
+    assert_eq!(y2024.months[1].additional_transactions(), money("163") - money("327"));
+    // carried forward to the end of the history
+    assert_eq!(y2024.months[11].amount(), quantity("8"));
+
+    let days: Vec<u8> = entry.transactions().iter().map(|t| t.date.day()).collect();
+    assert_eq!(days, vec![15, 10, 1, 20]);
+}
+
+#[test]
+fn planned_transactions_are_part_of_the_log()
+{
+    let mut entry = entry();
+    let section = SavingsPlanSection {
+        start: FastDate::new_risky(2024, 1, 1),
+        end: FastDate::new_risky(2024, 12, 31),
+        amount: money("100"),
+        interval: SavingsPlanInterval::Monthly,
+    };
+    entry.add_savings_plan_section(section).unwrap();
+
+    entry
+        .add_transaction(transaction((2024, 3, 1), DepotTransactionKind::Buy, "1", "150", "0"))
+        .unwrap();
+    assert_eq!(entry.history[&2024].months[2].additional_transactions(), money("50"));
+}
+
+#[test]
+fn selling_more_than_held()
+{
+    let mut entry = entry();
+    entry
+        .add_transaction(transaction((2024, 1, 5), DepotTransactionKind::Buy, "3", "10", "0"))
+        .unwrap();
+    let before = entry.clone();
+
+    let result = entry.add_transaction(transaction((2024, 1, 4), DepotTransactionKind::Sell, "1", "10", "0"));
+    match result {
+        Err(Error::InvalidTransaction(msg)) => assert_eq!(msg, "after the sell on 2024-01-04 there would be -1 units"),
+        other => panic!("expected InvalidTransaction, got {other:?}"),
+    }
+    assert_eq!(entry, before);
+
+    entry
+        .add_transaction(transaction((2024, 2, 5), DepotTransactionKind::Sell, "3", "10", "0"))
+        .unwrap();
+    assert!(entry.remove_transaction(0).is_err());
+    assert!(matches!(entry.remove_transaction(2), Err(Error::InvalidTransaction(_))));
+}
+
+#[test]
+fn removing_transactions()
+{
+    let mut entry = entry();
+    entry
+        .add_transaction(transaction((2024, 1, 5), DepotTransactionKind::Buy, "3", "10", "0"))
+        .unwrap();
+    entry
+        .add_transaction(transaction((2024, 4, 5), DepotTransactionKind::Buy, "1", "12", "0"))
+        .unwrap();
+
+    let removed = entry.remove_transaction(1).unwrap();
+    assert_eq!(removed.units(), quantity("1"));
+    let months = &entry.history[&2024].months;
+    assert_eq!(months[3].amount(), quantity("3"));
+    assert_eq!(months[3].additional_transactions(), Money::ZERO);
+
+    // without transactions the months stay as they are
+    entry.remove_transaction(0).unwrap();
+    assert!(entry.transactions().is_empty());
+    assert_eq!(entry.history[&2024].months[0].amount(), quantity("3"));
+}
+
+#[test]
+fn files_without_transactions()
+{
+    let entry = entry();
+    let yaml = serde_yaml::to_string(&entry).unwrap();
+    assert!(!yaml.contains("\ntransactions:"));
+
+    let mut with_log = entry.clone();
+    with_log
+        .add_transaction(transaction((2024, 1, 5), DepotTransactionKind::Fee, "0", "0", "4.5"))
+        .unwrap();
+    let yaml = serde_yaml::to_string(&with_log).unwrap();
+    assert!(yaml.contains("\ntransactions:\n- date: "));
+    assert!(yaml.contains("  kind: Fee\n  fees: 4.5\n"));
+    assert_eq!(serde_yaml::from_str::<DepotEntry>(&yaml).unwrap(), with_log);
+}
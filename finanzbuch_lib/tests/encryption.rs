@@ -0,0 +1,104 @@
+use finanzbuch_lib::backup;
+use finanzbuch_lib::encryption;
+use finanzbuch_lib::encryption::KdfParams;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Error;
+use std::path::PathBuf;
+
+/// The default parameters are quite slow in debug builds
+const FAST_PARAMS: KdfParams = KdfParams {
+    m_cost: 64,
+    t_cost: 1,
+    p_cost: 1,
+};
+
+#[test]
+fn round_trip()
+{
+    let encrypted = encryption::encrypt_with_params(b"version: 4", "passphrase", FAST_PARAMS).unwrap();
+
+    assert!(encryption::is_encrypted(&encrypted));
+    assert_eq!(encryption::decrypt(&encrypted, "passphrase").unwrap(), b"version: 4");
+}
+
+#[test]
+fn wrong_passphrase()
+{
+    let encrypted = encryption::encrypt_with_params(b"version: 4", "passphrase", FAST_PARAMS).unwrap();
+    assert!(matches!(encryption::decrypt(&encrypted, "wrong"), Err(Error::Decryption)));
+}
+
+#[test]
+fn modified_header()
+{
+    let mut encrypted = encryption::encrypt_with_params(b"version: 4", "passphrase", FAST_PARAMS).unwrap();
+    encrypted[encryption::MAGIC.len() + 3] ^= 1; // m_cost
+    assert!(matches!(encryption::decrypt(&encrypted, "passphrase"), Err(Error::Decryption)));
+}
+
+#[test]
+fn kdf_params_above_the_maximum()
+{
+    let mut encrypted = encryption::encrypt_with_params(b"version: 4", "passphrase", FAST_PARAMS).unwrap();
+    let m_cost = encryption::MAGIC.len() + 2;
+    encrypted[m_cost..m_cost + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(encryption::decrypt(&encrypted, "passphrase"), Err(Error::Encryption(_))));
+
+    let too_many_iterations = KdfParams {
+        t_cost: encryption::MAX_KDF_PARAMS.t_cost + 1,
+        ..FAST_PARAMS
+    };
+    assert!(matches!(
+        encryption::encrypt_with_params(b"version: 4", "passphrase", too_many_iterations),
+        Err(Error::Encryption(_))
+    ));
+}
+
+#[test]
+fn datafile_round_trip()
+{
+    let path = PathBuf::from("/tmp/encryption_datafile_round_trip.yaml");
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.backup_count = 0;
+    datafile.accounting.goal = 0.6;
+    datafile.write_encrypted(path.clone(), "passphrase").unwrap();
+
+    assert!(!std::fs::read(&path).unwrap().windows(4).any(|w| w == b"goal"));
+    assert!(matches!(DataFile::read_from_custom_path(path.clone()), Err(Error::PassphraseRequired(_))));
+    assert_eq!(DataFile::read_encrypted(path, "passphrase").unwrap(), datafile);
+}
+
+#[test]
+fn plain_file_is_read_transparently()
+{
+    let path = PathBuf::from("/tmp/encryption_plain_file.yaml");
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.backup_count = 0;
+    datafile.write_to_custom_path(path.clone()).unwrap();
+
+    assert_eq!(DataFile::read_encrypted(path, "not needed").unwrap(), datafile);
+}
+
+#[test]
+fn switching_to_encryption_encrypts_the_backups()
+{
+    let dir = PathBuf::from("/tmp/encryption_backups");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("finanzbuch.yaml");
+
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.write_to_custom_path(path.clone()).unwrap();
+    let plain = std::fs::read(&path).unwrap();
+    datafile.accounting.goal = 0.6;
+    datafile.write_to_custom_path(path.clone()).unwrap();
+    assert_eq!(backup::list(&path).unwrap().len(), 1); // contains `plain`
+
+    datafile.write_encrypted(path.clone(), "passphrase").unwrap();
+
+    let backups = backup::list(&path).unwrap();
+    assert_eq!(backups.len(), 1);
+    let content = std::fs::read(&backups[0].path).unwrap();
+    assert!(encryption::is_encrypted(&content));
+    assert_eq!(encryption::decrypt(&content, "passphrase").unwrap(), plain);
+}
@@ -0,0 +1,51 @@
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::inv_months::InvestmentMonth;
+use finanzbuch_lib::AccountingMonth;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+use std::path::PathBuf;
+
+#[test]
+fn invalid_month()
+{
+    assert!(matches!(AccountingMonth::default(13), Err(Error::InvalidMonth(13))));
+    assert!(matches!(
+        AccountingMonth::new(0, Money::from_f64(1.0), Money::from_f64(1.0), String::new()),
+        Err(Error::InvalidMonth(0))
+    ));
+    assert!(matches!(
+        InvestmentMonth::new(13, Quantity::from_f64(1.0), Quantity::from_f64(1.0), Money::from_f64(1.0)),
+        Err(Error::InvalidMonth(13))
+    ));
+}
+
+#[test]
+fn invalid_date()
+{
+    assert!(matches!(FastDate::new(2023, 13, 1), Err(Error::InvalidMonth(13))));
+    assert!(matches!(FastDate::new(2023, 12, 32), Err(Error::InvalidDay(32))));
+}
+
+#[test]
+fn borked_file_has_location()
+{
+    let path = PathBuf::from("/tmp/error_borked_file.yaml");
+    std::fs::write(&path, "version: 4\naccounting:\n  goal: not a number\n").unwrap();
+
+    match DataFile::read_from_custom_path(path) {
+        Err(Error::Parse { line, column, .. }) => {
+            assert_eq!(line, Some(3));
+            assert!(column.is_some());
+        }
+        other => panic!("expected Error::Parse, got {:?}", other),
+    }
+}
+
+#[test]
+fn unreadable_csv()
+{
+    let result = finanzbuch_lib::get_csv_contents_with_header(&PathBuf::from("/tmp/this_file_does_not_exist.csv"));
+    assert!(matches!(result, Err(Error::Io { .. })));
+}
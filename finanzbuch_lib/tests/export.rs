@@ -0,0 +1,93 @@
+use finanzbuch_lib::export::Export;
+use finanzbuch_lib::export::ExportFilter;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::inv_year::InvestmentYear;
+use finanzbuch_lib::investing::savings_plan_section::SavingsPlanSection;
+use finanzbuch_lib::investing::SavingsPlanInterval;
+use finanzbuch_lib::AccountingMonth;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::DepotEntry;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+
+fn datafile() -> DataFile
+{
+    let mut datafile = DataFile::default();
+    datafile.write_on_drop = false;
+
+    for year in [2022, 2023] {
+        let month = AccountingMonth::new(3, Money::from_f64(2000.0), Money::from_f64(1500.5), String::from("a; note")).unwrap();
+        datafile.accounting.add_or_get_year(year).insert_or_overwrite_month(month);
+    }
+
+    for name in ["World", "Bond"] {
+        let mut entry = DepotEntry::default(name, InvestmentVariant::Etf);
+        entry.history.insert(2023, InvestmentYear::default(2023));
+        datafile.investing.depot.add_entry(name, entry);
+    }
+    let world = datafile.investing.depot.get_entry_mut_from_str("World").unwrap();
+    world.history.get_mut(&2023).unwrap().months[0].set_amount(Quantity::from_f64(2.0));
+    world.history.get_mut(&2023).unwrap().months[0].set_price_per_unit(Quantity::from_f64(80.125));
+    world
+        .add_savings_plan_section(SavingsPlanSection {
+            start: FastDate::new_risky(2023, 1, 1),
+            end: FastDate::new_risky(2023, 12, 31),
+            amount: Money::from_f64(50.0),
+            interval: SavingsPlanInterval::Monthly,
+        })
+        .unwrap();
+
+    return datafile;
+}
+
+#[test]
+fn rows_and_filter()
+{
+    let datafile = datafile();
+
+    let all = Export::new(&datafile, &ExportFilter::default());
+    assert_eq!(all.accounting.len(), 24);
+    assert_eq!(all.investing.len(), 24);
+    assert_eq!(all.investing[0].entry, "Bond"); // sorted by name
+
+    let filter = ExportFilter {
+        first_year: Some(2023),
+        last_year: None,
+        entry: Some(String::from("world")),
+    };
+    let filtered = Export::new(&datafile, &filter);
+    assert_eq!(filtered.accounting.len(), 12);
+    assert_eq!(filtered.accounting[2].difference, Money::from_f64(499.5));
+    assert_eq!(filtered.investing.len(), 12);
+
+    let january = &filtered.investing[0];
+    assert_eq!((january.entry.as_str(), january.variant.as_str()), ("World", "Etf"));
+    assert_eq!(january.volume, Money::from_f64(160.25));
+    assert_eq!(january.planned_transactions, Money::from_f64(50.0));
+}
+
+#[test]
+fn csv_and_json()
+{
+    let filter = ExportFilter {
+        first_year: Some(2023),
+        last_year: Some(2023),
+        entry: None,
+    };
+    let export = Export::new(&datafile(), &filter);
+
+    let csv = export.accounting_csv().unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("year;month;income;expenses;difference;note;planned"));
+    assert_eq!(lines.nth(2), Some("2023;3;2000.0;1500.5;499.5;\"a; note\";false"));
+
+    assert!(export
+        .investing_csv()
+        .unwrap()
+        .starts_with("entry;variant;year;month;units;price_per_unit;volume;planned_transactions;additional_transactions\n"));
+
+    let json = export.to_json().unwrap();
+    assert!(json.contains("\"accounting\""));
+    assert!(json.contains("\"planned_transactions\": 50.0"));
+}
@@ -1,4 +1,5 @@
 use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::Error;
 
 /// - 9.7.2023 is 190th day of the year:
 /// - 31 + 28 + 31 + 30 + 31 + 30 + 9
@@ -12,12 +13,32 @@ fn new_invalid_month() { assert!(FastDate::new(2023, 13, 23).is_err()) }
 #[test]
 fn new_invalid_day() { assert!(FastDate::new(2023, 11, 32).is_err()) }
 
+/// The day is only checked against 1-31, not against the length of the month
+#[test]
+fn new_does_not_check_days_in_month()
+{
+    assert_eq!(FastDate::new(2023, 2, 31).unwrap().date(), (2023, 2, 31, 9));
+    assert_eq!(FastDate::days_in_month(2023, 2), 28);
+}
+
 #[test]
 fn max_values() { assert_eq!(FastDate::new(2023, 12, 31).unwrap().date(), (2023, 12, 31, 53)) }
 
 #[test]
 fn min_values() { assert_eq!(FastDate::new(2023, 1, 1).unwrap().date(), (2023, 1, 1, 1)) }
 
+#[test]
+fn parse_and_display()
+{
+    let date: FastDate = "2024-03-05".parse().unwrap();
+    assert_eq!(date, FastDate::new(2024, 3, 5).unwrap());
+    assert_eq!(date.to_string(), "2024-03-05");
+
+    assert!(matches!("2024-3".parse::<FastDate>(), Err(Error::InvalidDate(_))));
+    assert!(matches!("05.03.2024".parse::<FastDate>(), Err(Error::InvalidDate(_))));
+    assert!(matches!("2024-13-01".parse::<FastDate>(), Err(Error::InvalidMonth(13))));
+}
+
 #[test]
 fn set_year()
 {
@@ -33,6 +54,20 @@ fn set_month()
     assert!(date.set_month(13).is_err());
     assert!(date.set_month(0).is_err());
     assert!(date.set_month(12).is_ok());
+    assert_eq!(date.date(), (2023, 12, 23, 51));
+}
+
+/// `set_month` and `set_day` used to shift the value into the wrong bits,
+/// which overwrote the neighbouring fields instead of the changed one
+#[test]
+fn set_month_and_day_keep_the_other_fields()
+{
+    let mut date = FastDate::new(2023, 1, 1).unwrap();
+    date.set_month(7).unwrap();
+    assert_eq!(date.date(), (2023, 7, 1, 26));
+    date.set_day(9).unwrap();
+    assert_eq!(date.date(), (2023, 7, 9, 28));
+    assert_eq!(date, FastDate::new(2023, 7, 9).unwrap());
 }
 
 #[test]
@@ -42,6 +77,7 @@ fn set_day()
     assert!(date.set_day(32).is_err());
     assert!(date.set_day(0).is_err());
     assert!(date.set_day(31).is_ok());
+    assert_eq!(date.date(), (2023, 11, 31, 48));
 }
 
 #[test]
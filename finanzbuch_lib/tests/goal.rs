@@ -0,0 +1,96 @@
+use finanzbuch_lib::accounting::goal::GoalResult;
+use finanzbuch_lib::accounting::goal::GoalStreaks;
+use finanzbuch_lib::Accounting;
+use finanzbuch_lib::AccountingMonth;
+use finanzbuch_lib::Money;
+
+/// `goal` is 0.8, `values` are (month, income, expenses)
+fn accounting(year: u16, values: &[(u8, f64, f64)]) -> Accounting
+{
+    let mut accounting = Accounting::default();
+    accounting.goal = 0.8;
+    for (month, income, expenses) in values {
+        let month = AccountingMonth::new(*month, Money::from_f64(*income), Money::from_f64(*expenses), String::new()).unwrap();
+        accounting.add_or_get_year(year).insert_or_overwrite_month(month);
+    }
+    return accounting;
+}
+
+#[test]
+fn goal_result()
+{
+    let reached = GoalResult::new(0.8, Money::from_f64(2000.0), Money::from_f64(1500.0)).unwrap();
+    assert!(reached.reached);
+    assert_eq!(reached.margin, Money::from_f64(100.0));
+    assert!((reached.margin_percentage.unwrap() - 0.05).abs() < 1e-9);
+
+    let exactly = GoalResult::new(0.8, Money::from_f64(1000.0), Money::from_f64(800.0)).unwrap();
+    assert!(exactly.reached);
+
+    let no_income = GoalResult::new(0.8, Money::from_f64(0.0), Money::from_f64(10.0)).unwrap();
+    assert!(!no_income.reached);
+    assert_eq!((no_income.margin, no_income.margin_percentage), (Money::from_f64(-10.0), None));
+
+    assert_eq!(GoalResult::new(0.8, Money::from_f64(0.0), Money::from_f64(0.0)), None);
+}
+
+#[test]
+fn streaks()
+{
+    let ok = Some(GoalResult::new(1.0, Money::from_f64(1.0), Money::from_f64(0.0)).unwrap());
+    let missed = Some(GoalResult::new(1.0, Money::from_f64(0.0), Money::from_f64(1.0)).unwrap());
+    let streaks = GoalStreaks::new([
+        ((2023, 11), ok),
+        ((2023, 12), ok),
+        ((2024, 1), missed),
+        ((2024, 2), ok),
+        ((2024, 3), None), // breaks
+        ((2024, 4), ok),
+        ((2024, 5), None), // after the last month with data, ignored
+    ]);
+    assert_eq!(streaks.longest, 2);
+    assert_eq!(streaks.longest_end, Some((2023, 12)));
+    assert_eq!(streaks.current, 1);
+}
+
+#[test]
+fn streaks_over_years()
+{
+    let mut accounting = accounting(2023, &[(11, 100.0, 10.0), (12, 100.0, 10.0)]);
+    accounting
+        .add_or_get_year(2024)
+        .insert_or_overwrite_month(AccountingMonth::new(1, Money::from_f64(100.0), Money::from_f64(10.0), String::new()).unwrap());
+    assert_eq!(accounting.get_goal_streaks().current, 3);
+
+    accounting
+        .add_or_get_year(2026)
+        .insert_or_overwrite_month(AccountingMonth::new(1, Money::from_f64(100.0), Money::from_f64(10.0), String::new()).unwrap());
+    let streaks = accounting.get_goal_streaks();
+    assert_eq!((streaks.current, streaks.longest), (1, 3)); // 2025 is missing
+}
+
+#[test]
+fn year_summary()
+{
+    let accounting = accounting(
+        2024,
+        &[(1, 2000.0, 1000.0), (2, 2000.0, 1900.0), (3, 2000.0, 1000.0), (4, 2000.0, 1000.0)],
+    );
+    let summary = accounting.get_year_summary(2024).unwrap();
+
+    assert_eq!(
+        (summary.sum_income, summary.sum_expenses, summary.difference),
+        (Money::from_f64(8000.0), Money::from_f64(4900.0), Money::from_f64(3100.0))
+    );
+    assert_eq!(summary.median_expenses, Some(Money::from_f64(1000.0)));
+    assert!(summary.goal.unwrap().reached);
+    assert_eq!(summary.goal.unwrap().margin, Money::from_f64(1500.0));
+    assert_eq!((summary.months_reached, summary.months_missed, summary.longest_streak), (3, 1, 2));
+
+    assert_eq!(summary.months[1].percentage, Some(0.95));
+    assert!(!summary.months[1].goal.unwrap().reached);
+    assert_eq!(summary.months[11].goal, None);
+    assert_eq!(summary.months[11].percentage, None);
+
+    assert_eq!(accounting.get_year_summary(2023), None);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use finanzbuch_lib::accounting::accounting_year::AccountingYear;
+use finanzbuch_lib::currency::Currency;
+use finanzbuch_lib::inflation::PriceIndex;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::inv_year::InvestmentYear;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::DepotEntry;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+
+use common::money;
+use common::quantity;
+use common::write_csv;
+
+/// Prices are 10% higher from 2024-01 and 20% higher from 2024-07 than in 2023
+fn price_index() -> PriceIndex
+{
+    let mut price_index = PriceIndex::default();
+    price_index.set_value(2024, 7, 120.0).unwrap();
+    price_index.set_value(2023, 1, 100.0).unwrap();
+    price_index.set_value(2024, 1, 110.0).unwrap();
+    return price_index;
+}
+
+#[test]
+fn values_stay_valid_until_the_next_one()
+{
+    let mut price_index = price_index();
+    let months: Vec<(u16, u8)> = price_index.values.iter().map(|v| (v.year, v.month)).collect();
+    assert_eq!(months, vec![(2023, 1), (2024, 1), (2024, 7)]);
+    assert_eq!(price_index.index(2023, 12).unwrap(), 100.0);
+    assert_eq!(price_index.index(2030, 1).unwrap(), 120.0);
+    assert!(matches!(
+        price_index.index(2022, 12),
+        Err(Error::MissingPriceIndex { year: 2022, month: 12 })
+    ));
+
+    assert!(matches!(price_index.set_value(2024, 13, 1.0), Err(Error::InvalidMonth(13))));
+    assert!(matches!(price_index.set_value(2024, 1, 0.0), Err(Error::InvalidPriceIndex(_))));
+    assert_eq!(price_index.remove_value(2024, 1).unwrap().index, 110.0);
+    assert!(price_index.remove_value(2024, 1).is_none());
+}
+
+#[test]
+fn deflate_into_prices_of_the_base_month()
+{
+    let price_index = price_index();
+    // 110 in 2024-01 bought as much as 100 in 2023
+    assert_eq!(price_index.deflate(money("110"), 2024, 1, (2023, 5)).unwrap(), money("100"));
+    assert_eq!(price_index.deflate(money("100"), 2023, 5, (2024, 8)).unwrap(), money("120"));
+    assert_eq!(price_index.deflate(money("-55"), 2024, 3, (2024, 1)).unwrap(), money("-55"));
+    // zero needs no index
+    assert_eq!(price_index.deflate(Money::ZERO, 1990, 1, (2024, 1)).unwrap(), Money::ZERO);
+    assert!(price_index.deflate(money("1"), 1990, 1, (2024, 1)).is_err());
+}
+
+#[test]
+fn import_csv()
+{
+    let mut price_index = price_index();
+    let path = write_csv("price_index_valid", "Index;Jahr;Monat\n117,5;2024;3\n121,1;2024;8\n");
+    assert_eq!(price_index.import_csv(&path, Locale::DeDe).unwrap(), 2);
+    assert_eq!(price_index.index(2024, 5).unwrap(), 117.5);
+    assert_eq!(price_index.values.len(), 5);
+
+    let before = price_index.clone();
+    let path = write_csv("price_index_invalid", "Jahr;Monat;Index\n2025;1;130\n2025;13;131\n");
+    match price_index.import_csv(&path, Locale::DeDe) {
+        Err(Error::Parse { line, .. }) => assert_eq!(line, Some(3)),
+        other => panic!("expected Parse, got {other:?}"),
+    }
+    assert_eq!(price_index, before);
+}
+
+#[test]
+fn real_accounting_and_depot_values()
+{
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.price_index = price_index();
+
+    let mut year = AccountingYear::default(2024);
+    year.months[0].set_income(money("2200"));
+    year.months[0].set_expenses(money("1100"));
+    datafile.accounting.history.insert(2024, year);
+
+    let mut entry = DepotEntry::default("ETF", InvestmentVariant::Etf);
+    entry.currency = Currency::new("USD").unwrap();
+    let mut investment_year = InvestmentYear::default(2024);
+    investment_year.months[6].set_amount(quantity("10"));
+    investment_year.months[6].set_price_per_unit(quantity("12"));
+    investment_year.months[6].set_additional_transactions(money("120"));
+    entry.history.insert(2024, investment_year);
+    datafile.investing.depot.add_entry("ETF", entry);
+    datafile.exchange_rates.set_rate(Currency::new("USD").unwrap(), 2024, 1, 0.5).unwrap();
+
+    let accounting = datafile.real_accounting_months((2023, 12), (2024, 1), (2023, 1)).unwrap();
+    assert_eq!(accounting.len(), 2);
+    assert_eq!((accounting[0].income, accounting[0].expenses), (Money::ZERO, Money::ZERO));
+    assert_eq!((accounting[1].income, accounting[1].expenses), (money("2000"), money("1000")));
+    assert_eq!(accounting[1].difference(), money("1000"));
+
+    // 120 USD are 60 EUR, which are 50 EUR in prices of 2023
+    let depot = datafile.real_depot_values((2024, 6), (2024, 7), (2023, 1)).unwrap();
+    assert_eq!(depot.len(), 2);
+    assert_eq!((depot[0].year, depot[0].month, depot[0].value), (2024, 6, Money::ZERO));
+    assert_eq!((depot[1].value, depot[1].cash_flow), (money("50"), money("50")));
+
+    let yaml = serde_yaml::to_string(&datafile).unwrap();
+    assert!(yaml.contains("price_index:\n  values:\n  - year: 2023\n    month: 1\n    index: 100.0\n"));
+    datafile.price_index = PriceIndex::default();
+    assert!(!serde_yaml::to_string(&datafile).unwrap().contains("price_index"));
+}
@@ -0,0 +1,85 @@
+use finanzbuch_lib::accounting::accounting_year::AccountingYear;
+use finanzbuch_lib::accounting::ledger::LedgerTransaction;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::AccountingMonth;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+
+fn transaction(year: u16, month: u8, day: u8, amount: f64) -> LedgerTransaction
+{
+    return LedgerTransaction::new(
+        FastDate::new_risky(year, month, day),
+        Money::from_f64(amount),
+        String::from("Counterparty"),
+        String::new(),
+        String::new(),
+    );
+}
+
+#[test]
+fn totals_are_derived_from_the_ledger()
+{
+    let mut year = AccountingYear::default(2024);
+    year.months[2].set_income(Money::from_f64(999.0));
+
+    year.add_transaction(transaction(2024, 3, 20, -950.0)).unwrap();
+    year.add_transaction(transaction(2024, 3, 1, 2500.504)).unwrap();
+    year.add_transaction(transaction(2024, 3, 5, -123.45)).unwrap();
+
+    let march = &year.months[2];
+    assert_eq!(march.income(), Money::from_f64(2500.5));
+    assert_eq!(march.expenses(), Money::from_f64(1073.45));
+    assert_eq!(march.difference(), Money::from_f64(1427.05));
+    assert_eq!(year.get_sum_income(), Money::from_f64(2500.5));
+
+    let days: Vec<u8> = march.transactions().iter().map(|t| t.date.day()).collect();
+    assert_eq!(days, vec![1, 5, 20]);
+}
+
+#[test]
+fn wrong_month_or_year()
+{
+    let mut year = AccountingYear::default(2024);
+    assert!(matches!(
+        year.add_transaction(transaction(2023, 3, 1, 1.0)),
+        Err(Error::InvalidTransaction(_))
+    ));
+    assert!(matches!(
+        year.months[0].add_transaction(transaction(2024, 3, 1, 1.0)),
+        Err(Error::InvalidTransaction(_))
+    ));
+
+    let mut month = AccountingMonth::default(1).unwrap();
+    assert!(month
+        .set_transactions(vec![transaction(2024, 1, 1, 1.0), transaction(2024, 2, 1, 1.0)])
+        .is_err());
+    assert!(month.is_default());
+}
+
+#[test]
+fn removing_the_last_transaction_keeps_the_totals()
+{
+    let mut month = AccountingMonth::default(4).unwrap();
+    month.add_transaction(transaction(2024, 4, 1, -20.0)).unwrap();
+
+    assert_eq!(month.remove_transaction(1), None);
+    assert!(month.remove_transaction(0).is_some());
+    assert!(month.transactions().is_empty());
+    assert_eq!(month.expenses(), Money::from_f64(20.0));
+}
+
+#[test]
+fn files_without_ledger()
+{
+    let old = "month_nr: 5\nincome: 100.0\nexpenses: 50.0\nnote: ''\n";
+    let month: AccountingMonth = serde_yaml::from_str(old).unwrap();
+    assert_eq!((month.income(), month.expenses()), (Money::from_f64(100.0), Money::from_f64(50.0)));
+    assert_eq!(serde_yaml::to_string(&month).unwrap(), old);
+
+    let mut with_ledger = month.clone();
+    with_ledger.add_transaction(transaction(2024, 5, 2, 70.0)).unwrap();
+    let yaml = serde_yaml::to_string(&with_ledger).unwrap();
+    // the totals are stored as well, for older versions
+    assert!(yaml.contains("income: 70.0"));
+    assert_eq!(serde_yaml::from_str::<AccountingMonth>(&yaml).unwrap(), with_ledger);
+}
@@ -3,10 +3,14 @@ use finanzbuch_lib::accounting::accounting_year::AccountingYear;
 use finanzbuch_lib::accounting::recurrence::Recurrence;
 use finanzbuch_lib::accounting::recurrence::RecurringInOut;
 use finanzbuch_lib::accounting::Accounting;
+use finanzbuch_lib::currency::Currency;
+use finanzbuch_lib::currency::ExchangeRates;
 use finanzbuch_lib::datafile::FILE_VERSION;
+use finanzbuch_lib::inflation::PriceIndex;
 use finanzbuch_lib::investing::depot::Depot;
 use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
 use finanzbuch_lib::investing::Investing;
+use finanzbuch_lib::locale::Locale;
 use std::collections::BTreeMap;
 
 use finanzbuch_lib::DataFile;
@@ -16,9 +20,18 @@ use finanzbuch_lib::DepotEntry;
 mod read_write_datafile
 {
     use finanzbuch_lib::accounting::accounting_year::AccountingYear;
+    use finanzbuch_lib::accounting::category::Category;
+    use finanzbuch_lib::accounting::category::CategoryKind;
+    use finanzbuch_lib::accounting::recurrence::QuantityChange;
     use finanzbuch_lib::accounting::recurrence::Recurrence;
     use finanzbuch_lib::accounting::recurrence::RecurringInOut;
+    use finanzbuch_lib::currency::Currency;
+    use finanzbuch_lib::currency::ExchangeRate;
+    use finanzbuch_lib::currency::ExchangeRates;
+    use finanzbuch_lib::datafile::FILE_VERSION;
     use finanzbuch_lib::fast_date::FastDate;
+    use finanzbuch_lib::inflation::PriceIndex;
+    use finanzbuch_lib::inflation::PriceIndexValue;
     use finanzbuch_lib::investing::depot::Depot;
     use finanzbuch_lib::investing::inv_months::InvestmentMonth;
     use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
@@ -26,10 +39,13 @@ mod read_write_datafile
     use finanzbuch_lib::investing::savings_plan_section::SavingsPlanSection;
     use finanzbuch_lib::investing::Investing;
     use finanzbuch_lib::investing::SavingsPlanInterval;
+    use finanzbuch_lib::locale::Locale;
     use finanzbuch_lib::Accounting;
     use finanzbuch_lib::AccountingMonth;
     use finanzbuch_lib::DataFile;
     use finanzbuch_lib::DepotEntry;
+    use finanzbuch_lib::Money;
+    use finanzbuch_lib::Quantity;
     use std::collections::BTreeMap;
     use std::collections::HashMap;
     use std::path::PathBuf;
@@ -61,10 +77,11 @@ mod read_write_datafile
         return std::array::from_fn(|i| {
             return InvestmentMonth::new(
                 i as u8 + 1,
-                123.45,
-                _next_price(&mut rand, &mut start_value),
-                rand.next_u16() as f64 / 1000.0,
-            );
+                Quantity::from_f64(123.45),
+                Quantity::from_f64(_next_price(&mut rand, &mut start_value)),
+                Money::from_f64(rand.next_u16() as f64 / 1000.0),
+            )
+            .unwrap();
         });
     }
 
@@ -74,7 +91,13 @@ mod read_write_datafile
         let mut rand = StdRand::seed(seed);
 
         return std::array::from_fn(|i| {
-            return AccountingMonth::new(i as u8 + 1, rand.next_u16() as f64 / 11.11, rand.next_u16() as f64 / 11.11, String::new());
+            return AccountingMonth::new(
+                i as u8 + 1,
+                Money::from_f64(rand.next_u16() as f64 / 11.11),
+                Money::from_f64(rand.next_u16() as f64 / 11.11),
+                String::new(),
+            )
+            .unwrap();
         });
     }
 
@@ -82,10 +105,10 @@ mod read_write_datafile
     fn file_parsing_defaults()
     {
         let datafile = DataFile::default_no_write_on_drop();
-        datafile.write_to_custom_path(PathBuf::from("/tmp/file_parsing_defaults.yaml"));
+        datafile.write_to_custom_path(PathBuf::from("/tmp/file_parsing_defaults.yaml")).unwrap();
         drop(datafile);
 
-        let datafile = DataFile::read_from_custom_path(PathBuf::from("/tmp/file_parsing_defaults.yaml"));
+        let datafile = DataFile::read_from_custom_path(PathBuf::from("/tmp/file_parsing_defaults.yaml")).unwrap();
 
         assert_eq!(datafile.accounting, Accounting::default());
         assert_eq!(datafile.investing, Investing::default());
@@ -111,6 +134,13 @@ mod read_write_datafile
                     recurrence: Recurrence::Week,
                     interval: 1,
                     frequency: 5,
+                    start: Some(FastDate::new_risky(2023, 1, 1)),
+                    end: Some(FastDate::new_risky(2023, 12, 31)),
+                    anchor_day: Some(15),
+                    changes: vec![QuantityChange {
+                        from: FastDate::new_risky(2023, 6, 1),
+                        quantity: 6.0,
+                    }],
                 }],
                 recurring_expenses: vec![RecurringInOut {
                     name: String::from("name for recurring expenses"),
@@ -118,10 +148,19 @@ mod read_write_datafile
                     recurrence: Recurrence::Week,
                     interval: 3,
                     frequency: 1,
+                    start: None,
+                    end: None,
+                    anchor_day: None,
+                    changes: vec![],
                 }],
+                categories: vec![Category::new("Wohnen", CategoryKind::Expense).unwrap()],
+                prefill_from_recurring: true,
+                currency: Currency::new("CHF").unwrap(),
             },
             investing: Investing {
                 comparisons: vec![5, 8],
+                overview_currency: Some(Currency::new("USD").unwrap()),
+                inflation_adjusted: true,
                 depot: Depot {
                     entries: HashMap::from([(
                         Depot::name_to_key("depot entry 1 name"),
@@ -131,7 +170,7 @@ mod read_write_datafile
                             vec![SavingsPlanSection {
                                 start: FastDate::new_risky(2023, 1, 1),
                                 end: FastDate::new_risky(2023, 12, 1),
-                                amount: 50.0,
+                                amount: Money::from_f64(50.0),
                                 interval: SavingsPlanInterval::Monthly,
                             }],
                             BTreeMap::from([(
@@ -145,16 +184,36 @@ mod read_write_datafile
                     )]),
                 },
             },
+            exchange_rates: ExchangeRates {
+                base: Currency::new("CHF").unwrap(),
+                rates: vec![ExchangeRate {
+                    currency: Currency::new("USD").unwrap(),
+                    year: 2023,
+                    month: 1,
+                    rate: 0.92,
+                }],
+            },
+            price_index: PriceIndex {
+                values: vec![PriceIndexValue {
+                    year: 2023,
+                    month: 1,
+                    index: 114.3,
+                }],
+            },
+            locale: Locale::DeCh,
+            version: FILE_VERSION,
             write_on_drop: false,
-            ..Default::default()
+            backup_count: DataFile::default_backup_count(),
+            filepath: None,
+            passphrase: None,
         };
 
         // ----- Write and Read again to confirm parsing works as expected
         let control = datafile.clone();
-        datafile.write_to_custom_path(PathBuf::from("/tmp/file_parsing_rand.yaml"));
+        datafile.write_to_custom_path(PathBuf::from("/tmp/file_parsing_rand.yaml")).unwrap();
         drop(datafile);
 
-        let localfile = DataFile::read_from_custom_path(PathBuf::from("/tmp/file_parsing_rand.yaml"));
+        let localfile = DataFile::read_from_custom_path(PathBuf::from("/tmp/file_parsing_rand.yaml")).unwrap();
         assert_eq!(localfile, control);
     }
 }
@@ -164,22 +223,6 @@ mod sanitize_input_tests
 {
     use finanzbuch_lib::SanitizeInput;
 
-    #[test]
-    fn float_in_string()
-    {
-        let neg_f_str = String::from("-9876,54321");
-        let pos_f_str = String::from("+9876,54321");
-        let other_f_str = String::from("9876,54321");
-
-        assert_eq!(-9876.54321, SanitizeInput::string_to_f64(&neg_f_str, false).unwrap());
-        assert_eq!(9876.54321, SanitizeInput::string_to_f64(&neg_f_str, true).unwrap());
-
-        assert_eq!(9876.54321, SanitizeInput::string_to_f64(&pos_f_str, false).unwrap());
-        assert_eq!(9876.54321, SanitizeInput::string_to_f64(&pos_f_str, true).unwrap());
-
-        assert_eq!(9876.54321, SanitizeInput::string_to_f64(&other_f_str, true).unwrap());
-    }
-
     #[test]
     fn float_to_float()
     {
@@ -200,6 +243,8 @@ mod depot_entry
     use finanzbuch_lib::investing::savings_plan_section::SavingsPlanSection;
     use finanzbuch_lib::investing::SavingsPlanInterval;
     use finanzbuch_lib::DepotEntry;
+    use finanzbuch_lib::Error;
+    use finanzbuch_lib::Money;
 
     #[test]
     fn add_savings_plan_section_same()
@@ -209,7 +254,7 @@ mod depot_entry
         let result = de.add_savings_plan_section(SavingsPlanSection {
             start: FastDate::new_risky(2023, 1, 1),
             end: FastDate::new_risky(2023, 12, 31),
-            amount: 10.0,
+            amount: Money::from_f64(10.0),
             interval: SavingsPlanInterval::Monthly,
         });
 
@@ -223,7 +268,7 @@ mod depot_entry
         let result = de.add_savings_plan_section(SavingsPlanSection {
             start: FastDate::new_risky(2022, 6, 1),
             end: FastDate::new_risky(2023, 1, 1),
-            amount: 10.0,
+            amount: Money::from_f64(10.0),
             interval: SavingsPlanInterval::Monthly,
         });
 
@@ -237,7 +282,7 @@ mod depot_entry
         let result = de.add_savings_plan_section(SavingsPlanSection {
             start: FastDate::new_risky(2023, 6, 1),
             end: FastDate::new_risky(2023, 12, 31),
-            amount: 10.0,
+            amount: Money::from_f64(10.0),
             interval: SavingsPlanInterval::Monthly,
         });
 
@@ -251,7 +296,7 @@ mod depot_entry
         let result = de.add_savings_plan_section(SavingsPlanSection {
             start: FastDate::new_risky(2023, 2, 2),
             end: FastDate::new_risky(2023, 11, 11),
-            amount: 10.0,
+            amount: Money::from_f64(10.0),
             interval: SavingsPlanInterval::Monthly,
         });
 
@@ -265,7 +310,7 @@ mod depot_entry
         let result = de.add_savings_plan_section(SavingsPlanSection {
             start: FastDate::new_risky(2022, 6, 1),
             end: FastDate::new_risky(2022, 12, 31),
-            amount: 10.0,
+            amount: Money::from_f64(10.0),
             interval: SavingsPlanInterval::Monthly,
         });
 
@@ -279,19 +324,74 @@ mod depot_entry
         let result = de.add_savings_plan_section(SavingsPlanSection {
             start: FastDate::new_risky(2024, 1, 1),
             end: FastDate::new_risky(2024, 12, 31),
-            amount: 10.0,
+            amount: Money::from_f64(10.0),
             interval: SavingsPlanInterval::Monthly,
         });
 
         assert_eq!(result.is_ok(), true);
     }
 
+    #[test]
+    fn add_savings_plan_section_before_several()
+    {
+        let mut de = prepare_tests();
+        de.add_savings_plan_section(section((2025, 1, 1), (2025, 12, 31))).unwrap();
+        de.add_savings_plan_section(section((2020, 1, 1), (2020, 12, 31))).unwrap();
+
+        let starts: Vec<u16> = de.savings_plan().iter().map(|s| s.start.year()).collect();
+        assert_eq!(starts, vec![2020, 2023, 2025]);
+    }
+    #[test]
+    fn remove_savings_plan_section()
+    {
+        let mut de = prepare_tests();
+        de.add_savings_plan_section(section((2024, 1, 1), (2024, 12, 31))).unwrap();
+
+        let removed = de.remove_savings_plan_section(0).unwrap();
+        assert_eq!(removed.start, FastDate::new_risky(2023, 1, 1));
+        assert_eq!(de.savings_plan().len(), 1);
+        assert!(matches!(de.remove_savings_plan_section(1), Err(Error::InvalidSavingsPlan(_))));
+    }
+    #[test]
+    fn update_savings_plan_section()
+    {
+        let mut de = prepare_tests();
+        de.add_savings_plan_section(section((2024, 1, 1), (2024, 12, 31))).unwrap();
+
+        // the section may overlap with its old dates, but not with the other sections
+        de.update_savings_plan_section(0, section((2022, 1, 1), (2023, 6, 30))).unwrap();
+        assert_eq!(de.savings_plan()[0].end, FastDate::new_risky(2023, 6, 30));
+
+        let before = de.clone();
+        let result = de.update_savings_plan_section(0, section((2022, 1, 1), (2024, 2, 1)));
+        assert!(matches!(result, Err(Error::OverlappingSavingsPlan(_))));
+        let result = de.update_savings_plan_section(1, section((2025, 1, 1), (2024, 1, 1)));
+        assert!(matches!(result, Err(Error::InvalidSavingsPlan(_))));
+        assert!(de.update_savings_plan_section(2, section((2030, 1, 1), (2030, 12, 31))).is_err());
+        assert_eq!(de, before);
+
+        // moving a section behind the other one keeps them sorted
+        de.update_savings_plan_section(0, section((2026, 1, 1), (2026, 12, 31))).unwrap();
+        let starts: Vec<u16> = de.savings_plan().iter().map(|s| s.start.year()).collect();
+        assert_eq!(starts, vec![2024, 2026]);
+    }
+
+    fn section(start: (u16, u8, u8), end: (u16, u8, u8)) -> SavingsPlanSection
+    {
+        return SavingsPlanSection {
+            start: FastDate::new_risky(start.0, start.1, start.2),
+            end: FastDate::new_risky(end.0, end.1, end.2),
+            amount: Money::from_f64(10.0),
+            interval: SavingsPlanInterval::Monthly,
+        };
+    }
+
     fn prepare_tests() -> DepotEntry
     {
         let savings_plan = vec![SavingsPlanSection {
             start: FastDate::new_risky(2023, 1, 1),
             end: FastDate::new_risky(2023, 12, 31),
-            amount: 10.0,
+            amount: Money::from_f64(10.0),
             interval: SavingsPlanInterval::Monthly,
         }];
         DepotEntry::new(InvestmentVariant::Etf, String::from("name"), savings_plan, BTreeMap::new())
@@ -342,6 +442,10 @@ fn month_compare()
                 recurrence: Recurrence::Week,
                 interval: 1,
                 frequency: 5,
+                start: None,
+                end: None,
+                anchor_day: None,
+                changes: vec![],
             }],
             recurring_expenses: vec![RecurringInOut {
                 name: String::from("name for recurring income"),
@@ -349,10 +453,23 @@ fn month_compare()
                 recurrence: Recurrence::Week,
                 interval: 3,
                 frequency: 1,
+                start: None,
+                end: None,
+                anchor_day: None,
+                changes: vec![],
             }],
+            categories: vec![],
+            prefill_from_recurring: false,
+            currency: Currency::default(),
         },
         investing: Investing::default(),
+        exchange_rates: ExchangeRates::default(),
+        price_index: PriceIndex::default(),
+        locale: Locale::default(),
         write_on_drop: false,
+        backup_count: 0,
+        filepath: None,
+        passphrase: None,
     };
 
     let year = match datafile.accounting.history.get_mut(&YEAR) {
@@ -364,8 +481,8 @@ fn month_compare()
 
     // I just created this test because I wasn't sure that this comparison is done correctly
     // other languages might have compared the datatype of both sides and would always say its the same
-    assert!(*month == AccountingMonth::default(month.month_nr()));
-    assert_ne!(*month, AccountingMonth::default(month.month_nr() + 1));
+    assert!(*month == AccountingMonth::default(month.month_nr()).unwrap());
+    assert_ne!(*month, AccountingMonth::default(month.month_nr() + 1).unwrap());
 }
 
 #[cfg(test)]
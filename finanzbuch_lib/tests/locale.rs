@@ -0,0 +1,106 @@
+mod common;
+
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+
+use common::money;
+
+fn reason(result: Result<Money, Error>) -> String
+{
+    return match result {
+        Err(Error::InvalidNumber { reason, .. }) => reason,
+        other => panic!("expected InvalidNumber, got {other:?}"),
+    };
+}
+
+#[test]
+fn parse_german_numbers()
+{
+    let de = Locale::DeDe;
+    assert_eq!(de.parse_money("1.234,56").unwrap(), money("1234.56"));
+    assert_eq!(de.parse_money("1234,56").unwrap(), money("1234.56"));
+    assert_eq!(de.parse_money("-1.234.567").unwrap(), money("-1234567"));
+    assert_eq!(de.parse_money("1 234,5").unwrap(), money("1234.5"));
+    assert_eq!(de.parse_money(",5").unwrap(), money("0.5"));
+    assert_eq!(de.parse_money("").unwrap(), Money::ZERO);
+    assert_eq!(de.parse_quantity("0,12345678").unwrap(), "0.12345678".parse::<Quantity>().unwrap());
+
+    assert!(reason(de.parse_money("1,234.56")).contains("'.' separates thousands and can not be used after ','"));
+    assert!(reason(de.parse_money("12.5")).contains("Decimals are separated by ','"));
+    assert!(reason(de.parse_money("1,2,3")).contains("only be used once"));
+}
+
+#[test]
+fn parse_english_and_swiss_numbers()
+{
+    let en = Locale::EnUs;
+    assert_eq!(en.parse_money("1,234.56").unwrap(), money("1234.56"));
+    assert_eq!(en.parse_money("$1,234,567.8").unwrap(), money("1234567.8"));
+    assert!(reason(en.parse_money("1.234,56")).contains("can not be used after '.'"));
+    assert!(reason(en.parse_money("1,23")).contains("three digits"));
+
+    let ch = Locale::DeCh;
+    assert_eq!(ch.parse_money("1'234.56").unwrap(), money("1234.56"));
+    assert_eq!(ch.parse_money("1’234.56").unwrap(), money("1234.56"));
+    assert_eq!(ch.parse_money("CHF 12.50").unwrap(), money("12.5"));
+    assert_eq!(ch.parse_money("Fr. -12.50").unwrap(), money("-12.5"));
+    assert_eq!(ch.parse_money("12.50 Fr.").unwrap(), money("12.5"));
+}
+
+#[test]
+fn signs_and_currencies()
+{
+    let de = Locale::DeDe;
+    assert_eq!(de.parse_money("-12,50 €").unwrap(), money("-12.5"));
+    assert_eq!(de.parse_money("€ -12,50").unwrap(), money("-12.5"));
+    assert_eq!(de.parse_money("12,50 EUR-").unwrap(), money("-12.5"));
+    assert_eq!(de.parse_money("− 12,50").unwrap(), money("-12.5"));
+    assert_eq!(de.parse_money("+12,50").unwrap(), money("12.5"));
+
+    assert!(reason(de.parse_money("-12,50-")).contains("more than one sign"));
+    assert!(reason(de.parse_money("€")).contains("no digits"));
+    assert!(reason(de.parse_money("12#50")).contains("'#'"));
+    assert!(reason(de.parse_money("99.999.999.999.999.999.999")).contains("too large"));
+}
+
+#[test]
+fn error_message_names_the_locale()
+{
+    let error = Locale::EnUs.parse_money("1.234,56").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "\"1.234,56\" is not a valid number in en-US: ',' separates thousands and can not be used after '.'"
+    );
+}
+
+#[test]
+fn format_numbers()
+{
+    assert_eq!(Locale::DeDe.format_money(money("-1234567.8")), "-1.234.567,80");
+    assert_eq!(Locale::EnUs.format_money(money("1234.5")), "1,234.50");
+    assert_eq!(Locale::DeCh.format_money(money("999.99")), "999.99");
+    assert_eq!(Locale::DeCh.format_money(money("1000")), "1'000.00");
+
+    let quantity = "1234.5".parse::<Quantity>().unwrap();
+    assert_eq!(Locale::DeDe.format_quantity(quantity, 0), "1.234,5");
+    assert_eq!(Locale::DeDe.format_quantity(quantity, 3), "1.234,500");
+    assert_eq!(Locale::EnUs.format_quantity("3".parse().unwrap(), 2), "3.00");
+
+    // everything that is formatted can be parsed again
+    for locale in Locale::ALL {
+        let value = money("-9876543.21");
+        assert_eq!(locale.parse_money(&locale.format_money(value)).unwrap(), value);
+    }
+}
+
+#[test]
+fn locale_codes()
+{
+    assert_eq!("de_de".parse::<Locale>().unwrap(), Locale::DeDe);
+    assert_eq!("en-US".parse::<Locale>().unwrap(), Locale::EnUs);
+    assert_eq!(Locale::DeCh.to_string(), "de-CH");
+    assert!(matches!("fr-FR".parse::<Locale>(), Err(Error::UnknownLocale(_))));
+    assert_eq!(serde_yaml::to_string(&Locale::EnUs).unwrap(), "en-US\n");
+}
@@ -0,0 +1,128 @@
+use finanzbuch_lib::datafile::FILE_VERSION;
+use finanzbuch_lib::investing::depot::Depot;
+use finanzbuch_lib::migration;
+use finanzbuch_lib::migration::MigrationError;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+use std::path::PathBuf;
+
+const VERSION_3_FILE_START: &str = r#"
+version: 3
+accounting:
+  goal: 0.75
+  history:
+    2023:
+      year_nr: 2023
+      income_sum: 100.0
+      months:
+"#;
+
+const VERSION_3_FILE_END: &str = r#"
+  recurring_income: []
+  recurring_expenses: []
+investing:
+  comparisons:
+  - 5
+  depot:
+    depot entry 1 name:
+      variant: Bond
+      savings_plan:
+      - start: 132583489
+        end: 132628592
+        amount: 50.0
+        interval: Monthly
+      history: {}
+write_on_drop: false
+"#;
+
+/// Every month has `income: 100.0`, `expenses: 50.0` and the field `difference`, which was removed in version 3
+fn version_3_file() -> String
+{
+    let mut months = String::new();
+    for month_nr in 1..=12 {
+        months.push_str(&format!(
+            "      - month_nr: {month_nr}\n        income: 100.0\n        expenses: 50.0\n        difference: 50.0\n        note: ''\n"
+        ));
+    }
+    return format!("{VERSION_3_FILE_START}{}{VERSION_3_FILE_END}", months.trim_end());
+}
+
+#[test]
+fn current_version_is_unchanged()
+{
+    let mut document = serde_yaml::to_value(DataFile::default_no_write_on_drop()).unwrap();
+    let control = document.clone();
+
+    let report = migration::migrate(&mut document).unwrap();
+
+    assert!(report.is_empty());
+    assert_eq!(document, control);
+}
+
+#[test]
+fn version_3_depot_is_keyed_by_hash()
+{
+    let mut document: serde_yaml::Value = serde_yaml::from_str(&version_3_file()).unwrap();
+    let report = migration::migrate(&mut document).unwrap();
+
+    assert_eq!(report.from_version, 3);
+    assert_eq!(report.to_version, FILE_VERSION);
+    assert_eq!(migration::read_version(&document), Ok(FILE_VERSION));
+
+    let datafile: DataFile = serde_yaml::from_value(document).unwrap();
+    let entry = datafile.investing.depot.entries.get(&Depot::name_to_key("depot entry 1 name")).unwrap();
+    assert_eq!(entry.name(), "depot entry 1 name");
+    assert_eq!(entry.savings_plan().len(), 1);
+}
+
+#[test]
+fn version_2_calculated_fields_are_removed()
+{
+    let mut document: serde_yaml::Value = serde_yaml::from_str(&version_3_file().replace("version: 3", "version: 2")).unwrap();
+    let report = migration::migrate(&mut document).unwrap();
+
+    assert!(report.changes.contains(&String::from("accounting.history.2023.income_sum removed")));
+    assert!(report
+        .changes
+        .contains(&String::from("accounting.history.2023.months[0].difference removed")));
+
+    let datafile: DataFile = serde_yaml::from_value(document).unwrap();
+    assert_eq!(datafile.accounting.history.get(&2023).unwrap().months[0].income(), Money::from_f64(100.0));
+}
+
+#[test]
+fn version_1_missing_sections_are_added()
+{
+    let mut document: serde_yaml::Value = serde_yaml::from_str("version: 1\naccounting:\n  goal: 0.5\n").unwrap();
+    migration::migrate(&mut document).unwrap();
+
+    let mut datafile: DataFile = serde_yaml::from_value(document).unwrap();
+    datafile.write_on_drop = false;
+    assert_eq!(datafile.accounting.goal, 0.5);
+    assert!(datafile.investing.depot.entries.is_empty());
+}
+
+#[test]
+fn newer_version_is_refused()
+{
+    let path = PathBuf::from("/tmp/migration_newer_version.yaml");
+    let content = format!("version: {}\n", FILE_VERSION + 1);
+    std::fs::write(&path, &content).unwrap();
+
+    let result = DataFile::read_from_custom_path(path.clone());
+    assert!(matches!(
+        result,
+        Err(Error::Migration(MigrationError::NewerVersion { found, supported })) if found == FILE_VERSION + 1 && supported == FILE_VERSION
+    ));
+
+    // the file must not be touched
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+}
+
+#[test]
+fn invalid_version()
+{
+    let mut document: serde_yaml::Value = serde_yaml::from_str("version: abc\n").unwrap();
+    assert!(matches!(migration::migrate(&mut document), Err(MigrationError::InvalidVersion(_))));
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use finanzbuch_lib::accounting::accounting_year::AccountingYear;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+use finanzbuch_lib::Quantity;
+
+use common::money;
+use common::quantity;
+
+#[test]
+fn parse_money()
+{
+    assert_eq!(money("1234.56").cents(), 123456);
+    assert_eq!(money("1234,56").cents(), 123456);
+    assert_eq!(money("-1.234,56").cents(), -123456);
+    assert_eq!(money("1,234.56").cents(), 123456);
+    assert_eq!(money("1.234.567").cents(), 123456700);
+    assert_eq!(money("12,5 €").cents(), 1250);
+    assert_eq!(money("+3").cents(), 300);
+    assert_eq!(money("").cents(), 0);
+    // more than two decimal places are rounded half away from zero
+    assert_eq!(money("2.675").cents(), 268);
+    assert_eq!(money("-2.675").cents(), -268);
+    assert_eq!(money("2.674999").cents(), 267);
+
+    assert!(matches!("abc".parse::<Money>(), Err(Error::InvalidAmount(_))));
+    assert!(matches!("12-5".parse::<Money>(), Err(Error::InvalidAmount(_))));
+    assert!(matches!("99999999999999999999".parse::<Money>(), Err(Error::InvalidAmount(_))));
+}
+
+#[test]
+fn from_f64_rounds_the_written_number()
+{
+    // 2.675 * 100 is 267.49999999999997 as f64
+    assert_eq!(Money::from_f64(2.675).cents(), 268);
+    assert_eq!(Money::from_f64(-1235.019).cents(), -123502);
+    assert_eq!(Money::from_f64(0.1 + 0.2).cents(), 30);
+    assert_eq!(Money::from_f64(f64::NAN), Money::ZERO);
+    assert_eq!(Quantity::from_f64(0.123456789).to_string(), "0.12345679");
+}
+
+#[test]
+fn display()
+{
+    assert_eq!(money("-12.3").to_string(), "-12.30");
+    assert_eq!(money("0.05").to_string(), "0.05");
+    assert_eq!(format!("{:>8}", money("1")), "    1.00");
+    assert_eq!(format!("{:>8.2}", money("-1")), "   -1.00");
+    assert_eq!(format!("{:<6}|", quantity("-2")), "-2    |");
+    assert_eq!(quantity("12.50").to_string(), "12.5");
+    assert_eq!(quantity("3").to_string(), "3");
+    assert_eq!(quantity("12.50").decimal_places(), 1);
+}
+
+#[test]
+fn sums_are_exact()
+{
+    let sum: Money = std::iter::repeat(money("0.10")).take(1000).sum();
+    assert_eq!(sum, money("100"));
+
+    let floats: f64 = std::iter::repeat(0.1).take(1000).sum();
+    assert_ne!(floats, 100.0);
+
+    let mut year = AccountingYear::default(2024);
+    for month in year.months.iter_mut() {
+        month.set_income(money("1000.10"));
+        month.set_expenses(money("333.33"));
+    }
+    assert_eq!(year.get_sum_income(), money("12001.20"));
+    assert_eq!(year.get_difference(), money("8001.24"));
+}
+
+#[test]
+fn arithmetic()
+{
+    assert_eq!(money("10") - money("12.5"), money("-2.5"));
+    assert_eq!(-money("3").abs(), money("-3"));
+    assert_eq!(money("100").mul_f64(0.075), money("7.5"));
+    assert_eq!(money("0.05").div_rounded(2), Some(money("0.03")));
+    assert_eq!(money("-0.05").div_rounded(2), Some(money("-0.03")));
+    assert_eq!(money("1").div_rounded(0), None);
+}
+
+#[test]
+fn quantity_times_price()
+{
+    assert_eq!(quantity("12.5").times(quantity("80")), money("1000"));
+    assert_eq!(quantity("0.00123456").times(quantity("45123.12345678")), money("55.71"));
+    assert_eq!(quantity("3").times(quantity("0.005")), money("0.02"));
+}
+
+#[test]
+fn serialized_as_numbers()
+{
+    let values = vec![money("0.1"), money("-1234.56"), money("0.07"), Money::from_cents(987654321012)];
+    let yaml = serde_yaml::to_string(&values).unwrap();
+    assert_eq!(yaml, "- 0.1\n- -1234.56\n- 0.07\n- 9876543210.12\n");
+    assert_eq!(serde_yaml::from_str::<Vec<Money>>(&yaml).unwrap(), values);
+
+    // older files can contain whole numbers and floats with more decimal places
+    assert_eq!(serde_yaml::from_str::<Vec<Money>>("[5, 2.675]").unwrap(), vec![money("5"), money("2.68")]);
+    assert_eq!(serde_yaml::from_str::<Quantity>("0.12345678").unwrap(), quantity("0.12345678"));
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use finanzbuch_lib::currency::Currency;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::savings_plan_section::SavingsPlanSection;
+use finanzbuch_lib::investing::SavingsPlanInterval;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+
+use common::depot_entry;
+use common::money;
+
+fn assert_close(actual: f64, expected: f64)
+{
+    assert!((actual - expected).abs() < 1e-6, "{actual} is not {expected}");
+}
+
+#[test]
+fn without_cash_flows_both_returns_are_equal()
+{
+    let entry = depot_entry(
+        InvestmentVariant::Etf,
+        &[(2023, 12, "10", "100", "0"), (2024, 1, "10", "110", "0"), (2024, 2, "10", "121", "0")],
+    );
+    let performance = entry.performance((2024, 1), (2024, 2)).unwrap();
+
+    assert_eq!((performance.start_value, performance.end_value), (money("1000"), money("1210")));
+    assert_eq!(performance.gain(), money("210"));
+    assert_eq!(performance.months(), 2);
+    assert_close(performance.time_weighted, 0.21);
+    assert_close(performance.annualized_time_weighted(), 1.21_f64.powi(6) - 1.0);
+    // 60 days from January 1st to March 1st
+    assert_close(performance.xirr.unwrap(), 1.21_f64.powf(365.0 / 60.0) - 1.0);
+    assert_close(performance.money_weighted().unwrap(), 0.21);
+}
+
+#[test]
+fn time_weighted_ignores_when_money_was_invested()
+{
+    // +10% with 100, then 1000 more are invested and everything loses 10%
+    let entry = depot_entry(
+        InvestmentVariant::Etf,
+        &[(2023, 12, "1", "100", "0"), (2024, 1, "1", "110", "0"), (2024, 2, "10", "99.9", "1000")],
+    );
+    let performance = entry.performance((2024, 1), (2024, 2)).unwrap();
+
+    assert_eq!(performance.cash_flow, money("1000"));
+    assert_close(performance.time_weighted, 1.1 * 0.9 - 1.0);
+    // most of the money was invested before the loss
+    assert!(performance.money_weighted().unwrap() < -0.08);
+}
+
+#[test]
+fn savings_plan_is_a_cash_flow()
+{
+    let mut entry = depot_entry(
+        InvestmentVariant::Etf,
+        &[(2024, 1, "1", "100", "0"), (2024, 2, "2", "100", "0"), (2024, 3, "3", "100", "0")],
+    );
+    let section = SavingsPlanSection {
+        start: FastDate::new_risky(2024, 1, 1),
+        end: FastDate::new_risky(2024, 12, 31),
+        amount: money("100"),
+        interval: SavingsPlanInterval::Monthly,
+    };
+    entry.add_savings_plan_section(section).unwrap();
+
+    let performance = entry.performance((2024, 1), (2024, 3)).unwrap();
+    assert_eq!((performance.start_value, performance.cash_flow), (Money::ZERO, money("300")));
+    assert_close(performance.time_weighted, 0.0);
+    assert_close(performance.xirr.unwrap(), 0.0);
+
+    // nothing happened in this range
+    let empty = entry.performance((2030, 1), (2030, 12)).unwrap();
+    assert_eq!((empty.time_weighted, empty.xirr), (0.0, None));
+    assert!(matches!(entry.performance((2024, 13), (2024, 12)), Err(Error::InvalidMonth(13))));
+}
+
+#[test]
+fn per_variant_and_for_the_whole_depot()
+{
+    let etf = depot_entry(InvestmentVariant::Etf, &[(2023, 12, "10", "100", "0"), (2024, 1, "10", "110", "0")]);
+    let mut stock = depot_entry(InvestmentVariant::Stock, &[(2023, 12, "10", "100", "0"), (2024, 1, "10", "90", "0")]);
+    stock.currency = Currency::new("USD").unwrap();
+
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.investing.depot.add_entry("ETF", etf.clone());
+    datafile.investing.depot.add_entry("Stock", stock);
+    let depot = &datafile.investing.depot;
+
+    let etfs = depot.performance(Some(&InvestmentVariant::Etf), (2024, 1), (2024, 1)).unwrap();
+    assert_eq!(etfs, etf.performance((2024, 1), (2024, 1)).unwrap());
+    let all = depot.performance(None, (2024, 1), (2024, 1)).unwrap();
+    assert_eq!((all.start_value, all.end_value), (money("2000"), money("2000")));
+    assert_close(all.time_weighted, 0.0);
+
+    // the stock is worth half as much in EUR
+    assert!(matches!(
+        datafile.depot_performance(None, (2024, 1), (2024, 1)),
+        Err(Error::MissingExchangeRate { .. })
+    ));
+    datafile.exchange_rates.set_rate(Currency::new("USD").unwrap(), 2023, 1, 0.5).unwrap();
+    let converted = datafile.depot_performance(None, (2024, 1), (2024, 1)).unwrap();
+    assert_eq!((converted.start_value, converted.end_value), (money("1500"), money("1550")));
+}
+
+#[test]
+fn prognosis_compounds_every_cash_flow()
+{
+    let mut entry = depot_entry(InvestmentVariant::Etf, &[(2024, 2, "0", "100", "50")]);
+    let section = SavingsPlanSection {
+        start: FastDate::new_risky(2024, 1, 1),
+        end: FastDate::new_risky(2024, 12, 31),
+        amount: money("100"),
+        interval: SavingsPlanInterval::Monthly,
+    };
+    entry.add_savings_plan_section(section).unwrap();
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.investing.depot.add_entry("ETF", entry);
+
+    let prognosis = datafile.investing.depot.prognosis(12, (2024, 1), (2024, 3)).unwrap();
+    assert_eq!(prognosis.len(), 3);
+    let rate = 1.12_f64.powf(1.0 / 12.0);
+    let january = 100.0 * rate;
+    let february = (january + 150.0) * rate;
+    let march = (february + 100.0) * rate;
+    assert_eq!(
+        prognosis,
+        vec![Money::from_f64(january), Money::from_f64(february), Money::from_f64(march)]
+    );
+
+    // without growth it is the sum of all cash flows
+    let flat = datafile.investing.depot.prognosis(0, (2024, 1), (2024, 12)).unwrap();
+    assert_eq!(flat.last(), Some(&money("1250")));
+    assert_eq!(datafile.depot_prognosis(0, (2024, 1), (2024, 12)).unwrap(), flat);
+}
+
+#[test]
+fn prognosis_starts_with_the_value_before()
+{
+    // a whole year with 7% and no cash flows
+    let entry = depot_entry(InvestmentVariant::Etf, &[(2023, 12, "10", "100", "0"), (2024, 12, "10", "100", "0")]);
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.investing.depot.add_entry("ETF", entry);
+
+    let prognosis = datafile.investing.depot.prognosis(7, (2024, 1), (2024, 12)).unwrap();
+    assert_eq!(prognosis.len(), 12);
+    assert_eq!(prognosis.last(), Some(&money("1070")));
+    assert!(matches!(
+        datafile.investing.depot.prognosis(7, (2024, 0), (2024, 12)),
+        Err(Error::InvalidMonth(0))
+    ));
+}
@@ -0,0 +1,104 @@
+use finanzbuch_lib::accounting::recurrence::Recurrence;
+use finanzbuch_lib::accounting::recurrence::RecurringInOut;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::Accounting;
+use finanzbuch_lib::AccountingMonth;
+use finanzbuch_lib::Money;
+
+fn accounting() -> Accounting
+{
+    let mut accounting = Accounting::default();
+    accounting
+        .recurring_income
+        .push(RecurringInOut::new("Salary", 2500.0, Recurrence::Month, 1, 1));
+    let mut rent = RecurringInOut::new("Rent", 900.0, Recurrence::Month, 1, 1);
+    rent.end = Some(FastDate::new_risky(2024, 6, 30));
+    accounting.recurring_expenses.push(rent);
+    return accounting;
+}
+
+#[test]
+fn only_when_enabled()
+{
+    let mut accounting = accounting();
+    assert!(accounting.add_or_get_year(2023).months.iter().all(|m| m.is_default()));
+
+    accounting.prefill_from_recurring = true;
+    let year = accounting.add_or_get_year(2024);
+    assert!(year.months[0].is_planned());
+    assert_eq!(
+        (year.months[0].income(), year.months[0].expenses()),
+        (Money::from_f64(2500.0), Money::from_f64(900.0))
+    );
+    assert_eq!(year.months[6].expenses(), Money::from_f64(0.0));
+
+    // existing years are not changed
+    assert!(accounting.add_or_get_year(2023).months.iter().all(|m| m.is_default()));
+}
+
+#[test]
+fn manual_changes_are_kept()
+{
+    let mut accounting = accounting();
+    accounting.prefill_from_recurring = true;
+    let year = accounting.add_or_get_year(2024);
+
+    year.months[0].set_expenses(Money::from_f64(1000.0));
+    year.months[1].set_note(String::from("only a note"));
+    year.insert_or_overwrite_month(AccountingMonth::new(3, Money::from_f64(100.0), Money::from_f64(50.0), String::new()).unwrap());
+    assert!(!year.months[0].is_planned());
+    assert!(year.months[1].is_planned());
+
+    // the rent was raised, refresh the planned months
+    accounting.recurring_expenses[0].add_change(FastDate::new_risky(2024, 1, 1), 950.0);
+    assert_eq!(accounting.fill_planned_months(2024), 10);
+
+    let months = &accounting.history.get(&2024).unwrap().months;
+    assert_eq!(months[0].expenses(), Money::from_f64(1000.0));
+    assert_eq!((months[1].expenses(), months[1].note()), (Money::from_f64(950.0), "only a note"));
+    assert_eq!(months[2].income(), Money::from_f64(100.0));
+}
+
+#[test]
+fn planned_months_are_not_actual_values()
+{
+    let mut accounting = accounting();
+    accounting.prefill_from_recurring = true;
+    accounting.add_or_get_year(2024).months[0].set_income(Money::from_f64(2600.0));
+
+    assert_eq!(accounting.history.get(&2024).unwrap().months[1].evaluate_goal(1.0), None);
+    assert_eq!(accounting.get_goal_streaks().current, 1);
+
+    let comparisons = accounting.compare_with_projection((2024, 1), (2024, 2)).unwrap();
+    assert_eq!(comparisons[0].income_deviation(), Some(Money::from_f64(100.0)));
+    assert_eq!(comparisons[1].actual_income, None);
+
+    let yaml = serde_yaml::to_string(&accounting.history.get(&2024).unwrap().months[1]).unwrap();
+    assert!(yaml.contains("planned: true"));
+}
+
+#[test]
+fn planned_months_are_not_in_the_year_summary()
+{
+    let mut accounting = accounting();
+    accounting.prefill_from_recurring = true;
+    let year = accounting.add_or_get_year(2024);
+    for month in year.months[0..6].iter_mut() {
+        month.set_income(Money::from_f64(2000.0));
+        month.set_expenses(Money::from_f64(1500.0));
+    }
+    assert!(year.months[6..].iter().all(|m| m.is_planned()));
+
+    // with the planned salaries, 33% would have been spent
+    let summary = year.get_summary(0.5);
+    assert_eq!(
+        (summary.sum_income, summary.sum_expenses),
+        (Money::from_f64(12000.0), Money::from_f64(9000.0))
+    );
+    assert_eq!(summary.percentage, Some(0.75));
+    assert_eq!(summary.median_income, Some(Money::from_f64(2000.0)));
+    assert_eq!(summary.median_difference, Some(Money::from_f64(500.0)));
+    assert!(!summary.goal.unwrap().reached);
+    assert_eq!((summary.months_reached, summary.months_missed), (0, 6));
+    assert_eq!(year.get_median_percentage_100(), Some(75));
+}
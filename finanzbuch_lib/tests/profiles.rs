@@ -0,0 +1,107 @@
+use finanzbuch_lib::profiles::AppConfig;
+use finanzbuch_lib::profiles::Location;
+use finanzbuch_lib::profiles::Selection;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Error;
+use std::path::PathBuf;
+
+fn args(list: &[&str]) -> Vec<String> { list.iter().map(|a| String::from(*a)).collect() }
+
+fn two_profiles() -> AppConfig
+{
+    let mut config = AppConfig::default();
+    config.add_profile("me", PathBuf::from("/tmp/me.yaml")).unwrap();
+    config.add_profile("household", PathBuf::from("/tmp/household.yaml")).unwrap();
+    return config;
+}
+
+#[test]
+fn add_and_remove()
+{
+    let mut config = two_profiles();
+    assert!(matches!(
+        config.add_profile("me", PathBuf::from("/tmp/other.yaml")),
+        Err(Error::InvalidProfile(_))
+    ));
+    assert!(matches!(
+        config.add_profile("  ", PathBuf::from("/tmp/other.yaml")),
+        Err(Error::InvalidProfile(_))
+    ));
+
+    config.last_profile = Some(String::from("me"));
+    assert_eq!(config.remove_profile("me").unwrap().path, PathBuf::from("/tmp/me.yaml"));
+    assert_eq!(config.last_profile, None);
+    assert!(matches!(config.remove_profile("me"), Err(Error::UnknownProfile(_))));
+}
+
+#[test]
+fn resolve_order()
+{
+    let mut config = two_profiles();
+    config.last_profile = Some(String::from("me"));
+
+    assert_eq!(
+        config.resolve(Some(Selection::File(PathBuf::from("/tmp/household.yaml")))).unwrap(),
+        Location {
+            profile: Some(String::from("household")),
+            path: PathBuf::from("/tmp/household.yaml")
+        }
+    );
+    assert_eq!(
+        config.resolve(Some(Selection::Profile(String::from("household")))).unwrap().path,
+        PathBuf::from("/tmp/household.yaml")
+    );
+    assert!(matches!(
+        config.resolve(Some(Selection::Profile(String::from("partner")))),
+        Err(Error::UnknownProfile(_))
+    ));
+    assert_eq!(config.resolve(None).unwrap().path, PathBuf::from("/tmp/me.yaml"));
+
+    // last profile was removed in the meantime
+    config.last_profile = Some(String::from("partner"));
+    assert_eq!(config.resolve(None).unwrap().path, DataFile::home_path().unwrap());
+}
+
+#[test]
+fn selection_from_args()
+{
+    assert_eq!(Selection::from_args(args(&["finanzbuch"])), None);
+    assert_eq!(
+        Selection::from_args(args(&["finanzbuch", "--profile", "me"])),
+        Some(Selection::Profile(String::from("me")))
+    );
+    assert_eq!(
+        Selection::from_args(args(&["finanzbuch", "--profile=me", "--file=/tmp/a.yaml"])),
+        Some(Selection::File(PathBuf::from("/tmp/a.yaml")))
+    );
+    assert_eq!(Selection::from_args(args(&["finanzbuch", "--file", ""])), None);
+}
+
+#[test]
+fn config_round_trip()
+{
+    let path = PathBuf::from("/tmp/finanzbuch_profiles_config/sub/config.yaml");
+    let _ = std::fs::remove_dir_all("/tmp/finanzbuch_profiles_config");
+    assert_eq!(AppConfig::read_from_custom_path(&path).unwrap(), AppConfig::default());
+
+    let mut config = two_profiles();
+    config.last_profile = Some(String::from("household"));
+    config.write_to_custom_path(&path).unwrap();
+
+    assert_eq!(AppConfig::read_from_custom_path(&path).unwrap(), config);
+}
+
+#[test]
+fn write_goes_back_into_the_file_that_was_read()
+{
+    let path = PathBuf::from("/tmp/finanzbuch_profiles_write_back.yaml");
+    let _ = std::fs::remove_file(&path);
+
+    let mut datafile = DataFile::read_from_custom_path(path.clone()).unwrap();
+    datafile.write_on_drop = false;
+    datafile.backup_count = 0;
+    datafile.accounting.goal = 0.5;
+    datafile.write().unwrap();
+
+    assert_eq!(DataFile::read_from_custom_path(path).unwrap().accounting.goal, 0.5);
+}
@@ -0,0 +1,132 @@
+use finanzbuch_lib::accounting::projection;
+use finanzbuch_lib::accounting::recurrence::Recurrence;
+use finanzbuch_lib::accounting::recurrence::RecurringInOut;
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::Accounting;
+use finanzbuch_lib::AccountingMonth;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+
+fn recurring(quantity: f64, recurrence: Recurrence, interval: u16, frequency: u16) -> RecurringInOut
+{
+    return RecurringInOut::new("name", quantity, recurrence, interval, frequency);
+}
+
+fn round(value: f64) -> f64 { (value * 10000.0).round() / 10000.0 }
+
+#[test]
+fn per_month_and_year()
+{
+    // the example of the comment in RecurringInOut
+    let every_5_weeks = recurring(100.0, Recurrence::Week, 5, 1);
+    assert_eq!(round(every_5_weeks.occurrences_per_year()), 10.4286);
+    assert_eq!(round(every_5_weeks.per_month()), 86.9048);
+
+    assert_eq!(recurring(10.0, Recurrence::Day, 1, 1).per_year(), 3650.0);
+    assert_eq!(recurring(50.0, Recurrence::Month, 3, 1).per_year(), 200.0);
+    assert_eq!(recurring(30.0, Recurrence::Month, 1, 2).per_month(), 60.0);
+    assert_eq!(recurring(1200.0, Recurrence::Year, 2, 1).per_month(), 50.0);
+    assert_eq!(recurring(1200.0, Recurrence::Year, 0, 1).per_month(), 0.0);
+}
+
+#[test]
+fn month_range()
+{
+    let months = projection::month_range((2023, 11), (2024, 2)).unwrap();
+    assert_eq!(months, vec![(2023, 11), (2023, 12), (2024, 1), (2024, 2)]);
+    assert!(projection::month_range((2024, 2), (2024, 1)).unwrap().is_empty());
+    assert!(matches!(projection::month_range((2024, 0), (2024, 1)), Err(Error::InvalidMonth(0))));
+}
+
+#[test]
+fn compare_with_actual_values()
+{
+    let mut accounting = Accounting::default();
+    accounting.recurring_income.push(recurring(2500.0, Recurrence::Month, 1, 1));
+    accounting.recurring_expenses.push(recurring(900.0, Recurrence::Month, 1, 1));
+    accounting.recurring_expenses.push(recurring(1200.0, Recurrence::Year, 1, 1));
+    accounting
+        .add_or_get_year(2024)
+        .insert_or_overwrite_month(AccountingMonth::new(12, Money::from_f64(2600.0), Money::from_f64(1200.0), String::new()).unwrap());
+
+    let projected = accounting.project((2024, 12), (2025, 1)).unwrap();
+    assert_eq!(projected.len(), 2);
+    assert_eq!((projected[1].year, projected[1].month), (2025, 1));
+    assert_eq!(
+        (projected[1].income, projected[1].expenses),
+        (Money::from_f64(2500.0), Money::from_f64(1000.0))
+    );
+
+    let comparisons = accounting.compare_with_projection((2024, 11), (2025, 1)).unwrap();
+    assert_eq!(comparisons[0].actual_income, None); // month without data
+    assert_eq!(comparisons[1].income_deviation(), Some(Money::from_f64(100.0)));
+    assert_eq!(comparisons[1].expenses_deviation(), Some(Money::from_f64(200.0)));
+    assert_eq!(comparisons[2].expenses_deviation(), None); // year does not exist
+}
+
+#[test]
+fn validity_and_changes()
+{
+    // rent of 900 from March 2024 on, 950 from 2025 on
+    let mut rent = recurring(900.0, Recurrence::Month, 1, 1);
+    rent.start = Some(FastDate::new_risky(2024, 3, 1));
+    rent.add_change(FastDate::new_risky(2025, 1, 1), 950.0);
+    assert_eq!(rent.amount_in_month(2024, 2), 0.0);
+    assert_eq!(rent.amount_in_month(2024, 3), 900.0);
+    assert_eq!(rent.amount_in_month(2025, 1), 950.0);
+    assert_eq!(rent.current_quantity(), 950.0);
+
+    // cancelled in the middle of the month, without anchor it is spread over the days
+    let mut subscription = recurring(30.0, Recurrence::Month, 1, 1);
+    subscription.end = Some(FastDate::new_risky(2024, 4, 10));
+    assert_eq!(subscription.amount_in_month(2024, 4), 10.0);
+    assert_eq!(subscription.amount_in_month(2024, 5), 0.0);
+
+    // with anchor, it is either booked in full or not at all
+    subscription.anchor_day = Some(15);
+    assert_eq!(subscription.amount_in_month(2024, 3), 30.0);
+    assert_eq!(subscription.amount_in_month(2024, 4), 0.0);
+
+    // a change in the middle of the month
+    let mut changed = recurring(30.0, Recurrence::Month, 1, 1);
+    changed.add_change(FastDate::new_risky(2024, 4, 16), 60.0);
+    assert_eq!(round(changed.amount_in_month(2024, 4)), 45.0);
+}
+
+#[test]
+fn anchored_intervals()
+{
+    // every 3 months on the 31st, starting in January
+    let mut quarterly = recurring(100.0, Recurrence::Month, 3, 1);
+    quarterly.start = Some(FastDate::new_risky(2024, 1, 31));
+    quarterly.anchor_day = Some(31);
+    let booked: Vec<u8> = (1..=12).filter(|m| quarterly.amount_in_month(2024, *m) > 0.0).collect();
+    assert_eq!(booked, vec![1, 4, 7, 10]); // April has 30 days, the 30th is used
+
+    // every 2 years in May
+    let mut yearly = recurring(500.0, Recurrence::Year, 2, 1);
+    yearly.start = Some(FastDate::new_risky(2024, 5, 1));
+    yearly.anchor_day = Some(1);
+    assert_eq!(yearly.amount_in_month(2024, 5), 500.0);
+    assert_eq!(yearly.amount_in_month(2024, 6), 0.0);
+    assert_eq!(yearly.amount_in_month(2025, 5), 0.0);
+    assert_eq!(yearly.amount_in_month(2026, 5), 500.0);
+}
+
+#[test]
+fn projection_respects_periods()
+{
+    let mut accounting = Accounting::default();
+    let mut rent = recurring(900.0, Recurrence::Month, 1, 1);
+    rent.end = Some(FastDate::new_risky(2024, 2, 29));
+    accounting.recurring_expenses.push(rent);
+    accounting.recurring_expenses.push(recurring(1000.0, Recurrence::Month, 1, 1));
+
+    let expenses: Vec<f64> = accounting
+        .project((2024, 1), (2024, 3))
+        .unwrap()
+        .iter()
+        .map(|p| p.expenses.to_f64())
+        .collect();
+    assert_eq!(expenses, vec![1900.0, 1900.0, 1000.0]);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use finanzbuch_lib::currency::Currency;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::running_costs::ExpenseRatio;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
+
+use common::depot_entry;
+use common::money;
+
+#[test]
+fn expense_ratio_changes()
+{
+    let mut entry = depot_entry(InvestmentVariant::Etf, &[]);
+    entry.set_expense_ratio(ExpenseRatio::new(2024, 1, 1.2).unwrap());
+    entry.set_expense_ratio(ExpenseRatio::new(2024, 7, 0.5).unwrap());
+    entry.set_expense_ratio(ExpenseRatio::new(2023, 1, 2.0).unwrap());
+    entry.set_expense_ratio(ExpenseRatio::new(2024, 7, 0.12).unwrap());
+
+    let months: Vec<(u16, u8)> = entry.expense_ratios().iter().map(|r| (r.year, r.month)).collect();
+    assert_eq!(months, vec![(2023, 1), (2024, 1), (2024, 7)]);
+    assert_eq!(entry.expense_ratio(2024, 6).unwrap().percent, 1.2);
+    assert_eq!(entry.expense_ratio(2030, 1).unwrap().percent, 0.12);
+    assert!(entry.expense_ratio(2022, 12).is_none());
+
+    assert_eq!(entry.remove_expense_ratio(2023, 1).unwrap().percent, 2.0);
+    assert!(entry.remove_expense_ratio(2023, 1).is_none());
+    assert!(entry.expense_ratio(2023, 6).is_none());
+
+    assert!(matches!(ExpenseRatio::new(2024, 1, -0.1), Err(Error::InvalidExpenseRatio(_))));
+    assert!(matches!(ExpenseRatio::new(2024, 1, 100.0), Err(Error::InvalidExpenseRatio(_))));
+    assert!(matches!(ExpenseRatio::new(2024, 0, 0.2), Err(Error::InvalidMonth(0))));
+}
+
+#[test]
+fn costs_and_value_without_them()
+{
+    // 0.1% of the value per month, the price stays the same after costs
+    let months = [(2023, 12, "10", "100", "0"), (2024, 1, "10", "100", "0"), (2024, 2, "20", "100", "1000")];
+    let mut entry = depot_entry(InvestmentVariant::Etf, &months);
+    entry.set_expense_ratio(ExpenseRatio::new(2024, 1, 1.2).unwrap());
+    let costs = entry.running_costs((2023, 12), (2024, 2)).unwrap();
+
+    let values: Vec<(Money, Money, Money)> = costs.months.iter().map(|m| (m.value, m.cost, m.value_without_costs)).collect();
+    assert_eq!(
+        values,
+        vec![
+            // no costs before 2024, and the start of the range
+            (money("1000"), Money::ZERO, money("1000")),
+            (money("1000"), money("1"), money("1001")),
+            (money("2000"), money("2"), money("2003")),
+        ]
+    );
+    assert_eq!(costs.total_cost(), money("3"));
+    assert_eq!(costs.drag(), money("3"));
+
+    // without an expense ratio nothing changes
+    let mut free = entry.clone();
+    free.remove_expense_ratio(2024, 1);
+    let costs = free.running_costs((2024, 1), (2024, 2)).unwrap();
+    assert!(costs.months.iter().all(|m| m.cost.is_zero() && m.value == m.value_without_costs));
+}
+
+#[test]
+fn costs_of_the_whole_depot()
+{
+    let months = [(2023, 12, "10", "100", "0"), (2024, 1, "10", "100", "0")];
+    let mut eur = depot_entry(InvestmentVariant::Etf, &months);
+    eur.set_expense_ratio(ExpenseRatio::new(2024, 1, 1.2).unwrap());
+    let mut usd = eur.clone();
+    usd.currency = Currency::new("USD").unwrap();
+
+    let mut datafile = DataFile::default_no_write_on_drop();
+    datafile.investing.depot.add_entry("EUR", eur);
+    datafile.investing.depot.add_entry("USD", usd);
+
+    let sum = datafile.investing.depot.running_costs((2024, 1), (2024, 1)).unwrap();
+    assert_eq!((sum.total_cost(), sum.drag()), (money("2"), money("2")));
+
+    assert!(matches!(
+        datafile.depot_running_costs((2024, 1), (2024, 1)),
+        Err(Error::MissingExchangeRate { .. })
+    ));
+    datafile.exchange_rates.set_rate(Currency::new("USD").unwrap(), 2023, 1, 0.5).unwrap();
+    let converted = datafile.depot_running_costs((2024, 1), (2024, 1)).unwrap();
+    assert_eq!(converted.months[0].value, money("1500"));
+    assert_eq!(converted.total_cost(), money("1.5"));
+
+    let yaml = serde_yaml::to_string(datafile.investing.depot.get_entry_from_str("EUR").unwrap()).unwrap();
+    assert!(yaml.contains("expense_ratios:\n- year: 2024\n  month: 1\n  percent: 1.2\n"));
+}
@@ -3,16 +3,18 @@ use std::str::FromStr;
 use finanzbuch_lib::fast_date::FastDate;
 use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
 use finanzbuch_lib::investing::inv_year::InvestmentYear;
+use finanzbuch_lib::locale::Locale;
 use finanzbuch_lib::CurrentDate;
 use finanzbuch_lib::DepotEntry;
-use finanzbuch_lib::SanitizeInput;
+use finanzbuch_lib::Money;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::investing::savings_plan::savings_plan_get_html;
 // keep this one imported for better linting support
+use crate::DATAFILE_GLOBAL;
 #[allow(unused_imports)]
 use finanzbuch_lib::datafile;
-use crate::DATAFILE_GLOBAL;
 
 static YEAR_TD_ID_PREFIX: &str = "depotTableScrollTarget";
 
@@ -25,41 +27,61 @@ pub enum InvestmentMonthFields
 }
 
 #[tauri::command]
-/// Returns `false` if either
-/// - any of the the given fields could not be parsed
+/// Returns an error message if either
+/// - the value is not a number in the locale of the data file
 /// - no `DepotEntry` with `depot_entry_hash` exists
 /// - there is no entry for the given `year` in this `DepotEntry`
 ///
-/// The given value was only saved, if true is returned
-pub fn depot_entry_set_cell_value(depot_entry_hash: String, field: InvestmentMonthFields, value: String, year: u16, month: usize) -> bool
+/// The given value was only saved, if `Ok` is returned
+pub fn depot_entry_set_cell_value(
+    depot_entry_hash: String,
+    field: InvestmentMonthFields,
+    value: String,
+    year: u16,
+    month: usize,
+) -> Result<(), String>
 {
     // println!( "depot_entry_set_cell_value: {:?} {:?} {:?} {:?} {:?}", depot_entry_hash, field, value, year, month );
 
     // JS does not support 64 bit Ints without using BigInt and BigInt cannot be serialized.
     let Ok(depot_entry_hash) = depot_entry_hash.parse() else {
-        return false;
+        return Err(format!("{depot_entry_hash} is not a valid depot entry"));
     };
 
-    let Ok(value_f64) = SanitizeInput::string_to_f64(&value, false) else {
-        return false;
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return Err(String::from("No profile is open"));
     };
-    let mut datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
-    let year = match datafile.investing.depot.entries.get_mut(&depot_entry_hash) {
-        Some(v) => match v.history.get_mut(&(year as u16)) {
-            Some(v) => v,
-            None => return false,
-        },
-        None => return false,
+    let locale = datafile.locale;
+    let Some(depot_entry) = datafile.investing.depot.entries.get_mut(&depot_entry_hash) else {
+        return Err(String::from("The depot entry does not exist anymore"));
     };
-
-    match field {
-        InvestmentMonthFields::PricePerUnit => year.months[month - 1].set_price_per_unit(value_f64),
-        InvestmentMonthFields::Amount => year.months[month - 1].set_amount(value_f64),
-        InvestmentMonthFields::AdditionalTransactions => year.months[month - 1].set_additional_transactions(value_f64),
+    // from the first transaction on, only the price can be changed by hand
+    let calculated = depot_entry
+        .transactions()
+        .first()
+        .is_some_and(|t| (t.date.year(), t.date.month() as usize) <= (year, month));
+    if calculated && !matches!(field, InvestmentMonthFields::PricePerUnit) {
+        return Err(String::from("This value is calculated from the transactions of the depot entry"));
     }
+    let year = match depot_entry.history.get_mut(&year) {
+        Some(v) => v,
+        None => return Err(format!("The depot entry has no data for {year}")),
+    };
 
-    datafile.write();
-    return true;
+    let inv_month = &mut year.months[month - 1];
+    let parsed = match field {
+        InvestmentMonthFields::PricePerUnit => locale.parse_quantity(&value).map(|v| inv_month.set_price_per_unit(v)),
+        InvestmentMonthFields::Amount => locale.parse_quantity(&value).map(|v| inv_month.set_amount(v)),
+        InvestmentMonthFields::AdditionalTransactions => locale.parse_money(&value).map(|v| inv_month.set_additional_transactions(v)),
+    };
+    parsed.map_err(|e| e.to_string())?;
+
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+        return Err(e.to_string());
+    }
+    return Ok(());
 }
 
 #[tauri::command]
@@ -72,7 +94,11 @@ pub fn depot_entry_get_table_html(depot_entry_hash: String) -> String
         return format!(r#"<div class="error">This hash {depot_entry_hash} could not be parsed</div>"#);
     };
 
-    let mut datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return String::from(r#"<div class="error">No profile is open</div>"#);
+    };
+    let locale = datafile.locale;
     let depot_entry = match datafile.investing.depot.entries.get_mut(&depot_entry_hash) {
         None => return format!(r#"<div class="error">There is no depot entry with this hash: {depot_entry_hash}</div>"#),
         // if this ^ pops up after changing the hashing algorithm, the new one is not deterministic
@@ -108,16 +134,23 @@ pub fn depot_entry_get_table_html(depot_entry_hash: String) -> String
 
         // Find out max precision necessary
         for inv_month in inv_year.months.iter() {
-            price_precision = std::cmp::max(_count_precision(inv_month.price_per_unit()), price_precision);
-            amount_precision = std::cmp::max(_count_precision(inv_month.amount()), amount_precision);
+            price_precision = std::cmp::max(inv_month.price_per_unit().decimal_places(), price_precision);
+            amount_precision = std::cmp::max(inv_month.amount().decimal_places(), amount_precision);
         }
 
         // Generate html for the months <tr>'s
-        let mut trs_of_this_year: String =
-            match _build_all_month_rows(year_nr, &price_precision, &amount_precision, &depot_entry, &depot_entry_hash, inv_year) {
-                Ok(trs_of_this_year) => trs_of_this_year,
-                Err(error_msg_html) => return error_msg_html,
-            };
+        let mut trs_of_this_year: String = match _build_all_month_rows(
+            year_nr,
+            &price_precision,
+            &amount_precision,
+            &depot_entry,
+            &depot_entry_hash,
+            inv_year,
+            &locale,
+        ) {
+            Ok(trs_of_this_year) => trs_of_this_year,
+            Err(error_msg_html) => return error_msg_html,
+        };
 
         if history_iterator.peek() != None {
             // This is not the last year in the iterator, so add a spacer to visually seperate the years
@@ -143,6 +176,8 @@ pub fn depot_entry_get_table_html(depot_entry_hash: String) -> String
         );
     }
 
+    let savings_plan_html = savings_plan_get_html(depot_entry, &depot_entry_hash, &locale);
+
     format!(
         r#"
         <div class="depotEntry" id="{depot_entry_hash}">
@@ -177,6 +212,7 @@ pub fn depot_entry_get_table_html(depot_entry_hash: String) -> String
                     </thead>
                     <tbody>{all_years_trs}</tbody>
                 </table>
+                {savings_plan_html}
             </div>
         </div>
         "#
@@ -190,7 +226,10 @@ pub fn depot_entry_add_previous_year(depot_entry_hash: String) -> bool
         return false;
     };
 
-    let mut datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return false;
+    };
     let this_depot_entry = match datafile.investing.depot.entries.get_mut(&depot_entry_hash) {
         Some(de) => de,
         None => return false,
@@ -206,7 +245,10 @@ pub fn depot_entry_add_previous_year(depot_entry_hash: String) -> bool
         None => (),
     };
 
-    datafile.write();
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+        return false;
+    }
     return true;
 }
 
@@ -225,13 +267,19 @@ pub fn depot_entry_add(name: String, variant: String) -> bool
         }
     };
 
-    let mut datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return false;
+    };
     datafile
         .investing
         .depot
         .add_entry(name.as_str(), DepotEntry::default_with_current_year(name.as_str(), variant));
 
-    datafile.write();
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+        return false;
+    }
     return true;
 }
 
@@ -242,28 +290,24 @@ pub fn depot_entry_delete(depot_entry_hash: String) -> bool
         return false;
     };
 
-    let mut datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return false;
+    };
 
     let Some(_) = datafile.investing.depot.entries.remove(&depot_entry_hash) else {
         return false;
     };
 
-    datafile.write();
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+        return false;
+    }
     return true;
 }
 
 // -------------------- private -------------------- //
 
-fn _count_precision(num: f64) -> usize
-{
-    let num_str = format!("{}", num);
-    let num_str_split = num_str.split('.').collect::<Vec<&str>>();
-    match num_str_split.get(1) {
-        None => return 0,
-        Some(len) => return len.chars().filter(|&c| c != '0').count(),
-    };
-}
-
 /// - If sucessful, will return 12 `<tr>`'s for that year as a HTML String
 /// - In the case of an error, a warning as a HTML String will be returned
 fn _build_all_month_rows(
@@ -273,9 +317,11 @@ fn _build_all_month_rows(
     depot_entry: &DepotEntry,
     depot_entry_hash: &u64,
     inv_year: &InvestmentYear,
+    locale: &Locale,
 ) -> Result<String, String>
 {
     let mut trs_of_this_year: String = String::new();
+    let currency_symbol = depot_entry.currency.symbol();
 
     for inv_month in inv_year.months.iter() {
         let month_nr = inv_month.month_nr();
@@ -286,14 +332,12 @@ fn _build_all_month_rows(
         };
 
         // Group 1
-        let price = inv_month.price_per_unit();
-        let amount = inv_month.amount();
-        let price_fmt = format!("{:.*}", price_precision, price);
-        let amount_fmt = format!("{:.*}", amount_precision, amount);
-        let share_volume_fmt = format!("{:.2}", SanitizeInput::f64_to_monetary_f64(price * amount));
+        let price_fmt = locale.format_quantity(inv_month.price_per_unit(), *price_precision);
+        let amount_fmt = locale.format_quantity(inv_month.amount(), *amount_precision);
+        let share_volume_fmt = locale.format_money(inv_month.volume());
 
         // Group 2
-        let planned_trs: f64 = depot_entry.get_planned_transactions(match FastDate::new(year_nr.to_owned(), month_nr, 1) {
+        let planned_trs: Money = depot_entry.get_planned_transactions(match FastDate::new(year_nr.to_owned(), month_nr, 1) {
             Ok(v) => v,
             Err(_) => {
                 return Err(format!(
@@ -301,12 +345,12 @@ fn _build_all_month_rows(
                 ))
             }
         });
-        let combined_trs: f64 = planned_trs + inv_month.additional_transactions();
+        let combined_trs: Money = planned_trs + inv_month.additional_transactions();
 
-        // These only need precision 2, because they are monetary values
-        let additional_trs_fmt = format!("{:.2}", inv_month.additional_transactions());
-        let planned_trs_fmt = format!("{:.2}", planned_trs);
-        let combined_trs_fmt = format!("{:.2}", combined_trs);
+        // Money is always shown with 2 decimal places
+        let additional_trs_fmt = locale.format_money(inv_month.additional_transactions());
+        let planned_trs_fmt = locale.format_money(planned_trs);
+        let combined_trs_fmt = locale.format_money(combined_trs);
 
         // - <span> automatically adjusts it size to the content, which is way easier to use than fiddling with <input>'s
         //   but its innerHTML cannot be empty, or tabbing from one to the next will look weird
@@ -320,16 +364,16 @@ fn _build_all_month_rows(
                     <td>{month_nr}</td>
                     <td><span contenteditable="true" oninput="depotEntryTableSetCell()" 
                               data-year="{year_nr}" data-month="{month_nr}" data-hash="{depot_entry_hash}" data-type="itp"
-                              class="investingTablePrice">{price_fmt}</span> €</td>
+                              class="investingTablePrice">{price_fmt}</span> {currency_symbol}</td>
                     <td><span contenteditable="true" oninput="depotEntryTableSetCell()" 
                               data-year="{year_nr}" data-month="{month_nr}" data-hash="{depot_entry_hash}" data-type="its"
                               class="investingTableSharecount">{amount_fmt}</span></td>
-                    <td>{share_volume_fmt} €</td>
+                    <td>{share_volume_fmt} {currency_symbol}</td>
                     <td><span contenteditable="true" oninput="depotEntryTableSetCell()" 
                               data-year="{year_nr}" data-month="{month_nr}" data-hash="{depot_entry_hash}" data-type="ita"
-                              class="investingTableAdditional">{additional_trs_fmt}</span> €</td>
-                    <td>{planned_trs_fmt} €</td>
-                    <td>{combined_trs_fmt} €</td>
+                              class="investingTableAdditional">{additional_trs_fmt}</span> {currency_symbol}</td>
+                    <td>{planned_trs_fmt} {currency_symbol}</td>
+                    <td>{combined_trs_fmt} {currency_symbol}</td>
                 </tr>
                 "#,
             )
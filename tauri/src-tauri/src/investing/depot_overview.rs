@@ -1,6 +1,11 @@
+use finanzbuch_lib::currency::Currency;
 use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
+use finanzbuch_lib::investing::performance::Performance;
 use finanzbuch_lib::CurrentDate;
 use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Error;
+use finanzbuch_lib::Money;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -30,22 +35,31 @@ pub struct ChartJsDataset
 /// Adds or removes a comparison at the end and returns the html to replace the entire row of comparisons
 pub fn depot_overview_do_comparison_action(action: ComparisonAction)
 {
-    let mut datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return;
+    };
     match action {
         ComparisonAction::Add => datafile.investing.comparisons.push(7),
         ComparisonAction::Remove => {
             let _ = datafile.investing.comparisons.pop();
         }
     }
-    datafile.write();
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+    }
 }
 
 #[tauri::command]
 /// Get the html for the entire "Overview" page
 pub fn depot_overview_get_html() -> String
 {
-    let datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
-    let comparison_bar_html = _build_comparison_bar_html(&datafile);
+    let datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_ref() else {
+        return String::from(r#"<div class="error">No profile is open</div>"#);
+    };
+    let comparison_bar_html = _build_comparison_bar_html(datafile);
+    let performance_table_html = _build_performance_table_html(datafile);
 
     return format!(
         r#"
@@ -53,6 +67,7 @@ pub fn depot_overview_get_html() -> String
             <div class="depotOverview" id="comparisonSelectionContainer">
                 {comparison_bar_html}
             </div>
+            {performance_table_html}
             <div id="depotOverviewAllChartsContainer">
                 <div class="depotOverviewChartContainer">
                     <canvas class="chartjs" id="fullDepotChartContext"></canvas>
@@ -76,7 +91,10 @@ pub fn depot_overview_get_html() -> String
 /// Returnes an empty Vec, if there is no data available
 pub fn depot_overview_alltime_get_labels() -> Vec<String>
 {
-    let datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_ref() else {
+        return vec![];
+    };
 
     let Some((oldest_date, _, month_count)) = datafile.investing.depot.get_oldest_year_and_total_month_count() else {
         return vec![]; // All depot entries have no history so there is no data
@@ -98,29 +116,65 @@ pub fn depot_overview_alltime_get_labels() -> Vec<String>
 /// Constructs an Array of Objects that should be used in the ChartJs `data.datasets` property.
 pub fn depot_overview_alltime_get_datasets() -> Vec<ChartJsDataset>
 {
-    let datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_ref() else {
+        return vec![];
+    };
     let mut datasets: Vec<ChartJsDataset> = Vec::new();
 
     let mut depot_value_data = Vec::new();
     let mut transactions_data = Vec::new();
-    _alltime_graph_data_poll(&datafile, &mut depot_value_data, &mut transactions_data);
+    if let Err(e) = _alltime_graph_data_poll(datafile, &mut depot_value_data, &mut transactions_data) {
+        println!("{e}");
+        return vec![];
+    }
+    let mut currency = datafile.overview_currency().symbol().to_string();
+    if datafile.investing.inflation_adjusted {
+        for data in [&mut depot_value_data, &mut transactions_data] {
+            if let Err(e) = _deflate_alltime_data(datafile, data) {
+                println!("{e}");
+                return vec![];
+            }
+        }
+        currency = format!("{currency}, real");
+    }
 
     // 1. Depot value over time
     datasets.push(ChartJsDataset {
-        label: "Depot value".to_string(),
+        label: format!("Depot value ({currency})"),
         data: depot_value_data,
     });
     // 2. All planned and additional transactions
     datasets.push(ChartJsDataset {
-        label: "Transactions".to_string(),
+        label: format!("Transactions ({currency})"),
         data: transactions_data,
     });
 
-    // 3. Calculated prognosis for each comparison
+    // 3. Depot value without the running costs of the entries
+    let mut without_costs_data = _alltime_graph_get_without_costs(datafile);
+    if datafile.investing.inflation_adjusted {
+        if let Err(e) = _deflate_alltime_data(datafile, &mut without_costs_data) {
+            println!("{e}");
+            return vec![];
+        }
+    }
+    datasets.push(ChartJsDataset {
+        label: format!("Without running costs ({currency})"),
+        data: without_costs_data,
+    });
+
+    // 4. Calculated prognosis for each comparison
     for growth_rate in datafile.investing.comparisons.iter() {
+        let mut data = _alltime_graph_get_prognosis(datafile, *growth_rate);
+        if datafile.investing.inflation_adjusted {
+            if let Err(e) = _deflate_alltime_data(datafile, &mut data) {
+                println!("{e}");
+                return vec![];
+            }
+        }
         datasets.push(ChartJsDataset {
             label: format!("Prognosis {}%", *growth_rate),
-            data: _alltime_graph_get_prognosis(&datafile, *growth_rate),
+            data,
         });
     }
 
@@ -130,7 +184,10 @@ pub fn depot_overview_alltime_get_datasets() -> Vec<ChartJsDataset>
 #[tauri::command]
 pub fn depot_overview_change_comparison(comparison_id: String, new_value: String)
 {
-    let mut datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return;
+    };
 
     let Ok(new_value) = new_value.parse::<u8>() else {
         return;
@@ -143,60 +200,116 @@ pub fn depot_overview_change_comparison(comparison_id: String, new_value: String
         Some(comp_val) => *comp_val = new_value,
         None => {}
     };
-    datafile.write();
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+    }
 }
 
-// ------------------------- Private functions ------------------------- //
+#[tauri::command]
+/// Changes the currency all values of the overview are converted into. Returns `false` if the code is not valid
+pub fn depot_overview_set_currency(code: String) -> bool
+{
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return false;
+    };
+    let Ok(currency) = Currency::new(&code) else {
+        return false;
+    };
 
-fn _alltime_graph_get_prognosis(datafile: &DataFile, growth_rate: u8) -> Vec<f64>
+    datafile.investing.overview_currency = Some(currency);
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+        return false;
+    }
+    return true;
+}
+
+#[tauri::command]
+/// Show the datasets of the overview in prices of the current month
+pub fn depot_overview_set_inflation_adjusted(enabled: bool) -> bool
 {
-    // TODO calc in savings plans
-    // value of month 1 = 0 + saving plan transactions + additional transactions
-    // value of month 2 = (value of month 1) * growth + saving plan transactions + additional transactions
-    // this never uses the actual values of the depot, only the transactions
-
-    // since growth_rate is for one year, the monthly growth_rate has to be calculated:
-    // For growth_rate=7 : 1,07^(1÷12) = 1,005654145
-    //                                   1,005654145^12 = 1,07
-    let rate_yearly = 1.0 + (growth_rate as f64 / 100.0); // 1.08 for 8%
-    let rate_monthly = rate_yearly.powf(1.0 / 12.0);
-
-    let oldest_year: u16 = match datafile.investing.depot.get_oldest_year() {
-        Some(y) => y,
-        None => return vec![], // All depot entries have no history so there is no data
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return false;
     };
-    let current_year = CurrentDate::current_year();
 
-    // 1. get the oldest value of all entries and sum them up
-    let mut start_value: f64 = 0.0;
-    for entry in datafile.investing.depot.entries.values() {
-        match entry.history.first_key_value() {
-            Some((_, val)) => start_value += val.months[0].amount() * val.months[0].price_per_unit(),
-            None => {}
-        };
+    datafile.investing.inflation_adjusted = enabled;
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+        return false;
     }
+    return true;
+}
 
-    let total_months_in_depot = (current_year + 1 - oldest_year) * 12;
+// ------------------------- Private functions ------------------------- //
 
-    let mut values: Vec<f64> = Vec::new();
-    let mut prev: f64 = start_value;
+/// Deflates the values of `depot_overview_alltime_get_labels()` into prices of the current month, with `DataFile.price_index`
+fn _deflate_alltime_data(datafile: &DataFile, data: &mut [f64]) -> Result<(), Error>
+{
+    let Some(oldest_year) = datafile.investing.depot.get_oldest_year() else {
+        return Ok(());
+    };
+    let base = (CurrentDate::current_year(), CurrentDate::current_month());
 
-    for _ in 0..total_months_in_depot {
-        values.push(prev * rate_monthly);
-        prev = prev * rate_monthly;
+    for (i, value) in data.iter_mut().enumerate() {
+        let year = oldest_year + (i / 12) as u16;
+        let month = (i % 12) as u8 + 1;
+        *value = datafile.price_index.deflate(Money::from_f64(*value), year, month, base)?.to_f64();
     }
+    return Ok(());
+}
 
-    return values;
+/// One value for each label of `depot_overview_alltime_get_labels()`, in `DataFile::overview_currency()`
+///
+/// Returnes an empty Vec, if there is no data available
+fn _alltime_graph_get_prognosis(datafile: &DataFile, growth_rate: u8) -> Vec<f64>
+{
+    let Some((oldest_date, end_date, _)) = datafile.investing.depot.get_oldest_year_and_total_month_count() else {
+        return vec![]; // All depot entries have no history so there is no data
+    };
+
+    let first = (oldest_date.year(), oldest_date.month());
+    let last = (end_date.year(), end_date.month());
+    return match datafile.depot_prognosis(growth_rate, first, last) {
+        Ok(values) => values.iter().map(|v| v.to_f64()).collect(),
+        Err(e) => {
+            println!("{e}");
+            vec![]
+        }
+    };
+}
+
+/// One value for each label of `depot_overview_alltime_get_labels()`, in `DataFile::overview_currency()`
+///
+/// Returnes an empty Vec, if there is no data available
+fn _alltime_graph_get_without_costs(datafile: &DataFile) -> Vec<f64>
+{
+    let Some((oldest_date, end_date, _)) = datafile.investing.depot.get_oldest_year_and_total_month_count() else {
+        return vec![]; // All depot entries have no history so there is no data
+    };
+
+    let first = (oldest_date.year(), oldest_date.month());
+    let last = (end_date.year(), end_date.month());
+    return match datafile.depot_running_costs(first, last) {
+        Ok(costs) => costs.months.iter().map(|m| m.value_without_costs.to_f64()).collect(),
+        Err(e) => {
+            println!("{e}");
+            vec![]
+        }
+    };
 }
 
 /// - First Vec contains data for the total value of the depot in each month
 /// - Second Vec contains data for the total transactions in each month
 ///
+/// All values are converted into `DataFile::overview_currency()`
+///
 /// Does not change the Vec's, if there is no data available in the depot
-fn _alltime_graph_data_poll(datafile: &DataFile, history_data_vec: &mut Vec<f64>, transactions_data_vec: &mut Vec<f64>)
+fn _alltime_graph_data_poll(datafile: &DataFile, history_data_vec: &mut Vec<f64>, transactions_data_vec: &mut Vec<f64>) -> Result<(), Error>
 {
     let Some((oldest_date, end_date, month_count)) = datafile.investing.depot.get_oldest_year_and_total_month_count() else {
-        return; // All depot entries have no history so there is no data
+        return Ok(()); // All depot entries have no history so there is no data
     };
 
     *history_data_vec = vec![0.0; month_count];
@@ -219,15 +332,18 @@ fn _alltime_graph_data_poll(datafile: &DataFile, history_data_vec: &mut Vec<f64>
                 let i: usize = (index_year_offset + month.month_nr() as u16 - 1) as usize; // since months start with 1, subtract 1
 
                 // actual history //
-                history_data[i] = history_data[i] + month.amount() * month.price_per_unit();
+                let value = month.volume().to_f64();
+                history_data[i] = history_data[i] + datafile.to_overview_currency(entry, value, year.year_nr, month.month_nr())?;
 
                 // transactions //
-                transactions_data[i] = transactions_data[i]
-                    + month.additional_transactions()
-                    + entry.get_planned_transactions(FastDate::new_risky(year.year_nr, month.month_nr(), 1));
+                let transactions = (month.additional_transactions()
+                    + entry.get_planned_transactions(FastDate::new_risky(year.year_nr, month.month_nr(), 1)))
+                .to_f64();
+                transactions_data[i] = transactions_data[i] + datafile.to_overview_currency(entry, transactions, year.year_nr, month.month_nr())?;
             }
         }
     }
+    return Ok(());
 }
 
 /// Use like this:
@@ -259,6 +375,23 @@ fn _build_comparison_bar_html(datafile: &DataFile) -> String
         );
     }
 
+    // every currency that can be converted into, plus the ones of the depot entries
+    let selected = datafile.overview_currency();
+    let mut currencies: Vec<&Currency> = datafile.investing.depot.entries.values().map(|e| &e.currency).collect();
+    currencies.extend(datafile.exchange_rates.currencies());
+    currencies.push(&datafile.exchange_rates.base);
+    currencies.push(selected);
+    currencies.sort();
+    currencies.dedup();
+
+    let mut currency_options_html: String = String::new();
+    for currency in currencies {
+        let selected_attr = if currency == selected { "selected" } else { "" };
+        currency_options_html.push_str(format!(r#"<option value="{currency}" {selected_attr}>{currency}</option>"#).as_str());
+    }
+
+    let inflation_checked_attr = if datafile.investing.inflation_adjusted { "checked" } else { "" };
+
     return format!(
         r#"
         <div class="textContainer">
@@ -267,6 +400,61 @@ fn _build_comparison_bar_html(datafile: &DataFile) -> String
         {comparison_groups_html}
         <button id="addComparison" onclick="depotOverviewAddComparison()">+</button>
         <button id="removeComparison" onclick="depotOverviewRemoveComparison()">-</button>
+        <div class="textContainer">
+            <div>Währung:</div>
+        </div>
+        <select id="overviewCurrency" onchange="depotOverviewSetCurrency()">
+            {currency_options_html}
+        </select>
+        <div class="textContainer">
+            <label for="inflationAdjusted">Inflationsbereinigt:</label>
+        </div>
+        <input type="checkbox" id="inflationAdjusted" {inflation_checked_attr} onchange="depotOverviewSetInflationAdjusted()">
         "#
     );
 }
+
+/// Returns of the whole depot and of each variant from the oldest year until now, in the overview currency
+fn _build_performance_table_html(datafile: &DataFile) -> String
+{
+    let Some(oldest_year) = datafile.investing.depot.get_oldest_year() else {
+        return String::new();
+    };
+    let first = (oldest_year, 1);
+    let last = (CurrentDate::current_year(), CurrentDate::current_month());
+    // percentages with two decimal places, like money
+    let percent = |value: f64| -> String { format!("{} %", datafile.locale.format_money(Money::from_f64(value * 100.0))) };
+    let row = |name: &str, performance: Result<Performance, Error>| -> String {
+        return match performance {
+            Ok(p) => format!(
+                r#"<tr><td>{name}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>"#,
+                datafile.locale.format_money(p.gain()),
+                percent(p.time_weighted),
+                percent(p.annualized_time_weighted()),
+                p.xirr.map(percent).unwrap_or_else(|| String::from("-"))
+            ),
+            Err(e) => format!(r#"<tr><td>{name}</td><td colspan="4"><span class="error">{e}</span></td></tr>"#),
+        };
+    };
+
+    let mut rows = row("Depot", datafile.depot_performance(None, first, last));
+    for variant in InvestmentVariant::into_iter() {
+        if datafile.investing.depot.entries.values().any(|e| e.variant == variant) {
+            rows.push_str(&row(&variant.to_string(), datafile.depot_performance(Some(&variant), first, last)));
+        }
+    }
+
+    return format!(
+        r#"
+        <table class="depotOverview" id="performanceTable">
+            <tr><th>{}-{:0>2} - {}-{:0>2}</th><th>Gewinn ({})</th><th>TWR</th><th>TWR p.a.</th><th>XIRR p.a.</th></tr>
+            {rows}
+        </table>
+        "#,
+        first.0,
+        first.1,
+        last.0,
+        last.1,
+        datafile.overview_currency()
+    );
+}
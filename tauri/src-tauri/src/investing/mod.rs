@@ -1,2 +1,3 @@
 pub mod depot_entry_table;
 pub mod depot_overview;
+pub mod savings_plan;
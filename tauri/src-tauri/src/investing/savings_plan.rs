@@ -0,0 +1,192 @@
+use finanzbuch_lib::fast_date::FastDate;
+use finanzbuch_lib::investing::savings_plan_section::SavingsPlanSection;
+use finanzbuch_lib::investing::SavingsPlanInterval;
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::DepotEntry;
+use serde::Deserialize;
+use serde::Serialize;
+
+// keep this one imported for better linting support
+use crate::DATAFILE_GLOBAL;
+#[allow(unused_imports)]
+use finanzbuch_lib::datafile;
+
+/// One `SavingsPlanSection` as it is sent to and from JS
+/// - `start` and `end` like `2024-01-31`, which is the format of `<input type="date">`
+/// - `amount` in the locale of the data file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavingsPlanSectionData
+{
+    pub start: String,
+    pub end: String,
+    pub amount: String,
+    pub interval: SavingsPlanInterval,
+}
+
+#[tauri::command]
+/// All sections of the savings plan, sorted by date. Their index is used by the other commands
+pub fn savings_plan_get_sections(depot_entry_hash: String) -> Result<Vec<SavingsPlanSectionData>, String>
+{
+    return _with_depot_entry(depot_entry_hash, false, |depot_entry, locale| {
+        let sections = depot_entry
+            .savings_plan()
+            .iter()
+            .map(|s| SavingsPlanSectionData {
+                start: s.start.to_string(),
+                end: s.end.to_string(),
+                amount: locale.format_money(s.amount),
+                interval: s.interval.clone(),
+            })
+            .collect();
+        return Ok(sections);
+    });
+}
+
+#[tauri::command]
+/// Returns an error message if the section is not valid or overlaps with an existing one
+pub fn savings_plan_add_section(depot_entry_hash: String, section: SavingsPlanSectionData) -> Result<(), String>
+{
+    return _with_depot_entry(depot_entry_hash, true, |depot_entry, locale| {
+        let section = _parse_section(section, locale)?;
+        return depot_entry.add_savings_plan_section(section).map_err(|e| e.to_string());
+    });
+}
+
+#[tauri::command]
+/// Replaces the section at `index`. Nothing is changed if an error message is returned
+pub fn savings_plan_update_section(depot_entry_hash: String, index: usize, section: SavingsPlanSectionData) -> Result<(), String>
+{
+    return _with_depot_entry(depot_entry_hash, true, |depot_entry, locale| {
+        let section = _parse_section(section, locale)?;
+        return depot_entry.update_savings_plan_section(index, section).map_err(|e| e.to_string());
+    });
+}
+
+#[tauri::command]
+pub fn savings_plan_remove_section(depot_entry_hash: String, index: usize) -> Result<(), String>
+{
+    return _with_depot_entry(depot_entry_hash, true, |depot_entry, _| {
+        return depot_entry.remove_savings_plan_section(index).map(|_| ()).map_err(|e| e.to_string());
+    });
+}
+
+/// Editable rows for all sections of the savings plan, and an empty one to add a new section.
+/// Part of `depot_entry_get_table_html()`
+pub fn savings_plan_get_html(depot_entry: &DepotEntry, depot_entry_hash: &u64, locale: &Locale) -> String
+{
+    let currency_symbol = depot_entry.currency.symbol();
+    let mut all_trs = String::new();
+
+    for (index, section) in depot_entry.savings_plan().iter().enumerate() {
+        let start = section.start.to_string();
+        let end = section.end.to_string();
+        let amount = locale.format_money(section.amount);
+        let interval_options = _interval_options(&section.interval);
+        all_trs.push_str(
+            format!(
+                r#"
+                <tr>
+                    <td><input type="date" class="savingsPlanStart" value="{start}"></td>
+                    <td><input type="date" class="savingsPlanEnd" value="{end}"></td>
+                    <td><input type="text" class="savingsPlanAmount" value="{amount}"> {currency_symbol}</td>
+                    <td><select class="savingsPlanInterval">{interval_options}</select></td>
+                    <td>
+                        <button onclick="savingsPlanUpdateSection()" data-hash="{depot_entry_hash}" data-index="{index}">Speichern</button>
+                        <button class="savingsPlanDeleteBtn" ondblclick="savingsPlanRemoveSection()" data-hash="{depot_entry_hash}" data-index="{index}">Löschen</button>
+                    </td>
+                </tr>
+                "#
+            )
+            .as_str(),
+        );
+    }
+
+    let interval_options = _interval_options(&SavingsPlanInterval::Monthly);
+    return format!(
+        r#"
+        <div id="savingsPlanContainer">
+            <h2>Sparplan</h2>
+            <table>
+                <thead>
+                    <tr>
+                        <th>Beginn</th>
+                        <th>Ende</th>
+                        <th>Betrag</th>
+                        <th>Intervall</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {all_trs}
+                    <tr>
+                        <td><input type="date" class="savingsPlanStart"></td>
+                        <td><input type="date" class="savingsPlanEnd"></td>
+                        <td><input type="text" class="savingsPlanAmount"> {currency_symbol}</td>
+                        <td><select class="savingsPlanInterval">{interval_options}</select></td>
+                        <td><button onclick="savingsPlanAddSection()" data-hash="{depot_entry_hash}">Hinzufügen</button></td>
+                    </tr>
+                </tbody>
+            </table>
+            <div id="savingsPlanError" class="error"></div>
+        </div>
+        "#
+    );
+}
+
+// -------------------- private -------------------- //
+
+/// Runs `f` with the `DepotEntry` of `depot_entry_hash` and the locale of the data file.
+/// If `write` is set, the data file is written after `f` was successful
+fn _with_depot_entry<T>(depot_entry_hash: String, write: bool, f: impl FnOnce(&mut DepotEntry, Locale) -> Result<T, String>) -> Result<T, String>
+{
+    // JS does not support 64 bit Ints without using BigInt and BigInt cannot be serialized.
+    let Ok(depot_entry_hash) = depot_entry_hash.parse::<u64>() else {
+        return Err(format!("{depot_entry_hash} ist kein gültiger Depoteintrag"));
+    };
+
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return Err(String::from("Es ist kein Profil geöffnet"));
+    };
+    let locale = datafile.locale;
+    let Some(depot_entry) = datafile.investing.depot.entries.get_mut(&depot_entry_hash) else {
+        return Err(String::from("Der Depoteintrag existiert nicht mehr"));
+    };
+    let result = f(depot_entry, locale)?;
+
+    if write {
+        if let Err(e) = datafile.write() {
+            println!("Error writing data file: {e}");
+            return Err(e.to_string());
+        }
+    }
+    return Ok(result);
+}
+
+fn _parse_section(section: SavingsPlanSectionData, locale: Locale) -> Result<SavingsPlanSection, String>
+{
+    return Ok(SavingsPlanSection {
+        start: section.start.parse::<FastDate>().map_err(|e| e.to_string())?,
+        end: section.end.parse::<FastDate>().map_err(|e| e.to_string())?,
+        amount: locale.parse_money(&section.amount).map_err(|e| e.to_string())?,
+        interval: section.interval,
+    });
+}
+
+/// `<option>`'s of all intervals, with `selected` already selected
+fn _interval_options(selected: &SavingsPlanInterval) -> String
+{
+    let mut options = String::new();
+    for interval in [SavingsPlanInterval::Monthly, SavingsPlanInterval::Annually] {
+        let selected = match interval == *selected {
+            true => " selected",
+            false => "",
+        };
+        let label = match interval {
+            SavingsPlanInterval::Monthly => "Monatlich",
+            SavingsPlanInterval::Annually => "Jährlich",
+        };
+        options.push_str(format!(r#"<option value="{interval:?}"{selected}>{label}</option>"#).as_str());
+    }
+    return options;
+}
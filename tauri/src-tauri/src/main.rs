@@ -5,9 +5,12 @@
 extern crate lazy_static;
 
 mod investing;
+mod profiles;
 
 use crate::investing::depot_entry_table::*;
 use crate::investing::depot_overview::*;
+use crate::investing::savings_plan::*;
+use crate::profiles::*;
 use finanzbuch_lib::investing::inv_variant::InvestmentVariant;
 use finanzbuch_lib::DataFile;
 use finanzbuch_lib::DepotEntry;
@@ -15,19 +18,27 @@ use std::sync::Mutex;
 
 lazy_static! {
 
+    /// `None` if no profile is open
+    ///
     /// #### Entire data:
     /// ```Rust
-    /// let mut datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    /// let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    /// let Some(datafile) = datafile_guard.as_mut() else {
+    ///     return false;
+    /// };
     /// ```
     ///
     /// #### Read access to one thing (clone() is required)
     /// ```Rust
     /// let depot = {
-    ///     let datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
-    ///     datafile.investing.depot.clone()
+    ///     let datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    ///     match datafile_guard.as_ref() {
+    ///         Some(datafile) => datafile.investing.depot.clone(),
+    ///         None => return String::new(),
+    ///     }
     /// };
     /// ```
-    pub static ref DATAFILE_GLOBAL: Mutex<DataFile> = Mutex::new(DataFile::read());
+    pub static ref DATAFILE_GLOBAL: Mutex<Option<DataFile>> = Mutex::new(open_initial_datafile());
 }
 
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
@@ -45,8 +56,20 @@ fn main()
             depot_overview_change_comparison,
             depot_overview_do_comparison_action,
             depot_overview_get_html,
+            depot_overview_set_currency,
+            depot_overview_set_inflation_adjusted,
             get_depot_entry_list_html,
             get_html_depot_entry_add_form,
+            profile_close,
+            profile_create,
+            profile_get_name,
+            profile_set_locale,
+            profile_switch,
+            profiles_get_html,
+            savings_plan_add_section,
+            savings_plan_get_sections,
+            savings_plan_remove_section,
+            savings_plan_update_section,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -59,8 +82,11 @@ fn get_depot_entry_list_html() -> String
 {
     let mut all_buttons: String = String::new();
     let depot = {
-        let datafile = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
-        datafile.investing.depot.clone()
+        let datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+        match datafile_guard.as_ref() {
+            Some(datafile) => datafile.investing.depot.clone(),
+            None => return all_buttons, // no profile open, so there is nothing to add to
+        }
     };
 
     let mut sorted_depot: Vec<(&u64, &DepotEntry)> = depot.entries.iter().collect();
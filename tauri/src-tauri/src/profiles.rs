@@ -0,0 +1,299 @@
+use finanzbuch_lib::locale::Locale;
+use finanzbuch_lib::migration::MigrationReport;
+use finanzbuch_lib::profiles::AppConfig;
+use finanzbuch_lib::profiles::Selection;
+use finanzbuch_lib::DataFile;
+use finanzbuch_lib::Money;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::DATAFILE_GLOBAL;
+
+// Commands to list, create, switch and close profiles. A profile is a named data file, listed in the app config
+
+lazy_static! {
+    /// What was changed when the open data file was migrated from an older version.
+    /// Shown on the "Profiles" page, `None` if nothing was migrated
+    static ref MIGRATION_REPORT_GLOBAL: Mutex<Option<MigrationReport>> = Mutex::new(None);
+}
+
+/// Opens the data file chosen with `--file` / `--profile`, `FINANZBUCH_FILE` / `FINANZBUCH_PROFILE` or the last used profile.
+///
+/// Returns `None` if this fails, the user can then choose another profile.
+/// The file is not touched in this case, so the user can update the program and try again.
+pub fn open_initial_datafile() -> Option<DataFile>
+{
+    let selection = Selection::from_args(std::env::args().skip(1)).or_else(Selection::from_env);
+
+    let location = match AppConfig::read().and_then(|config| config.resolve(selection)) {
+        Ok(location) => location,
+        Err(e) => {
+            println!("Error choosing the data file: {e}");
+            return None;
+        }
+    };
+
+    return match DataFile::read_from_custom_path_with_report(location.path) {
+        Ok((datafile, report)) => {
+            _set_migration_report(report);
+            Some(datafile)
+        }
+        Err(e) => {
+            println!("Error reading data file: {e}");
+            None
+        }
+    };
+}
+
+#[tauri::command]
+/// Name of the open profile, for the navBar
+pub fn profile_get_name() -> String
+{
+    let datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_ref() else {
+        return String::from("Kein Profil geöffnet");
+    };
+    let Some(filepath) = &datafile.filepath else {
+        return String::from("Standard");
+    };
+
+    let config = AppConfig::read().unwrap_or_default();
+    return match config.profile_by_path(filepath) {
+        Some(profile) => profile.name.clone(),
+        None => filepath.to_string_lossy().to_string(),
+    };
+}
+
+#[tauri::command]
+/// Get the html for the entire "Profiles" page
+pub fn profiles_get_html() -> String
+{
+    let config = match AppConfig::read() {
+        Ok(config) => config,
+        Err(e) => return format!(r#"<div class="error">{}</div>"#, _escape_html(&e.to_string())),
+    };
+    let (open_path, open_locale): (Option<PathBuf>, Option<Locale>) = {
+        let datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+        match datafile_guard.as_ref() {
+            Some(datafile) => (datafile.filepath.clone(), Some(datafile.locale)),
+            None => (None, None),
+        }
+    };
+
+    let mut rows: String = String::new();
+    for profile in config.profiles.iter() {
+        // names and paths are chosen by the user, they must not be able to add any html
+        let name = _escape_html(&profile.name);
+        let path = _escape_html(&profile.path.to_string_lossy());
+        let button = match open_path.as_ref() == Some(&profile.path) {
+            true => String::from(r#"<button disabled>Öffnen</button>"#),
+            false => format!(r#"<button data-name="{name}" onclick="profilesSwitch(event)">Öffnen</button>"#),
+        };
+
+        rows.push_str(
+            format!(
+                r#"
+                <tr>
+                    <td>{name}</td>
+                    <td>{path}</td>
+                    <td>{button}</td>
+                </tr>
+                "#
+            )
+            .as_str(),
+        );
+    }
+
+    // the locale is stored in the data file, so it can only be changed while a profile is open
+    let locale_select = match open_locale {
+        Some(open_locale) => {
+            let mut options = String::new();
+            for locale in Locale::ALL {
+                let selected = if locale == open_locale { " selected" } else { "" };
+                let example = locale.format_money(Money::from_cents(123456789));
+                options.push_str(&format!(r#"<option value="{locale}"{selected}>{locale} ({example})</option>"#));
+            }
+            format!(
+                r#"<div class="profilesElement">
+                    <label>Zahlenformat:</label>
+                    <select id="profilesLocale" onchange="profilesSetLocale(event)">{options}</select>
+                </div>"#
+            )
+        }
+        None => String::new(),
+    };
+
+    let migration_report = match MIGRATION_REPORT_GLOBAL
+        .lock()
+        .expect("MIGRATION_REPORT_GLOBAL Mutex was poisoned")
+        .as_ref()
+    {
+        Some(report) => format!(r#"<pre id="profilesMigrationReport">{}</pre>"#, _escape_html(&report.to_string())),
+        None => String::new(),
+    };
+
+    return format!(
+        r#"
+        <div id="profilesContainer">
+            <h2>Profile</h2>
+            {migration_report}
+            <table>
+                {rows}
+            </table>
+            <div class="profilesElement">
+                <label>Passwort:</label>
+                <input type="password" id="profilesPassphrase" placeholder="nur für verschlüsselte Dateien">
+            </div>
+            {locale_select}
+            <button id="profilesCloseBtn" onclick="profilesClose()">Aktuelles Profil schließen</button>
+
+            <h2>Neues Profil</h2>
+            <form id="profilesAddContainer" onsubmit="profilesAddFormSubmit(event)">
+                <div class="profilesElement">
+                    <label>Name:</label>
+                    <input type="text" id="profilesAdd-Name">
+                </div>
+                <div class="profilesElement">
+                    <label>Datei:</label>
+                    <input type="text" id="profilesAdd-Path">
+                </div>
+                <button type="submit" id="profilesAddFormDoneBtn">Hinzufügen</button>
+            </form>
+        </div>
+        "#
+    );
+}
+
+#[tauri::command]
+/// Adds a profile to the app config. Its data file is created once something is changed in it
+pub fn profile_create(name: String, path: String) -> bool
+{
+    let mut config = match AppConfig::read() {
+        Ok(config) => config,
+        Err(e) => {
+            println!("Error reading app config: {e}");
+            return false;
+        }
+    };
+
+    if let Err(e) = config.add_profile(&name, PathBuf::from(path)) {
+        println!("Error adding profile: {e}");
+        return false;
+    }
+    if let Err(e) = config.write() {
+        println!("Error writing app config: {e}");
+        return false;
+    }
+    return true;
+}
+
+#[tauri::command]
+/// Closes the open profile and opens the one with this name instead.
+///
+/// `passphrase` is only used if the data file is encrypted, it can be empty otherwise.
+/// If the new profile can not be opened, the old one stays open.
+pub fn profile_switch(name: String, passphrase: String) -> bool
+{
+    let mut config = match AppConfig::read() {
+        Ok(config) => config,
+        Err(e) => {
+            println!("Error reading app config: {e}");
+            return false;
+        }
+    };
+    let Some(profile) = config.profile(&name) else {
+        println!("There is no profile called {name}");
+        return false;
+    };
+
+    let read_result = match passphrase.is_empty() {
+        true => DataFile::read_from_custom_path_with_report(profile.path.clone()),
+        false => DataFile::read_encrypted_with_report(profile.path.clone(), &passphrase),
+    };
+    let new_datafile = match read_result {
+        Ok((datafile, report)) => {
+            _set_migration_report(report);
+            datafile
+        }
+        Err(e) => {
+            println!("Error reading data file: {e}");
+            return false;
+        }
+    };
+
+    {
+        let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+        // the old DataFile is written when it is dropped here
+        *datafile_guard = Some(new_datafile);
+    }
+
+    config.last_profile = Some(name);
+    if let Err(e) = config.write() {
+        println!("Error writing app config: {e}");
+    }
+    return true;
+}
+
+#[tauri::command]
+/// Changes how numbers are entered and shown in the open profile. Returns `false` if the code is not valid
+pub fn profile_set_locale(code: String) -> bool
+{
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(datafile) = datafile_guard.as_mut() else {
+        return false;
+    };
+    let Ok(locale) = code.parse::<Locale>() else {
+        return false;
+    };
+
+    datafile.locale = locale;
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+        return false;
+    }
+    return true;
+}
+
+#[tauri::command]
+/// Writes and closes the open profile, no data can be seen until another one is opened
+pub fn profile_close() -> bool
+{
+    let mut datafile_guard = DATAFILE_GLOBAL.lock().expect("DATAFILE_GLOBAL Mutex was poisoned");
+    let Some(mut datafile) = datafile_guard.take() else {
+        return false;
+    };
+
+    if let Err(e) = datafile.write() {
+        println!("Error writing data file: {e}");
+        *datafile_guard = Some(datafile); // keep it open, so nothing is lost
+        return false;
+    }
+    datafile.write_on_drop = false; // was just written
+    return true;
+}
+
+// -------------------- private -------------------- //
+
+/// Keeps the report of the profile that was just opened, older reports belong to another profile
+fn _set_migration_report(report: MigrationReport)
+{
+    let mut report_guard = MIGRATION_REPORT_GLOBAL.lock().expect("MIGRATION_REPORT_GLOBAL Mutex was poisoned");
+    *report_guard = (!report.is_empty()).then_some(report);
+}
+
+/// So that text can be shown as it is, in elements as well as in attributes
+fn _escape_html(text: &str) -> String
+{
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    return escaped;
+}
@@ -38,14 +38,22 @@ async function depotEntryTableSetCell() {
             break;
     }
 
-    // TODO check for return value and highlight cell
-    invoke("depot_entry_set_cell_value", {
-        depotEntryHash: data.hash,
-        field: field,
-        value: this.event.target.textContent,
-        year: parseInt(data.year),
-        month: parseInt(data.month)
-    });
+    // the cell stays marked until it contains a valid number
+    let cell = this.event.target;
+    try {
+        await invoke("depot_entry_set_cell_value", {
+            depotEntryHash: data.hash,
+            field: field,
+            value: cell.textContent,
+            year: parseInt(data.year),
+            month: parseInt(data.month)
+        });
+        cell.classList.remove('error');
+        cell.removeAttribute('title');
+    } catch (error) {
+        cell.classList.add('error');
+        cell.title = error;
+    }
 }
 
 /// add new year + reload table html
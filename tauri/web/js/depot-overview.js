@@ -11,6 +11,18 @@ async function depotOverviewAddComparison() {
     depotOverviewInitialize();
 }
 
+async function depotOverviewSetCurrency() {
+    let code = document.getElementById("overviewCurrency").value;
+    await invoke("depot_overview_set_currency", { code: code });
+    depotOverviewInitialize();
+}
+
+async function depotOverviewSetInflationAdjusted() {
+    let enabled = document.getElementById("inflationAdjusted").checked;
+    await invoke("depot_overview_set_inflation_adjusted", { enabled: enabled });
+    depotOverviewInitialize();
+}
+
 async function depotOverviewInitialize() {
 
     // replace page content
@@ -48,6 +60,15 @@ async function depotOverviewInitialize() {
         order: 1,
         hidden: true,
     };
+    let withoutCostsDataConfig = {
+        borderColor: "hsla(160, 60%, 45%, 0.8)",
+        backgroundColor: "hsla(160, 60%, 45%, 0.2)",
+        borderDash: [6, 4],
+        fill: false,
+        pointStyle: false,
+        order: 3,
+        hidden: true,
+    };
     let prognosisDataConfig = {
         borderColor: "hsla(30, 0%, 40%, 0.5)",
         backgroundColor: "hsla(30, 0%, 40%, 0.25)",	// they arent filled, but bg color is used in legend two
@@ -60,9 +81,10 @@ async function depotOverviewInitialize() {
     // join datasets and their additional config
     datasets[0] = { ...datasets[0], ...depotDataConfig };
     datasets[1] = { ...datasets[1], ...transactionDataConfig };
+    datasets[2] = { ...datasets[2], ...withoutCostsDataConfig };
     datasets.forEach(function (el, index, array) {
         // for all prognosis configs
-        if (index >= 2) { array[index] = { ...array[index], ...prognosisDataConfig }; }
+        if (index >= 3) { array[index] = { ...array[index], ...prognosisDataConfig }; }
     });
 
     console.log(datasets);
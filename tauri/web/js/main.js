@@ -10,7 +10,7 @@ const { invoke } = window.__TAURI__.tauri;
 function sleep(ms) { return new Promise(resolve => setTimeout(resolve, ms)); }
 
 // -------------------- Init / Navbar -------------------- //
-window.onload = () => { navBarGetDepotEntryListHtml(); }
+window.onload = () => { navBarGetDepotEntryListHtml(); navBarGetProfileName(); }
 
 /// Will load the html to show a button in the navbar for each DepotEntry
 async function navBarGetDepotEntryListHtml() {
@@ -18,6 +18,12 @@ async function navBarGetDepotEntryListHtml() {
 	document.getElementById("depotEntryList").innerHTML = html;
 }
 
+/// Shows the name of the open profile in the navbar
+async function navBarGetProfileName() {
+	var name = await invoke("profile_get_name");
+	document.getElementById("navProfileBtn").innerText = name;
+}
+
 /// EventHandler for the button that shows a form to add one DepotEntry
 async function navBarLoadHtmlAddDepotEntry() {
 	var html = await invoke("get_html_depot_entry_add_form");
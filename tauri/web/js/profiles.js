@@ -0,0 +1,68 @@
+/// Only works in async functions, simply waits some time
+function sleep(ms) { return new Promise(resolve => setTimeout(resolve, ms)); }
+
+async function profilesInitialize() {
+	var html = await invoke("profiles_get_html");
+	document.getElementById("content").innerHTML = html;
+}
+
+/// Everything shown belongs to the previous profile, so reload navbar and page
+async function profilesReloadAll() {
+	navBarGetProfileName();
+	navBarGetDepotEntryListHtml();
+	profilesInitialize();
+}
+
+/// EventHandler for the "Öffnen" button of one profile
+async function profilesSwitch(event) {
+	var name = event.target.dataset.name;
+	var passphrase = document.getElementById("profilesPassphrase").value;
+	var sucessful = await invoke("profile_switch", { name: name, passphrase: passphrase });
+
+	if (sucessful) {
+		profilesReloadAll();
+	} else {
+		console.warn("profilesSwitch failed");
+		profilesShowError(event.target, "Konnte nicht geöffnet werden");
+	}
+}
+
+/// EventHandler for the select of the number format
+async function profilesSetLocale(event) {
+	var sucessful = await invoke("profile_set_locale", { code: event.target.value });
+	if (!sucessful) {
+		console.warn("profilesSetLocale failed");
+		profilesInitialize();	// show the locale that is actually used
+	}
+}
+
+async function profilesClose() {
+	await invoke("profile_close");
+	profilesReloadAll();
+}
+
+/// EventHandler for the submit button of the form where a user can add a profile
+async function profilesAddFormSubmit(event) {
+	event.preventDefault();
+
+	var name = document.getElementById("profilesAdd-Name").value;
+	var path = document.getElementById("profilesAdd-Path").value;
+	var sucessful = await invoke("profile_create", { name: name, path: path });
+
+	if (sucessful) {
+		profilesInitialize();
+	} else {
+		console.warn("profilesAddFormSubmit failed");
+		profilesShowError(document.getElementById("profilesAddFormDoneBtn"), "Profil konnte nicht hinzugefügt werden");
+	}
+}
+
+/// Shows the error in the button for 3 seconds
+async function profilesShowError(buttonElement, text) {
+	var innerTextBefore = buttonElement.innerHTML;
+	buttonElement.innerHTML = text;
+	buttonElement.classList.add('error');
+	await sleep(3000);
+	buttonElement.innerHTML = innerTextBefore;	// Reset text
+	buttonElement.classList.remove('error');
+}
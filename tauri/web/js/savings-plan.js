@@ -0,0 +1,45 @@
+/// reads the inputs of the row of the clicked button
+function savingsPlanReadRow(buttonElement) {
+    let row = buttonElement.closest("tr");
+    return {
+        start: row.querySelector(".savingsPlanStart").value,
+        end: row.querySelector(".savingsPlanEnd").value,
+        amount: row.querySelector(".savingsPlanAmount").value,
+        interval: row.querySelector(".savingsPlanInterval").value
+    };
+}
+
+/// the planned transactions of the table change as well, so the entire depot entry is reloaded
+async function savingsPlanInvoke(command, args) {
+    try {
+        await invoke(command, args);
+        depotEntryTableReloadHtml(args.depotEntryHash);
+    } catch (error) {
+        document.getElementById("savingsPlanError").innerText = error;
+    }
+}
+
+async function savingsPlanAddSection() {
+    let buttonElement = this.event.target;
+    await savingsPlanInvoke("savings_plan_add_section", {
+        depotEntryHash: buttonElement.dataset.hash,
+        section: savingsPlanReadRow(buttonElement)
+    });
+}
+
+async function savingsPlanUpdateSection() {
+    let buttonElement = this.event.target;
+    await savingsPlanInvoke("savings_plan_update_section", {
+        depotEntryHash: buttonElement.dataset.hash,
+        index: parseInt(buttonElement.dataset.index),
+        section: savingsPlanReadRow(buttonElement)
+    });
+}
+
+async function savingsPlanRemoveSection() {
+    let buttonElement = this.event.target;
+    await savingsPlanInvoke("savings_plan_remove_section", {
+        depotEntryHash: buttonElement.dataset.hash,
+        index: parseInt(buttonElement.dataset.index)
+    });
+}